@@ -14,6 +14,9 @@ use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::RateLimitConfig;
+use holochain_conductor_api::SignalEncodeFailurePolicy;
+use holochain_conductor_api::SignalOverflowPolicy;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p;
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
@@ -93,6 +96,46 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    /// Optional address to listen on, IPv4 or IPv6.
+    /// Defaults to the IPv4 loopback address.
+    #[structopt(long)]
+    pub bind_address: Option<std::net::IpAddr>,
+    /// Optional window in milliseconds over which to
+    /// coalesce signals sent on this interface.
+    #[structopt(long)]
+    pub signal_coalesce_window_ms: Option<u64>,
+    /// Send signals on this interface without waiting
+    /// unless the connection's buffer is full.
+    #[structopt(long)]
+    pub eager_signals: bool,
+    /// Limit each connection to this interface
+    /// to this many requests per second.
+    #[structopt(long)]
+    pub rate_limit_per_second: Option<u32>,
+    /// How many requests a connection can send at once
+    /// when rate limited. Defaults to the rate.
+    #[structopt(long, requires = "rate-limit-per-second")]
+    pub rate_limit_burst: Option<u32>,
+    /// Close connections to this interface which send
+    /// no messages for this many milliseconds.
+    #[structopt(long)]
+    pub idle_timeout_ms: Option<u64>,
+    /// Ask clients closed by shutdown to wait this many
+    /// milliseconds before reconnecting.
+    #[structopt(long)]
+    pub reconnect_backoff_ms: Option<u64>,
+    /// Make cells wait for connections to this interface which fall
+    /// behind on signals, instead of dropping their oldest signals.
+    /// A client which stops reading signals then stalls the cells.
+    #[structopt(long)]
+    pub block_on_signal_overflow: bool,
+    /// Drop signals which can't be serialized instead of
+    /// sending a notice in their place.
+    #[structopt(long)]
+    pub skip_unencodable_signals: bool,
+    /// Only carry signals from the cells of this app on this interface.
+    #[structopt(long)]
+    pub installed_app_id: Option<String>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -365,7 +408,16 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::Websocket {
+                    port,
+                    bind_address: None,
+                },
+                allowed_requests: None,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                coalesce_requests: false,
+                max_connection_lifetime_ms: None,
             },
         ]))
         .await?;
@@ -531,6 +583,7 @@ pub async fn activate_app(cmd: &mut CmdRunner, args: ActivateApp) -> anyhow::Res
     let resp = cmd
         .command(AdminRequest::ActivateApp {
             installed_app_id: args.app_id,
+            dry_run: false,
         })
         .await?;
     ensure!(
@@ -559,11 +612,35 @@ pub async fn deactivate_app(cmd: &mut CmdRunner, args: DeactivateApp) -> anyhow:
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            bind_address: args.bind_address,
+            signal_coalesce_window_ms: args.signal_coalesce_window_ms,
+            eager_signals: args.eager_signals,
+            rate_limit: args
+                .rate_limit_per_second
+                .map(|requests_per_second| RateLimitConfig {
+                    requests_per_second,
+                    burst: args.rate_limit_burst.unwrap_or(requests_per_second),
+                }),
+            idle_timeout_ms: args.idle_timeout_ms,
+            reconnect_backoff_ms: args.reconnect_backoff_ms,
+            signal_overflow: if args.block_on_signal_overflow {
+                SignalOverflowPolicy::Block
+            } else {
+                SignalOverflowPolicy::DropOldest
+            },
+            signal_encode_failure: if args.skip_unencodable_signals {
+                SignalEncodeFailurePolicy::Skip
+            } else {
+                SignalEncodeFailurePolicy::Notify
+            },
+            installed_app_id: args.installed_app_id,
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
-        AdminResponse::AppInterfaceAttached { port } => Ok(port),
+        AdminResponse::AppInterfaceAttached { port, .. } => Ok(port),
         _ => Err(anyhow!(
             "Failed to attach app interface {:?}, got: {:?}",
             args.port,
@@ -39,6 +39,16 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                bind_address: None,
+                signal_coalesce_window_ms: None,
+                eager_signals: false,
+                rate_limit_per_second: None,
+                rate_limit_burst: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                block_on_signal_overflow: false,
+                skip_unencodable_signals: false,
+                installed_app_id: None,
             },
         )
         .await?;
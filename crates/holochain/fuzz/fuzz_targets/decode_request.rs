@@ -0,0 +1,26 @@
+//! Feeds arbitrary frames through every way the conductor decodes
+//! requests from its admin and app interfaces.
+//! Decoding must never panic: a frame which doesn't decode is answered
+//! with an `ExternalApiWireError::Deserialization`.
+//!
+//! Run with `cargo +nightly fuzz run decode_request` from this directory.
+#![no_main]
+
+use holochain::conductor::api::AdminRequest;
+use holochain::conductor::api::AppRequest;
+use holochain::conductor::interface::codec::JsonCodec;
+use holochain::conductor::interface::codec::MsgPackCodec;
+use holochain::conductor::interface::codec::WireCodec;
+use holochain_serialized_bytes::prelude::*;
+use libfuzzer_sys::fuzz_target;
+use std::convert::TryFrom;
+
+fuzz_target!(|frame: &[u8]| {
+    let bytes = || SerializedBytes::from(UnsafeBytes::from(frame.to_vec()));
+    let _ = MsgPackCodec::decode::<AdminRequest>(bytes());
+    let _ = MsgPackCodec::decode::<AppRequest>(bytes());
+    let _ = JsonCodec::decode::<AdminRequest>(bytes());
+    let _ = JsonCodec::decode::<AppRequest>(bytes());
+    let _ = AdminRequest::try_from(bytes());
+    let _ = AppRequest::try_from(bytes());
+});
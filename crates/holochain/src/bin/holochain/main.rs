@@ -15,6 +15,10 @@ use tracing::*;
 
 const ERROR_CODE: i32 = 42;
 const MAGIC_CONDUCTOR_READY_STRING: &str = "Conductor ready.";
+/// How long interfaces are given to drain on SIGTERM
+/// before the rest of the conductor is stopped
+#[cfg(unix)]
+const SIGTERM_DRAIN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "holochain", about = "The Holochain Conductor.")]
@@ -80,6 +84,10 @@ async fn async_main() {
     #[cfg(unix)]
     let _ = notify(true, &[NotifyState::Ready]);
 
+    // Hand clients over cleanly rather than dropping them on SIGTERM
+    #[cfg(unix)]
+    tokio::task::spawn(drain_then_stop_on_sigterm(conductor.clone()));
+
     // Await on the main JoinHandle, keeping the process alive until all
     // Conductor activity has ceased
     let result = conductor
@@ -206,3 +214,24 @@ a valid default configuration. Details:
         config_path, error
     )
 }
+
+/// On SIGTERM, drain the conductor's interfaces so clients are handed over
+/// cleanly, then stop the rest of the conductor once they have had a chance
+/// to finish.
+#[cfg(unix)]
+async fn drain_then_stop_on_sigterm(conductor: ConductorHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut sigterm = match signal(SignalKind::terminate()) {
+        Ok(sigterm) => sigterm,
+        Err(e) => {
+            warn!(?e, "Couldn't listen for SIGTERM");
+            return;
+        }
+    };
+    sigterm.recv().await;
+    info!("Received SIGTERM, draining interfaces");
+    conductor.drain_interfaces().await;
+    tokio::time::sleep(SIGTERM_DRAIN_GRACE).await;
+    info!("Stopping the conductor");
+    conductor.shutdown().await;
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use super::error::ConductorApiError;
 use super::error::ConductorApiResult;
+use crate::conductor::interface::AppSignalCounter;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::workflow::call_zome_workflow::call_zome_workspace_lock::CallZomeWorkspaceLock;
@@ -21,6 +22,9 @@ use tracing::*;
 pub struct CellConductorApi {
     conductor_handle: ConductorHandle,
     cell_id: CellId,
+    /// numbers the app signals of the cell, across all its broadcasters.
+    /// each new API starts a new numbering, see [`AppSignalSeq`]
+    app_signal_seq: AppSignalCounter,
 }
 
 /// A handle that cn only call zome functions to avoid
@@ -34,6 +38,7 @@ impl CellConductorApi {
         Self {
             conductor_handle,
             cell_id,
+            app_signal_seq: AppSignalCounter::default(),
         }
     }
 }
@@ -72,7 +77,10 @@ impl CellConductorApiT for CellConductorApi {
     }
 
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
-        self.conductor_handle.signal_broadcaster().await
+        self.conductor_handle
+            .signal_broadcaster_for_cell(&self.cell_id)
+            .await
+            .with_app_signal_seq(self.app_signal_seq.clone())
     }
 
     async fn get_dna(&self, dna_hash: &DnaHash) -> Option<DnaFile> {
@@ -126,8 +134,8 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
     /// Request access to this conductor's keystore
     fn keystore(&self) -> &KeystoreSender;
 
-    /// Access the broadcast Sender which will send a Signal across every
-    /// attached app interface
+    /// Access the broadcast Sender which will send this cell's Signals
+    /// across every attached app interface which carries them
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
 
     /// Get a [Dna] from the [DnaStore]
@@ -7,14 +7,22 @@ use crate::conductor::api::error::SerializationError;
 use crate::conductor::error::CreateAppError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::InterfaceDriver;
+use crate::conductor::state::AppInterfaceConfig;
 use crate::conductor::ConductorHandle;
+use holochain_conductor_api::signal_subscription::SignalKind;
 use holochain_keystore::KeystoreSenderExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::dna::DnaBundle;
 use holochain_types::prelude::*;
+use mr_bundle::error::MrBundleError;
 use mr_bundle::Bundle;
 
 use holochain_zome_types::cell::CellId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use tracing::*;
 
@@ -56,6 +64,28 @@ impl RealAdminInterfaceApi {
     pub(crate) fn new(conductor_handle: ConductorHandle) -> Self {
         RealAdminInterfaceApi { conductor_handle }
     }
+
+    /// Store the Dna unless it has already been registered,
+    /// returning its hash either way
+    async fn register_dna_if_missing(&self, dna: DnaFile) -> ConductorApiResult<DnaHash> {
+        let hash = dna.dna_hash().clone();
+        let dna_list = self.conductor_handle.list_dnas().await?;
+        if !dna_list.contains(&hash) {
+            self.conductor_handle.register_dna(dna).await?;
+        }
+        Ok(hash)
+    }
+
+    /// The response to [AdminRequest::Ping]
+    fn pong(&self) -> AdminResponse {
+        let counters = self.conductor_handle.interface_counters();
+        AdminResponse::Pong {
+            uptime_ms: self.conductor_handle.uptime().as_millis() as u64,
+            admin_listeners_died: counters.admin_listener_died(),
+            app_signal_encode_failures: counters.signal_encode_failed(SignalKind::App),
+            system_signal_encode_failures: counters.signal_encode_failed(SignalKind::System),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -107,10 +137,14 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                         dna
                     }
                     DnaSource::Path(ref path) => {
-                        let bundle = Bundle::read_from_file(path).await?;
-                        let bundle: DnaBundle = bundle.into();
-                        let (dna_file, _original_hash) =
-                            bundle.into_dna_file(uid, properties).await?;
+                        let bundle = read_dna_bundle(path).await?;
+                        let (dna_file, _original_hash) = bundle
+                            .into_dna_file(uid, properties)
+                            .await
+                            .map_err(|e| ConductorApiError::DnaParseError {
+                                path: path.clone(),
+                                reason: e.to_string(),
+                            })?;
                         dna_file
                     }
                     DnaSource::Bundle(bundle) => {
@@ -120,11 +154,14 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     }
                 };
 
-                let hash = dna.dna_hash().clone();
-                let dna_list = self.conductor_handle.list_dnas().await?;
-                if !dna_list.contains(&hash) {
-                    self.conductor_handle.register_dna(dna).await?;
-                }
+                let hash = self.register_dna_if_missing(dna).await?;
+                Ok(AdminResponse::DnaRegistered(hash))
+            }
+            InstallDnaFromBytes { dna, properties } => {
+                trace!(dna_byte_count = dna.bytes().len(), ?properties);
+                let bundle = DnaBundle::decode(dna.bytes())?;
+                let (dna_file, _original_hash) = bundle.into_dna_file(None, properties).await?;
+                let hash = self.register_dna_if_missing(dna_file).await?;
                 Ok(AdminResponse::DnaRegistered(hash))
             }
             CreateCloneCell(payload) => {
@@ -201,6 +238,19 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dna_list = self.conductor_handle.list_dnas().await?;
                 Ok(AdminResponse::DnasListed(dna_list))
             }
+            ListInstalledDnas => {
+                let dnas = self
+                    .conductor_handle
+                    .list_dna_zome_names()
+                    .await?
+                    .into_iter()
+                    .map(|(dna_hash, zome_names)| InstalledDnaInfo {
+                        dna_hash,
+                        zome_names,
+                    })
+                    .collect();
+                Ok(AdminResponse::InstalledDnasListed(dnas))
+            }
             GenerateAgentPubKey => {
                 let agent_pub_key = self
                     .conductor_handle
@@ -218,7 +268,20 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let app_ids = self.conductor_handle.list_active_apps().await?;
                 Ok(AdminResponse::ActiveAppsListed(app_ids))
             }
-            ActivateApp { installed_app_id } => {
+            ActivateApp {
+                installed_app_id,
+                dry_run: true,
+            } => {
+                // Check the app could be activated without changing anything
+                self.conductor_handle
+                    .validate_app_activation(&installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppsValidated)
+            }
+            ActivateApp {
+                installed_app_id,
+                dry_run: false,
+            } => {
                 // Activate app
                 self.conductor_handle
                     .activate_app(installed_app_id.clone())
@@ -243,20 +306,44 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .unwrap_or(Ok(AdminResponse::AppActivated))
             }
             DeactivateApp { installed_app_id } => {
-                // Activate app
+                // Deactivate app, erroring if it isn't currently active
                 self.conductor_handle
                     .deactivate_app(installed_app_id.clone(), DeactivationReason::Normal)
                     .await?;
                 Ok(AdminResponse::AppDeactivated)
             }
-            AttachAppInterface { port } => {
+            AttachAppInterface {
+                port,
+                bind_address,
+                signal_coalesce_window_ms,
+                eager_signals,
+                rate_limit,
+                idle_timeout_ms,
+                reconnect_backoff_ms,
+                signal_overflow,
+                signal_encode_failure,
+                installed_app_id,
+            } => {
                 let port = port.unwrap_or(0);
+                let config = AppInterfaceConfig {
+                    driver: InterfaceDriver::Websocket { port, bind_address },
+                    signal_coalesce_window_ms,
+                    eager_signals,
+                    rate_limit,
+                    idle_timeout_ms,
+                    reconnect_backoff_ms,
+                    signal_overflow,
+                    signal_encode_failure,
+                    installed_app_id,
+                    ..AppInterfaceConfig::websocket(port)
+                };
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(config)
                     .await?;
-                Ok(AdminResponse::AppInterfaceAttached { port })
+                let active_cells = self.conductor_handle.active_cell_op_counts().await?;
+                Ok(AdminResponse::AppInterfaceAttached { port, active_cells })
             }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
@@ -266,6 +353,60 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::StateDumped(state))
             }
+            DumpStateRange {
+                cell_id,
+                start,
+                limit,
+            } => {
+                let state = self
+                    .conductor_handle
+                    .dump_cell_state_range(&cell_id, start, limit)
+                    .await?;
+                Ok(AdminResponse::StateRangeDumped(state))
+            }
+            AwaitIntegration {
+                cell_id,
+                timeout_ms,
+            } => {
+                let state = self
+                    .conductor_handle
+                    .await_cell_integration(&cell_id, std::time::Duration::from_millis(timeout_ms))
+                    .await?;
+                Ok(AdminResponse::IntegrationAwaited(state))
+            }
+            GossipStatus { dna_hash } => {
+                let status = self.conductor_handle.gossip_status(&dna_hash).await?;
+                Ok(AdminResponse::GossipStatusReported(status))
+            }
+            PauseGossip { dna_hash } => {
+                self.conductor_handle
+                    .set_gossip_paused(&dna_hash, true)
+                    .await?;
+                Ok(AdminResponse::GossipPaused)
+            }
+            ResumeGossip { dna_hash } => {
+                self.conductor_handle
+                    .set_gossip_paused(&dna_hash, false)
+                    .await?;
+                Ok(AdminResponse::GossipResumed)
+            }
+            SetAgentArc { agent_key, arc } => {
+                let previous_arcs = self.conductor_handle.set_agent_arc(&agent_key, arc).await?;
+                Ok(AdminResponse::AgentArcSet(previous_arcs))
+            }
+            Ping => Ok(self.pong()),
+            Info => Ok(AdminResponse::Info {
+                conductor_version: env!("CARGO_PKG_VERSION").to_string(),
+                wire_version: ADMIN_SUBPROTOCOL_V1.to_string(),
+                supported_requests: AdminRequest::SUPPORTED_REQUESTS
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect(),
+            }),
+            DumpConductorState => {
+                let state = self.conductor_handle.dump_conductor_state().await?;
+                Ok(AdminResponse::ConductorStateDumped(state))
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -274,6 +415,85 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let r = self.conductor_handle.get_agent_infos(cell_id).await?;
                 Ok(AdminResponse::AgentInfoRequested(r))
             }
+            RecentErrors => Ok(AdminResponse::RecentErrorsListed(
+                self.conductor_handle.recent_interface_errors().list(),
+            )),
+            GetOpData { dna_hash, op_hash } => {
+                let op = self
+                    .conductor_handle
+                    .get_op_data(&dna_hash, op_hash)
+                    .await?;
+                Ok(AdminResponse::OpDataFetched(op))
+            }
+            Idempotent { key, request } => {
+                let fingerprint = holo_hash::encode::blake2b_256(
+                    &holochain_serialized_bytes::encode(&request)
+                        .map_err(SerializationError::from)?,
+                );
+                let slot = match self
+                    .conductor_handle
+                    .idempotent_responses()
+                    .slot(key, fingerprint)
+                {
+                    Some(slot) => slot,
+                    None => {
+                        return Ok(AdminResponse::Error(ExternalApiWireError::validation(
+                            "request",
+                            "does not match the request first sent with this key",
+                        )))
+                    }
+                };
+                // a retry arriving while the first attempt is still running
+                // waits for its response
+                let mut response = slot.lock().await;
+                if let Some(response) = &*response {
+                    debug!(
+                        request_type = request.request_type(),
+                        "Replaying idempotent request"
+                    );
+                    return Ok(response.clone());
+                }
+                let r = self.handle_admin_request_inner(*request).await?;
+                *response = Some(r.clone());
+                Ok(r)
+            }
+        }
+    }
+}
+
+/// How long the response to an [`AdminRequest::Idempotent`] is kept,
+/// counting from when its key was first seen
+pub const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The successful responses to [`AdminRequest::Idempotent`] requests
+/// by key, shared by all admin interfaces. Clones share the responses.
+#[derive(Clone, Default)]
+pub struct IdempotentResponses {
+    responses: Arc<parking_lot::Mutex<HashMap<String, IdempotentEntry>>>,
+}
+
+/// When a key was first seen, a hash of the request first sent with it,
+/// and the slot for its response
+type IdempotentEntry = (Instant, Vec<u8>, IdempotentSlot);
+
+/// The response for one idempotency key, once there is one
+type IdempotentSlot = Arc<tokio::sync::Mutex<Option<AdminResponse>>>;
+
+impl IdempotentResponses {
+    /// The slot for `key`, forgetting any which have expired.
+    /// None if `key` was first sent with a request whose hash
+    /// isn't `fingerprint`.
+    fn slot(&self, key: String, fingerprint: Vec<u8>) -> Option<IdempotentSlot> {
+        let now = Instant::now();
+        let mut responses = self.responses.lock();
+        responses.retain(|_, (seen_at, _, _)| now.duration_since(*seen_at) < IDEMPOTENCY_KEY_TTL);
+        let (_, first_fingerprint, slot) = responses
+            .entry(key)
+            .or_insert_with(|| (now, fingerprint.clone(), Default::default()));
+        if *first_fingerprint == fingerprint {
+            Some(slot.clone())
+        } else {
+            None
         }
     }
 }
@@ -300,6 +520,27 @@ fn _resolve_phenotype(
     (uid, properties)
 }
 
+/// Read the `DnaBundle` at `path`, telling a file that isn't there
+/// apart from one that isn't a valid bundle
+async fn read_dna_bundle(path: &std::path::Path) -> ConductorApiResult<DnaBundle> {
+    let io_error = |e: std::io::Error| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ConductorApiError::DnaFileNotFound(path.to_owned())
+        } else {
+            ConductorApiError::DnaReadError(format!("{}: {}", path.display(), e))
+        }
+    };
+    match Bundle::read_from_file(path).await {
+        Ok(bundle) => Ok(bundle.into()),
+        Err(MrBundleError::IoError(e)) => Err(io_error(e.into_inner())),
+        Err(MrBundleError::StdIoError(e)) => Err(io_error(e)),
+        Err(e) => Err(ConductorApiError::DnaParseError {
+            path: path.to_owned(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
 #[async_trait::async_trait]
 impl InterfaceApi for RealAdminInterfaceApi {
     type ApiRequest = AdminRequest;
@@ -309,6 +550,12 @@ impl InterfaceApi for RealAdminInterfaceApi {
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
     ) -> InterfaceResult<Self::ApiResponse> {
+        // Answered without waiting for the conductor lock,
+        // so a liveness probe isn't held up by a busy conductor
+        if let Ok(AdminRequest::Ping) = request {
+            return Ok(self.pong());
+        }
+        let _pending = self.conductor_handle.interface_load().request_started();
         // Don't hold the read across both awaits
         {
             self.conductor_handle
@@ -327,6 +574,7 @@ impl InterfaceApi for RealAdminInterfaceApi {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::conductor::interface::websocket::InterfaceKind;
     use crate::conductor::Conductor;
     use anyhow::Result;
     use holochain_lmdb::test_utils::test_environments;
@@ -339,6 +587,244 @@ mod test {
     use observability;
     use uuid::Uuid;
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn info() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let res = admin_api.handle_admin_request(AdminRequest::Info).await;
+        assert_matches!(
+            res,
+            AdminResponse::Info { wire_version, supported_requests, .. }
+            if wire_version == ADMIN_SUBPROTOCOL_V1
+                && supported_requests.contains(&"info".to_string())
+                && supported_requests.contains(&"dump_conductor_state".to_string())
+        );
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ping() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let uptime = |res| match res {
+            AdminResponse::Pong {
+                uptime_ms,
+                admin_listeners_died,
+                app_signal_encode_failures,
+                system_signal_encode_failures,
+            } => {
+                assert_eq!(admin_listeners_died, 0);
+                assert_eq!(app_signal_encode_failures, 0);
+                assert_eq!(system_signal_encode_failures, 0);
+                uptime_ms
+            }
+            other => panic!("unexpected response {:?}", other),
+        };
+        let first = uptime(admin_api.handle_admin_request(AdminRequest::Ping).await);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        let second = uptime(admin_api.handle_request(Ok(AdminRequest::Ping)).await?);
+        assert!(second >= first + 10);
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn idempotent_requests_are_replayed() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let generate = |key: &str| AdminRequest::Idempotent {
+            key: key.to_string(),
+            request: Box::new(AdminRequest::GenerateAgentPubKey),
+        };
+
+        let first = match admin_api.handle_admin_request(generate("a")).await {
+            AdminResponse::AgentPubKeyGenerated(agent_key) => agent_key,
+            r => panic!("unexpected response {:?}", r),
+        };
+        // the retry gets the same key rather than generating another
+        let res = admin_api.handle_admin_request(generate("a")).await;
+        assert_matches!(res, AdminResponse::AgentPubKeyGenerated(k) if k == first);
+        let res = admin_api.handle_admin_request(generate("b")).await;
+        assert_matches!(res, AdminResponse::AgentPubKeyGenerated(k) if k != first);
+        // reusing a key for a different request is rejected
+        let res = admin_api
+            .handle_admin_request(AdminRequest::Idempotent {
+                key: "a".to_string(),
+                request: Box::new(AdminRequest::ListDnas),
+            })
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::Validation { .. })
+        );
+
+        assert!(generate("").validate().is_err());
+        let nested = AdminRequest::Idempotent {
+            key: "c".to_string(),
+            request: Box::new(generate("d")),
+        };
+        assert!(nested.validate().is_err());
+        assert_eq!(generate("e").request_type(), "generate_agent_pub_key");
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_interface_on_ipv6() -> Result<()> {
+        observability::test_run().ok();
+        // skip if this environment has no IPv6 loopback
+        if std::net::TcpListener::bind("[::1]:0").is_err() {
+            return Ok(());
+        }
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: None,
+                bind_address: Some(std::net::Ipv6Addr::LOCALHOST.into()),
+                signal_coalesce_window_ms: None,
+                eager_signals: false,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                signal_overflow: Default::default(),
+                signal_encode_failure: Default::default(),
+                installed_app_id: None,
+            })
+            .await;
+        let port = match res {
+            AdminResponse::AppInterfaceAttached { port, .. } => port,
+            other => panic!("unexpected response {:?}", other),
+        };
+        std::net::TcpStream::connect((std::net::Ipv6Addr::LOCALHOST, port))?;
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_interface_on_a_port_in_use() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let taken = std::net::TcpListener::bind("0.0.0.0:0")?;
+        let taken_port = taken.local_addr()?.port();
+        let res = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: Some(taken_port),
+                bind_address: None,
+                signal_coalesce_window_ms: None,
+                eager_signals: false,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                signal_overflow: Default::default(),
+                signal_encode_failure: Default::default(),
+                installed_app_id: None,
+            })
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::PortInUse { port }) if port == taken_port
+        );
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recent_errors() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::RecentErrors)
+            .await;
+        assert_matches!(res, AdminResponse::RecentErrorsListed(e) if e.is_empty());
+
+        handle.recent_interface_errors().record(
+            InterfaceKind::App,
+            "127.0.0.1:1",
+            InterfaceError::SendError,
+        );
+        let res = admin_api
+            .handle_admin_request(AdminRequest::RecentErrors)
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::RecentErrorsListed(e)
+            if e.len() == 1 && e[0].interface == "app" && e[0].remote_addr == "127.0.0.1:1"
+        );
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_op_data_not_held() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::GetOpData {
+                dna_hash: DnaHash::from_raw_32(vec![1; 32]),
+                op_hash: DhtOpHash::from_raw_32(vec![2; 32]),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::OpDataFetched(None));
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn register_list_dna_app() -> Result<()> {
         observability::test_run().ok();
@@ -384,6 +870,15 @@ mod test {
         let expects = vec![dna_hash.clone()];
         assert_matches!(dna_list, AdminResponse::DnasListed(a) if a == expects);
 
+        let dna_list = admin_api
+            .handle_admin_request(AdminRequest::ListInstalledDnas)
+            .await;
+        let expects = vec![InstalledDnaInfo {
+            dna_hash: dna_hash.clone(),
+            zome_names: vec![TestWasm::Foo.into()],
+        }];
+        assert_matches!(dna_list, AdminResponse::InstalledDnasListed(a) if a == expects);
+
         // register by hash
         let hash_payload = RegisterDnaPayload {
             uid: None,
@@ -471,6 +966,110 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_dna_from_bad_path() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let tmpdir = tempdir::TempDir::new("bad_dna").unwrap();
+
+        // nothing there
+        let missing = tmpdir.path().join("missing.dna");
+        let response = admin_api
+            .handle_admin_request(AdminRequest::RegisterDna(Box::new(RegisterDnaPayload {
+                uid: None,
+                properties: None,
+                source: DnaSource::Path(missing.clone()),
+            })))
+            .await;
+        assert_matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::DnaFileNotFound { path }) if path == missing
+        );
+
+        // something there, but not a dna
+        let garbage = tmpdir.path().join("garbage.dna");
+        std::fs::write(&garbage, b"not a dna bundle").unwrap();
+        let response = admin_api
+            .handle_admin_request(AdminRequest::RegisterDna(Box::new(RegisterDnaPayload {
+                uid: None,
+                properties: None,
+                source: DnaSource::Path(garbage.clone()),
+            })))
+            .await;
+        assert_matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::DnaParseError { path, .. }) if path == garbage
+        );
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn install_dna_from_bytes() -> Result<()> {
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let uid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let bytes = DnaBundle::from_dna_file(dna).await?.encode()?;
+
+        let install_response = admin_api
+            .handle_admin_request(AdminRequest::InstallDnaFromBytes {
+                dna: SerializedBytes::from(UnsafeBytes::from(bytes.clone())),
+                properties: None,
+            })
+            .await;
+        assert_matches!(
+            install_response,
+            AdminResponse::DnaRegistered(h) if h == dna_hash
+        );
+
+        let dna_list = admin_api.handle_admin_request(AdminRequest::ListDnas).await;
+        let expects = vec![dna_hash.clone()];
+        assert_matches!(dna_list, AdminResponse::DnasListed(a) if a == expects);
+
+        // with a property should install and produce a different hash
+        let json: serde_yaml::Value = serde_yaml::from_str("some prop: \"foo\"").unwrap();
+        let install_response = admin_api
+            .handle_admin_request(AdminRequest::InstallDnaFromBytes {
+                dna: SerializedBytes::from(UnsafeBytes::from(bytes)),
+                properties: Some(YamlProperties::new(json)),
+            })
+            .await;
+        assert_matches!(
+            install_response,
+            AdminResponse::DnaRegistered(hash) if hash != dna_hash
+        );
+
+        // garbage bytes should fail to parse
+        let install_response = admin_api
+            .handle_admin_request(AdminRequest::InstallDnaFromBytes {
+                dna: SerializedBytes::from(UnsafeBytes::from(vec![0xdb; 32])),
+                properties: None,
+            })
+            .await;
+        assert_matches!(install_response, AdminResponse::Error(_));
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn install_list_dna_app() -> Result<()> {
         observability::test_run().ok();
@@ -546,9 +1145,30 @@ mod test {
         let expects = vec![dna_hash.clone()];
         assert_matches!(dna_list, AdminResponse::DnasListed(a) if a == expects);
 
+        // a dry run validates the app without activating it
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                installed_app_id: "test-by-path".to_string(),
+                dry_run: true,
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppsValidated);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListActiveApps)
+            .await;
+        assert_matches!(res, AdminResponse::ActiveAppsListed(v) if v.is_empty());
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                installed_app_id: "not-installed".to_string(),
+                dry_run: true,
+            })
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
         let res = admin_api
             .handle_admin_request(AdminRequest::ActivateApp {
                 installed_app_id: "test-by-path".to_string(),
+                dry_run: false,
             })
             .await;
         assert_matches!(res, AdminResponse::AppActivated);
@@ -557,7 +1177,40 @@ mod test {
             .handle_admin_request(AdminRequest::ListCellIds)
             .await;
 
-        assert_matches!(res, AdminResponse::CellIdsListed(v) if v == vec![cell_id2]);
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v == vec![cell_id2.clone()]);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::DumpConductorState)
+            .await;
+        let expects = vec![ActiveCellDump {
+            installed_app_id: "test-by-path".to_string(),
+            dna_hash: cell_id2.dna_hash().clone(),
+            agent_pub_key: cell_id2.agent_pubkey().clone(),
+        }];
+        assert_matches!(res, AdminResponse::ConductorStateDumped(s) if s.active_cells == expects);
+
+        // attaching an app interface reports the active cells
+        let res = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: None,
+                bind_address: None,
+                signal_coalesce_window_ms: None,
+                eager_signals: false,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                signal_overflow: Default::default(),
+                signal_encode_failure: Default::default(),
+                installed_app_id: None,
+            })
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::AppInterfaceAttached { active_cells, .. }
+                if active_cells.len() == 1
+                    && &active_cells[0].dna_hash == cell_id2.dna_hash()
+                    && &active_cells[0].agent_pub_key == cell_id2.agent_pubkey()
+        );
 
         // now try to install the happ using the hash
         let _install_response = admin_api
@@ -566,6 +1219,7 @@ mod test {
         let _res = admin_api
             .handle_admin_request(AdminRequest::ActivateApp {
                 installed_app_id: "test-by-hash".to_string(),
+                dry_run: false,
             })
             .await;
 
@@ -576,6 +1230,98 @@ mod test {
         assert_matches!(res, AdminResponse::ActiveAppsListed(v) if v.contains(&"test-by-path".to_string()) && v.contains(&"test-by-hash".to_string())
         );
 
+        let res = admin_api
+            .handle_admin_request(AdminRequest::DeactivateApp {
+                installed_app_id: "test-by-path".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppDeactivated);
+
+        // deactivating an app which isn't active is an error
+        let res = admin_api
+            .handle_admin_request(AdminRequest::DeactivateApp {
+                installed_app_id: "test-by-path".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListActiveApps)
+            .await;
+        assert_matches!(res, AdminResponse::ActiveAppsListed(v) if v == vec!["test-by-hash".to_string()]);
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn generate_agent_key_install_activate() -> Result<()> {
+        use holochain_keystore::AgentPubKeyExt;
+
+        observability::test_run().ok();
+        let envs = test_environments();
+        let handle = Conductor::builder().test(&envs).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let uid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let (dna_path, _tempdir) = write_fake_dna_file(dna).await.unwrap();
+
+        let agent_key = match admin_api
+            .handle_admin_request(AdminRequest::GenerateAgentPubKey)
+            .await
+        {
+            AdminResponse::AgentPubKeyGenerated(agent_key) => agent_key,
+            other => panic!("expected a generated key, got {:?}", other),
+        };
+        // every request gets a key of its own
+        assert_matches!(
+            admin_api.handle_admin_request(AdminRequest::GenerateAgentPubKey).await,
+            AdminResponse::AgentPubKeyGenerated(other_key) if other_key != agent_key
+        );
+        // the conductor's keystore holds the private key, so can sign for it
+        let signature = agent_key.sign_raw(handle.keystore(), b"data").await?;
+        assert!(agent_key.verify_signature_raw(&signature, b"data").await?);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::RegisterDna(Box::new(RegisterDnaPayload {
+                uid: None,
+                properties: None,
+                source: DnaSource::Path(dna_path),
+            })))
+            .await;
+        assert_matches!(res, AdminResponse::DnaRegistered(h) if h == dna_hash);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::InstallApp(Box::new(InstallAppPayload {
+                dnas: vec![InstallAppDnaPayload::hash_only(
+                    dna_hash.clone(),
+                    "".to_string(),
+                )],
+                installed_app_id: "generated".to_string(),
+                agent_key: agent_key.clone(),
+            })))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                installed_app_id: "generated".to_string(),
+                dry_run: false,
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppActivated);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v == vec![CellId::new(dna_hash, agent_key)]);
+
         handle.shutdown().await;
         tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
             .await
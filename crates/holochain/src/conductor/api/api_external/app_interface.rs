@@ -82,6 +82,17 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     })
             }
             AppRequest::ZomeCall(call) => {
+                // don't go near the ribosome for a cell no active app has
+                if self
+                    .conductor_handle
+                    .list_active_apps_for_cell_id(&call.cell_id)
+                    .await?
+                    .is_empty()
+                {
+                    return Ok(AppResponse::Error(ExternalApiWireError::CellNotActive {
+                        cell_id: call.cell_id,
+                    }));
+                }
                 match self.conductor_handle.call_zome(*call.clone()).await? {
                     Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
                     Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
@@ -97,7 +108,22 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
+            AppRequest::ChainHead { cell_id } => Ok(AppResponse::ChainHead(
+                self.conductor_handle.get_chain_head(&cell_id).await?,
+            )),
+            AppRequest::ListCells => Ok(AppResponse::CellsListed(
+                self.conductor_handle
+                    .list_cell_ids_for_app_interface(&self.interface_id)
+                    .await?,
+            )),
             AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
+            // calls are only tracked by the connection they were made on,
+            // which handles cancelling them before they get here
+            AppRequest::CancelZomeCall { call_id } => {
+                Ok(AppResponse::Error(ExternalApiWireError::ZomeCallNotFound {
+                    call_id,
+                }))
+            }
             AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
         }
     }
@@ -111,6 +137,7 @@ impl InterfaceApi for RealAppInterfaceApi {
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
     ) -> InterfaceResult<Self::ApiResponse> {
+        let _pending = self.conductor_handle.interface_load().request_started();
         {
             self.conductor_handle
                 .check_running()
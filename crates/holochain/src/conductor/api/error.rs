@@ -71,6 +71,19 @@ pub enum ConductorApiError {
     #[error("The Dna file path provided was invalid")]
     DnaReadError(String),
 
+    /// There is no file at the Dna file path provided
+    #[error("There is no Dna file at {}", .0.display())]
+    DnaFileNotFound(std::path::PathBuf),
+
+    /// The file at the Dna file path provided isn't a valid DnaBundle
+    #[error("The file at {} is not a valid Dna bundle: {reason}", .path.display())]
+    DnaParseError {
+        /// The path that was given
+        path: std::path::PathBuf,
+        /// Why it couldn't be parsed
+        reason: String,
+    },
+
     /// KeystoreError
     #[error("KeystoreError: {0}")]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
@@ -121,6 +134,21 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::DnaReadError(e) => ExternalApiWireError::DnaReadError(e),
+            ConductorApiError::DnaFileNotFound(path) => {
+                ExternalApiWireError::DnaFileNotFound { path }
+            }
+            ConductorApiError::DnaParseError { path, reason } => {
+                ExternalApiWireError::DnaParseError { path, reason }
+            }
+            ConductorApiError::InterfaceError(InterfaceError::PortInUse { port }) => {
+                ExternalApiWireError::PortInUse { port }
+            }
+            ConductorApiError::ConductorError(ConductorError::InterfaceError(e)) => match *e {
+                InterfaceError::PortInUse { port } => ExternalApiWireError::PortInUse { port },
+                e => ExternalApiWireError::internal(ConductorApiError::ConductorError(
+                    ConductorError::InterfaceError(Box::new(e)),
+                )),
+            },
             e => ExternalApiWireError::internal(e),
         }
     }
@@ -140,6 +168,17 @@ impl From<RibosomeError> for ExternalApiWireError {
 
 impl From<CreateAppError> for ExternalApiWireError {
     fn from(e: CreateAppError) -> Self {
-        ExternalApiWireError::ActivateApp(e.to_string())
+        match e {
+            CreateAppError::Failed {
+                installed_app_id,
+                errors,
+            } => ExternalApiWireError::ActivateAppFailed {
+                installed_app_id,
+                failed_dnas: errors
+                    .into_iter()
+                    .map(|(cell_id, e)| (cell_id.dna_hash().clone(), e.to_string()))
+                    .collect(),
+            },
+        }
     }
 }
@@ -601,19 +601,20 @@ impl Cell {
     }
 
     #[instrument(skip(self, dht_arc, since, until))]
-    /// the network module is requesting a list of dht op hashes
+    /// the network module is requesting a list of dht op hashes,
+    /// with when each op was integrated
     fn handle_fetch_op_hashes_for_constraints(
         &self,
         dht_arc: holochain_p2p::dht_arc::DhtArc,
         since: Timestamp,
         until: Timestamp,
-    ) -> CellResult<Vec<DhtOpHash>> {
+    ) -> CellResult<Vec<(DhtOpHash, Timestamp)>> {
         let env_ref = self.env.guard();
         let reader = env_ref.reader()?;
         let integrated_dht_ops = IntegratedDhtOpsBuf::new(self.env().clone().into())?;
-        let result: Vec<DhtOpHash> = integrated_dht_ops
+        let result: Vec<(DhtOpHash, Timestamp)> = integrated_dht_ops
             .query(&reader, Some(since), Some(until), Some(dht_arc))?
-            .map(|(k, _)| Ok(k))
+            .map(|(k, v)| Ok((k, v.when_integrated)))
             .collect()?;
         Ok(result)
     }
@@ -653,6 +654,20 @@ impl Cell {
         Ok(out)
     }
 
+    /// the op with this hash, fetched as for a `FetchOpHashData` event,
+    /// if this cell holds it
+    pub(crate) async fn get_op_data(
+        &self,
+        op_hash: holo_hash::DhtOpHash,
+    ) -> CellResult<Option<holochain_types::dht_op::DhtOp>> {
+        Ok(self
+            .handle_fetch_op_hash_data(vec![op_hash])
+            .await?
+            .into_iter()
+            .next()
+            .map(|(_, _, op)| op))
+    }
+
     /// the network module would like this cell/agent to sign some data
     #[tracing::instrument(skip(self))]
     async fn handle_sign_network_data(&self) -> CellResult<Signature> {
@@ -676,6 +691,7 @@ impl Cell {
             payload,
             provenance: from_agent,
             fn_name,
+            call_id: None,
         };
         // double ? because
         // - ConductorApiResult
@@ -21,12 +21,19 @@ use super::interface::error::InterfaceResult;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
-use super::interface::websocket::SIGNAL_BUFFER_SIZE;
+use super::interface::websocket::AdminInterfaceOptions;
+use super::interface::websocket::AppInterfaceOptions;
+use super::interface::websocket::InterfaceContext;
+use super::interface::websocket::InterfaceCounters;
+use super::interface::websocket::InterfaceLoad;
+use super::interface::websocket::RecentInterfaceErrors;
+use super::interface::websocket::DEFAULT_RECENT_INTERFACE_ERRORS;
+use super::interface::InterfaceSignalSender;
 use super::interface::SignalBroadcaster;
 use super::manager::keep_alive_task;
 use super::manager::spawn_task_manager;
+use super::manager::InterfaceTaskHandle;
 use super::manager::ManagedTaskAdd;
-use super::manager::ManagedTaskHandle;
 use super::manager::TaskManagerRunHandle;
 use super::p2p_store::all_agent_infos;
 use super::p2p_store::get_single_agent_info;
@@ -54,6 +61,10 @@ use futures::future;
 use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
+use holochain_conductor_api::ActiveCellDump;
+use holochain_conductor_api::ActiveCellOpCount;
+use holochain_conductor_api::ChainHeadInfo;
+use holochain_conductor_api::ConductorStateDump;
 use holochain_conductor_api::JsonDump;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
@@ -69,9 +80,11 @@ use holochain_lmdb::env::ReadManager;
 use holochain_lmdb::exports::SingleStore;
 use holochain_lmdb::fresh_reader;
 use holochain_lmdb::prelude::*;
+use holochain_state::chain_sequence::ChainSequenceBuf;
 use holochain_state::source_chain::SourceChainBuf;
 use holochain_state::wasm::WasmBuf;
 use holochain_types::prelude::*;
+use holochain_websocket::WebsocketConfig;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -135,6 +148,10 @@ where
     /// Collection app interface data, keyed by id
     app_interfaces: HashMap<AppInterfaceId, AppInterfaceRuntime>,
 
+    /// The active apps each cell is part of, refreshed whenever the state
+    /// changes so signals can be scoped without reading the state
+    active_apps_by_cell: parking_lot::RwLock<ActiveAppsByCell>,
+
     /// The channels and handles needed to interact with the task_manager task.
     /// If this is None, then the task manager has not yet been initialized.
     task_manager: Option<TaskManagerClient>,
@@ -226,6 +243,26 @@ where
         }
     }
 
+    /// Broadcasts the drain signal to all interface tasks, which stop
+    /// accepting connections and close the ones they have once in-flight
+    /// requests are answered, or a deadline passes. Other managed tasks
+    /// keep running until [`Conductor::shutdown`].
+    pub(super) fn drain_interfaces(&mut self) {
+        if let Some(manager) = &self.task_manager {
+            tracing::info!(
+                "Sending drain signal to {} interfaces.",
+                manager.interface_drain_broadcaster().receiver_count(),
+            );
+            manager
+                .interface_drain_broadcaster()
+                .send(())
+                .map(|_| ())
+                .unwrap_or_else(|e| {
+                    error!(?e, "Couldn't broadcast drain signal to interfaces!");
+                })
+        }
+    }
+
     /// Return the handle which waits for the task manager task to complete
     pub(super) fn take_shutdown_handle(&mut self) -> Option<TaskManagerRunHandle> {
         self.task_manager
@@ -243,27 +280,43 @@ where
     where
         DS: DnaStore + 'static,
     {
+        let context = InterfaceContext {
+            websocket_config: handle.interface_websocket_config().clone(),
+            connection_hook: Some(handle.interface_counters().hook()),
+            recent_errors: Some(handle.recent_interface_errors().clone()),
+            load: Some(handle.interface_load().clone()),
+        };
         let admin_api = RealAdminInterfaceApi::new(handle);
-        let stop_tx = self
+        let task_manager = self
             .task_manager
             .as_ref()
-            .expect("Task manager not started yet")
-            .task_stop_broadcaster()
-            .clone();
+            .expect("Task manager not started yet");
+        let stop_tx = task_manager.task_stop_broadcaster().clone();
+        let drain_tx = task_manager.interface_drain_broadcaster().clone();
 
         // Closure to process each admin config item
-        let spawn_from_config = |AdminInterfaceConfig { driver, .. }| {
+        let spawn_from_config = |config: AdminInterfaceConfig| {
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
+            let drain_tx = drain_tx.clone();
+            let context = context.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
+                let options = AdminInterfaceOptions::from(&config);
+                match config.driver {
+                    InterfaceDriver::Websocket { port, .. } => {
+                        let (listener_handle, listener) = spawn_websocket_listener(
+                            config.driver.socket_addr(),
+                            context.websocket_config.clone(),
+                        )
+                        .await?;
                         let port = listener_handle.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                        let handle: InterfaceTaskHandle = spawn_admin_interface_task(
                             listener_handle,
                             listener,
                             admin_api.clone(),
+                            options,
+                            context,
+                            drain_tx.subscribe(),
                             stop_tx.subscribe(),
                         )?;
                         InterfaceResult::Ok((port, handle))
@@ -296,7 +349,7 @@ where
             // Now that tasks are spawned, register them with the TaskManager
             for (port, handle) in handles {
                 ports.push(port);
-                self.manage_task(ManagedTaskAdd::ignore(
+                self.manage_task(ManagedTaskAdd::interface(
                     handle,
                     &format!("admin interface, port {}", port),
                 ))
@@ -309,44 +362,63 @@ where
         Ok(())
     }
 
+    /// Start an app interface as `config` describes, under `interface_id`
+    /// if it is being restarted from state, or a new id otherwise
+    #[allow(irrefutable_let_patterns)]
     pub(super) async fn add_app_interface_via_handle(
         &mut self,
-        port: either::Either<u16, AppInterfaceId>,
+        interface_id: Option<AppInterfaceId>,
+        mut config: AppInterfaceConfig,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
-        let interface_id = match port {
-            either::Either::Left(port) => AppInterfaceId::new(port),
-            either::Either::Right(id) => id,
-        };
+        let interface_id =
+            interface_id.unwrap_or_else(|| AppInterfaceId::new(config.driver.port()));
         let port = interface_id.port();
         tracing::debug!("Attaching interface {}", port);
+        let context = InterfaceContext {
+            websocket_config: handle.interface_websocket_config().clone(),
+            connection_hook: Some(handle.interface_counters().hook()),
+            recent_errors: Some(handle.recent_interface_errors().clone()),
+            load: Some(handle.interface_load().clone()),
+        };
         let app_api = RealAppInterfaceApi::new(handle, interface_id.clone());
-        // This receiver is thrown away because we can produce infinite new
-        // receivers from the Sender
-        let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
-        let stop_rx = self
+        let signal_tx = InterfaceSignalSender::new(config.signal_overflow);
+        let task_manager = self
             .task_manager
             .as_ref()
-            .expect("Task manager not initialized")
-            .task_stop_broadcaster()
-            .subscribe();
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+            .expect("Task manager not initialized");
+        let drain_rx = task_manager.interface_drain_broadcaster().subscribe();
+        let stop_rx = task_manager.task_stop_broadcaster().subscribe();
+        let InterfaceDriver::Websocket { bind_address, .. } = config.driver;
+        let driver = InterfaceDriver::Websocket { port, bind_address };
+        let (port, task) = spawn_app_interface_task(
+            driver.socket_addr(),
+            app_api,
+            signal_tx.clone(),
+            AppInterfaceOptions::from(&config),
+            context,
+            drain_rx,
+            stop_rx,
+        )
+        .await
+        .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
-        self.manage_task(ManagedTaskAdd::ignore(
+        self.manage_task(ManagedTaskAdd::interface(
             task,
             &format!("app interface, port {}", port),
         ))
         .await?;
-        let interface = AppInterfaceRuntime::Websocket { signal_tx };
+        let interface = AppInterfaceRuntime::Websocket {
+            signal_tx,
+            installed_app_id: config.installed_app_id.clone(),
+        };
 
         if self.app_interfaces.contains_key(&interface_id) {
             return Err(ConductorError::AppInterfaceIdCollision(interface_id));
         }
 
         self.app_interfaces.insert(interface_id.clone(), interface);
-        let config = AppInterfaceConfig::websocket(port);
+        config.driver = InterfaceDriver::Websocket { port, bind_address };
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
@@ -399,15 +471,14 @@ where
 
     /// Start all app interfaces currently in state.
     /// This should only be run at conductor initialization.
-    #[allow(irrefutable_let_patterns)]
     pub(super) async fn startup_app_interfaces_via_handle(
         &mut self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(Some(id), config, handle.clone())
                 .await?;
         }
         Ok(())
@@ -418,7 +489,23 @@ where
             self.app_interfaces
                 .values()
                 .map(|i| i.signal_tx())
-                .cloned()
+                .collect(),
+        )
+    }
+
+    /// The signal broadcaster for `cell_id`, leaving out interfaces
+    /// attached for apps the cell isn't part of
+    pub(super) fn signal_broadcaster_for_cell(&self, cell_id: &CellId) -> SignalBroadcaster {
+        let active_apps_by_cell = self.active_apps_by_cell.read();
+        let apps = active_apps_by_cell.get(cell_id);
+        SignalBroadcaster::new(
+            self.app_interfaces
+                .values()
+                .filter(|i| {
+                    i.installed_app_id()
+                        .map_or(true, |id| apps.map_or(false, |apps| apps.contains(id)))
+                })
+                .map(|i| i.signal_tx())
                 .collect(),
         )
     }
@@ -545,25 +632,30 @@ where
                     // Create each cell
                     let cells_tasks = cells_to_create.map(
                         |(cell_id, dir, keystore, conductor_handle)| async move {
-                            let holochain_p2p_cell = self.holochain_p2p.to_cell(
-                                cell_id.dna_hash().clone(),
-                                cell_id.agent_pubkey().clone(),
-                            );
-
-                            let env = EnvironmentWrite::new_cell(
-                                &dir,
-                                cell_id.clone(),
-                                keystore.clone(),
-                            )?;
-                            Cell::create(
-                                cell_id.clone(),
-                                conductor_handle.clone(),
-                                env,
-                                holochain_p2p_cell,
-                                task_manager.task_add_sender().clone(),
-                                task_manager.task_stop_broadcaster().clone(),
-                            )
-                            .await
+                            let result = async {
+                                let holochain_p2p_cell = self.holochain_p2p.to_cell(
+                                    cell_id.dna_hash().clone(),
+                                    cell_id.agent_pubkey().clone(),
+                                );
+
+                                let env = EnvironmentWrite::new_cell(
+                                    &dir,
+                                    cell_id.clone(),
+                                    keystore.clone(),
+                                )?;
+                                Cell::create(
+                                    cell_id.clone(),
+                                    conductor_handle.clone(),
+                                    env,
+                                    holochain_p2p_cell,
+                                    task_manager.task_add_sender().clone(),
+                                    task_manager.task_stop_broadcaster().clone(),
+                                )
+                                .await
+                            }
+                            .await;
+                            // Keep the cell id so a failure can say which cell it was
+                            (cell_id, result)
                         },
                     );
 
@@ -573,24 +665,25 @@ where
                         futures::future::join_all(cells_tasks)
                             .await
                             .into_iter()
-                            .partition(Result::is_ok);
+                            .partition(|(_, result)| result.is_ok());
                     // unwrap safe because of the partition
-                    let success = success.into_iter().map(Result::unwrap);
+                    let success = success.into_iter().map(|(_, result)| result.unwrap());
 
                     // If there were errors, cleanup and return the errors
                     if !errors.is_empty() {
                         for cell in success {
+                            let cell_id = cell.0.id().clone();
                             // Error needs to capture which app failed
                             cell.0.destroy().await.map_err(|e| CreateAppError::Failed {
                                 installed_app_id: installed_app_id.clone(),
-                                errors: vec![e],
+                                errors: vec![(cell_id, e)],
                             })?;
                         }
                         // match needed to avoid Debug requirement on unwrap_err
                         let errors = errors
                             .into_iter()
-                            .map(|e| match e {
-                                Err(e) => e,
+                            .map(|(cell_id, result)| match result {
+                                Err(e) => (cell_id, e),
                                 Ok(_) => unreachable!("Safe because of the partition"),
                             })
                             .collect();
@@ -651,6 +744,28 @@ where
         Ok(())
     }
 
+    /// Check that an inactive app could be activated, i.e. that every DNA
+    /// its cells need is registered, without touching the database
+    pub(super) async fn validate_app_activation(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>> {
+        let state = self.get_state().await?;
+        let app = state
+            .inactive_apps
+            .get(installed_app_id)
+            .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+        app.all_cells()
+            .map(|cell_id| {
+                let dna_hash = cell_id.dna_hash();
+                match self.dna_store().get(dna_hash) {
+                    Some(_) => Ok(cell_id.clone()),
+                    None => Err(DnaError::DnaMissing(dna_hash.to_owned()).into()),
+                }
+            })
+            .collect()
+    }
+
     /// Deactivate an app in the database
     pub(super) async fn deactivate_app_in_db(
         &mut self,
@@ -901,6 +1016,25 @@ where
             .collect())
     }
 
+    /// The cells of the active apps the app interface `interface_id` serves,
+    /// which is only the attached app if it was attached for one
+    pub(super) async fn list_cell_ids_for_app_interface(
+        &self,
+        interface_id: &AppInterfaceId,
+    ) -> ConductorResult<Vec<CellId>> {
+        let scope = self
+            .app_interfaces
+            .get(interface_id)
+            .and_then(|i| i.installed_app_id().cloned());
+        let active_apps = self.get_state().await?.active_apps;
+        let cell_ids: HashSet<CellId> = active_apps
+            .iter()
+            .filter(|(k, _)| scope.as_ref().map_or(true, |id| id == *k))
+            .flat_map(|(_, v)| v.all_cells().cloned())
+            .collect();
+        Ok(cell_ids.into_iter().collect())
+    }
+
     pub(super) async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
         let cell = self.cell_by_id(cell_id)?;
         let arc = cell.env();
@@ -921,6 +1055,89 @@ where
         Ok(serde_json::to_string_pretty(&out)?)
     }
 
+    pub(super) async fn dump_cell_state_range(
+        &self,
+        cell_id: &CellId,
+        start: u32,
+        limit: u32,
+    ) -> ConductorApiResult<String> {
+        let cell = self.cell_by_id(cell_id)?;
+        let source_chain = SourceChainBuf::new(cell.env().clone().into())?;
+        let source_chain_dump = source_chain.dump_state_range(start, limit).await?;
+        Ok(serde_json::to_string_pretty(&source_chain_dump)?)
+    }
+
+    pub(super) fn get_chain_head(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Option<ChainHeadInfo>> {
+        let cell = self.cell_by_id(cell_id)?;
+        // Only the chain sequence is needed to find the head
+        let sequence = ChainSequenceBuf::new(cell.env().clone().into())?;
+        Ok(sequence.chain_head().map(|header_hash| ChainHeadInfo {
+            header_hash: header_hash.clone(),
+            header_seq: sequence.len() as u32 - 1,
+        }))
+    }
+
+    pub(super) async fn dump_conductor_state(&self) -> ConductorResult<ConductorStateDump> {
+        let state = self.get_state().await?;
+        let mut active_cells: Vec<ActiveCellDump> = state
+            .active_apps
+            .iter()
+            .flat_map(|(installed_app_id, app)| {
+                app.all_cells().map(move |cell_id| ActiveCellDump {
+                    installed_app_id: installed_app_id.clone(),
+                    dna_hash: cell_id.dna_hash().clone(),
+                    agent_pub_key: cell_id.agent_pubkey().clone(),
+                })
+            })
+            .collect();
+        active_cells.sort_by(|a, b| a.installed_app_id.cmp(&b.installed_app_id));
+        let mut inactive_apps: Vec<InstalledAppId> = state.inactive_apps.keys().cloned().collect();
+        inactive_apps.sort();
+        let mut app_interfaces: Vec<u16> = state
+            .app_interfaces
+            .values()
+            .map(|config| config.driver.port())
+            .collect();
+        app_interfaces.sort_unstable();
+        Ok(ConductorStateDump {
+            active_cells,
+            inactive_apps,
+            app_interfaces,
+        })
+    }
+
+    pub(super) async fn active_cell_op_counts(&self) -> ConductorApiResult<Vec<ActiveCellOpCount>> {
+        let state = self.get_state().await?;
+        let mut cell_ids: Vec<CellId> = state
+            .active_apps
+            .values()
+            .flat_map(|app| app.all_cells().cloned())
+            .collect();
+        cell_ids.sort_by(|a, b| {
+            (a.dna_hash(), a.agent_pubkey()).cmp(&(b.dna_hash(), b.agent_pubkey()))
+        });
+        cell_ids.dedup();
+        let mut counts = Vec::with_capacity(cell_ids.len());
+        for cell_id in cell_ids {
+            // An active app's cell may not be running yet
+            let cell = match self.cell_by_id(&cell_id) {
+                Ok(cell) => cell,
+                Err(_) => continue,
+            };
+            let integration_dump =
+                integrate_dht_ops_workflow::dump_state(cell.env().clone().into())?;
+            counts.push(ActiveCellOpCount {
+                dna_hash: cell_id.dna_hash().clone(),
+                agent_pub_key: cell_id.agent_pubkey().clone(),
+                op_count: integration_dump.integrated,
+            });
+        }
+        Ok(counts)
+    }
+
     pub(super) fn p2p_env(&self) -> EnvironmentWrite {
         self.p2p_env.clone()
     }
@@ -975,7 +1192,7 @@ where
     ) -> ConductorResult<Self> {
         let db: SingleStore = env.get_db(&db::CONDUCTOR_STATE)?;
 
-        Ok(Self {
+        let conductor = Self {
             env,
             wasm_env,
             p2p_env,
@@ -983,13 +1200,17 @@ where
             cells: HashMap::new(),
             shutting_down: false,
             app_interfaces: HashMap::new(),
+            active_apps_by_cell: Default::default(),
             task_manager: None,
             admin_websocket_ports: Vec::new(),
             dna_store,
             keystore,
             root_env_dir,
             holochain_p2p,
-        })
+        };
+        let state = conductor.get_state().await?;
+        *conductor.active_apps_by_cell.write() = active_apps_by_cell(&state);
+        Ok(conductor)
     }
 
     pub(super) async fn start_task_manager(
@@ -1001,9 +1222,11 @@ where
         }
         let (task_add_sender, run_handle) = spawn_task_manager(handle);
         let (task_stop_broadcaster, _) = tokio::sync::broadcast::channel::<()>(1);
+        let (interface_drain_broadcaster, _) = tokio::sync::broadcast::channel::<()>(1);
         self.task_manager = Some(TaskManagerClient::new(
             task_add_sender,
             task_stop_broadcaster,
+            interface_drain_broadcaster,
             run_handle,
         ));
         Ok(())
@@ -1039,6 +1262,7 @@ where
             self.state_db.put(txn, &UnitDbKey, &new_state)?;
             Result::<_, ConductorError>::Ok((new_state, output))
         })?;
+        *self.active_apps_by_cell.write() = active_apps_by_cell(&output.0);
         Ok(output)
     }
 
@@ -1061,6 +1285,22 @@ where
 /// The database used to store ConductorState. It has only one key-value pair.
 pub type ConductorStateDb = KvStore<UnitDbKey, ConductorState>;
 
+/// The active apps of each cell which is part of one
+type ActiveAppsByCell = HashMap<CellId, HashSet<InstalledAppId>>;
+
+fn active_apps_by_cell(state: &ConductorState) -> ActiveAppsByCell {
+    let mut by_cell = ActiveAppsByCell::new();
+    for (installed_app_id, app) in &state.active_apps {
+        for cell_id in app.all_cells() {
+            by_cell
+                .entry(cell_id.clone())
+                .or_default()
+                .insert(installed_app_id.clone());
+        }
+    }
+    by_cell
+}
+
 mod builder {
     use super::*;
     use crate::conductor::dna_store::RealDnaStore;
@@ -1211,6 +1451,17 @@ mod builder {
                 conductor: RwLock::new(conductor),
                 keystore,
                 holochain_p2p,
+                started_at: std::time::Instant::now(),
+                recent_interface_errors: RecentInterfaceErrors::new(
+                    conductor_config
+                        .recent_interface_errors
+                        .unwrap_or(DEFAULT_RECENT_INTERFACE_ERRORS),
+                ),
+                interface_load: InterfaceLoad::new(conductor_config.max_pending_interface_requests),
+                interface_counters: InterfaceCounters::default(),
+                interface_websocket_config: WebsocketConfig::default()
+                    .forwarded_for_header(conductor_config.interface_forwarded_for_header.clone()),
+                idempotent_responses: Default::default(),
             });
 
             let configs = conductor_config.admin_interfaces.unwrap_or_default();
@@ -468,6 +468,7 @@ async fn make_signing_call(client: &mut WebsocketSender, cell: &SweetCell) -> Ap
             payload: ExternIO::encode(()).unwrap(),
             cap: None,
             provenance: cell.agent_pubkey().clone(),
+            call_id: None,
         })))
         .await
         .unwrap()
@@ -522,7 +523,11 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(AppInterfaceConfig::websocket(0))
+        .await
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
@@ -599,6 +604,156 @@ async fn test_reactivate_app() {
     assert_eq_retry_10s!(conductor.list_active_apps().await.unwrap().len(), 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_scoped_interface_only_carries_its_apps_signals() {
+    observability::test_run().ok();
+    let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app1 = conductor.setup_app("app1", &[dna.clone()]).await.unwrap();
+    let app2 = conductor.setup_app("app2", &[dna]).await.unwrap();
+    let cell1 = app1.cells()[0].cell_id();
+    let cell2 = app2.cells()[0].cell_id();
+
+    let handle = conductor.inner_handle();
+    let interface_count = |broadcaster: SignalBroadcaster| broadcaster.subscribe_separately().len();
+    let unscoped = interface_count(handle.signal_broadcaster().await);
+    handle
+        .clone()
+        .add_app_interface(AppInterfaceConfig {
+            installed_app_id: Some("app1".to_string()),
+            ..AppInterfaceConfig::websocket(0)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        interface_count(handle.signal_broadcaster_for_cell(cell1).await),
+        unscoped + 1
+    );
+    assert_eq!(
+        interface_count(handle.signal_broadcaster_for_cell(cell2).await),
+        unscoped
+    );
+    // every interface is still there for anything not sent by a cell
+    assert_eq!(
+        interface_count(handle.signal_broadcaster().await),
+        unscoped + 1
+    );
+
+    // once its app is deactivated the cell no longer signals the app's interface
+    handle
+        .deactivate_app("app1".to_string(), DeactivationReason::Normal)
+        .await
+        .unwrap();
+    assert_eq!(
+        interface_count(handle.signal_broadcaster_for_cell(cell1).await),
+        unscoped
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_interfaces_list_the_cells_they_serve() {
+    observability::test_run().ok();
+    let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app1 = conductor.setup_app("app1", &[dna.clone()]).await.unwrap();
+    let app2 = conductor.setup_app("app2", &[dna]).await.unwrap();
+    let cell1 = app1.cells()[0].cell_id().clone();
+    let cell2 = app2.cells()[0].cell_id().clone();
+
+    let handle = conductor.inner_handle();
+    let list_cells = |installed_app_id: Option<InstalledAppId>| {
+        let handle = handle.clone();
+        async move {
+            let port = handle
+                .add_app_interface(AppInterfaceConfig {
+                    installed_app_id,
+                    ..AppInterfaceConfig::websocket(0)
+                })
+                .await
+                .unwrap();
+            let (mut client, _) = websocket_client_by_port(port).await.unwrap();
+            let response: AppResponse = client.request(AppRequest::ListCells).await.unwrap();
+            match response {
+                AppResponse::CellsListed(cell_ids) => cell_ids.into_iter().collect::<HashSet<_>>(),
+                other => panic!("unexpected response {:?}", other),
+            }
+        }
+    };
+
+    assert_eq!(list_cells(None).await, hashset![cell1.clone(), cell2]);
+    assert_eq!(list_cells(Some("app1".to_string())).await, hashset![cell1]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_draining_interfaces_leaves_the_conductor_running() {
+    use futures::StreamExt;
+    observability::test_run().ok();
+    let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    conductor.setup_app("app", &[dna]).await.unwrap();
+
+    let handle = conductor.inner_handle();
+    let port = handle
+        .clone()
+        .add_app_interface(AppInterfaceConfig::websocket(0))
+        .await
+        .unwrap();
+    let (_client, mut client_rx) = websocket_client_by_port(port).await.unwrap();
+
+    handle.drain_interfaces().await;
+
+    // connected clients are closed
+    let closed = tokio::time::timeout(std::time::Duration::from_secs(5), client_rx.next())
+        .await
+        .expect("client was not disconnected");
+    assert!(closed.is_none());
+
+    // but the conductor itself carries on
+    assert_eq!(
+        handle.list_active_apps().await.unwrap(),
+        vec!["app".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_gossip_events_can_be_subscribed_to() {
+    use kitsune_p2p::actor::GossipEvent;
+    observability::test_run().ok();
+    let (dna, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna.clone()]).await.unwrap();
+    let agent = app.cells()[0].agent_pubkey().clone();
+
+    let mut events = conductor
+        .subscribe_gossip_events(dna.dna_hash())
+        .await
+        .unwrap();
+    let event = tokio::time::timeout(std::time::Duration::from_secs(30), events.recv())
+        .await
+        .expect("no local sync finished")
+        .unwrap();
+    match event {
+        GossipEvent::LocalSyncFinished { status, stats } => {
+            assert_eq!(status.local_agent_count, 1);
+            assert!(status.last_local_sync_ms.is_some());
+            assert_eq!(stats.agent_arcs.len(), 1);
+            assert_eq!(
+                *stats.agent_arcs[0].agent,
+                holochain_p2p::agent_holo_to_kit(agent)
+            );
+        }
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_cells_self_deactivate_on_validation_panic() {
     observability::test_run().ok();
@@ -35,6 +35,16 @@ impl DnaStore for RealDnaStore {
         self.dnas.keys().cloned().collect()
     }
     #[instrument]
+    fn list_zome_names(&self) -> Vec<(DnaHash, Vec<ZomeName>)> {
+        self.dnas
+            .iter()
+            .map(|(hash, dna)| {
+                let zome_names = dna.dna_def().zomes.iter().map(|(name, _)| name.clone());
+                (hash.clone(), zome_names.collect())
+            })
+            .collect()
+    }
+    #[instrument]
     fn get(&self, hash: &DnaHash) -> Option<DnaFile> {
         self.dnas.get(hash).cloned()
     }
@@ -94,6 +94,9 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not active: {0}")]
     AppNotActive(InstalledAppId),
 
+    #[error("Tried to perform an operation on the cells of an agent which has none: {0}")]
+    AgentHasNoCells(AgentPubKey),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -115,7 +118,7 @@ pub enum CreateAppError {
     #[error("Failed to create the following cells in the {installed_app_id} app: {errors:?}")]
     Failed {
         installed_app_id: InstalledAppId,
-        errors: Vec<CellError>,
+        errors: Vec<(CellId, CellError)>,
     },
 }
 
@@ -40,10 +40,15 @@
 //! code which interacted with the Conductor would also have to be highly generic.
 
 use super::api::error::ConductorApiResult;
+use super::api::IdempotentResponses;
 use super::api::ZomeCall;
 use super::config::AdminInterfaceConfig;
+use super::error::ConductorError;
 use super::error::ConductorResult;
 use super::error::CreateAppError;
+use super::interface::websocket::InterfaceCounters;
+use super::interface::websocket::InterfaceLoad;
+use super::interface::websocket::RecentInterfaceErrors;
 use super::interface::SignalBroadcaster;
 use super::manager::TaskManagerRunHandle;
 use super::p2p_store::get_agent_info_signed;
@@ -51,23 +56,35 @@ use super::p2p_store::put_agent_info_signed;
 use super::p2p_store::query_agent_info_signed;
 use super::Cell;
 use super::Conductor;
+use crate::core::workflow::integrate_dht_ops_workflow;
 use crate::core::workflow::CallZomeWorkspaceLock;
 use crate::core::workflow::ZomeCallResult;
 use crate::core::{queue_consumer::InitialQueueTriggers, ribosome::real_ribosome::RealRibosome};
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
+use holochain_conductor_api::ActiveCellOpCount;
+use holochain_conductor_api::ChainHeadInfo;
+use holochain_conductor_api::ConductorStateDump;
 use holochain_conductor_api::InstalledAppInfo;
+use holochain_conductor_api::IntegrationStateDump;
 use holochain_lmdb::env::EnvironmentRead;
+use holochain_p2p::dht_arc::DhtArc;
 use holochain_p2p::event::HolochainP2pEvent::*;
 use holochain_p2p::HolochainP2pCellT;
+use holochain_p2p::HolochainP2pSender;
 use holochain_types::prelude::*;
+use holochain_websocket::WebsocketConfig;
+use kitsune_p2p::actor::GossipEvent;
+use kitsune_p2p::actor::GossipStatus;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::RwLock;
 use tracing::*;
 
+use super::state::AppInterfaceConfig;
+use super::state::AppInterfaceId;
 #[cfg(any(test, feature = "test_utils"))]
 use super::state::ConductorState;
 #[cfg(any(test, feature = "test_utils"))]
@@ -104,8 +121,10 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface as `config` describes, returning the port it
+    /// listens on, which is chosen by the OS if `config` asks for port 0
+    async fn add_app_interface(self: Arc<Self>, config: AppInterfaceConfig)
+        -> ConductorResult<u16>;
 
     /// List the app interfaces currently install.
     async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
@@ -119,6 +138,9 @@ pub trait ConductorHandleT: Send + Sync {
     /// Get the list of hashes of installed Dnas in this Conductor
     async fn list_dnas(&self) -> ConductorResult<Vec<DnaHash>>;
 
+    /// Get the hashes of installed Dnas in this Conductor with the names of their zomes
+    async fn list_dna_zome_names(&self) -> ConductorResult<Vec<(DnaHash, Vec<ZomeName>)>>;
+
     /// Get a [Dna] from the [DnaStore]
     async fn get_dna(&self, hash: &DnaHash) -> Option<DnaFile>;
 
@@ -162,9 +184,33 @@ pub trait ConductorHandleT: Send + Sync {
     /// Send a signal to all managed tasks asking them to end ASAP.
     async fn shutdown(&self);
 
+    /// Send a signal to all interfaces asking them to stop accepting
+    /// connections and close the ones they have once in-flight requests are
+    /// answered, or a deadline passes, leaving other tasks running until
+    /// [`Self::shutdown`].
+    async fn drain_interfaces(&self);
+
     /// Request access to this conductor's keystore
     fn keystore(&self) -> &KeystoreSender;
 
+    /// How long since this conductor was built
+    fn uptime(&self) -> std::time::Duration;
+
+    /// The most recent errors on this conductor's interfaces
+    fn recent_interface_errors(&self) -> &RecentInterfaceErrors;
+
+    /// The requests this conductor's interfaces are handling
+    fn interface_load(&self) -> &InterfaceLoad;
+
+    /// Counts of rare events on this conductor's interfaces
+    fn interface_counters(&self) -> &InterfaceCounters;
+
+    /// How this conductor's interfaces listen for websocket connections
+    fn interface_websocket_config(&self) -> &WebsocketConfig;
+
+    /// The responses to idempotent admin requests, for replaying them
+    fn idempotent_responses(&self) -> &IdempotentResponses;
+
     /// Request access to this conductor's networking handle
     fn holochain_p2p(&self) -> &holochain_p2p::HolochainP2pRef;
 
@@ -201,6 +247,13 @@ pub trait ConductorHandleT: Send + Sync {
     /// Activate an app
     async fn activate_app(&self, installed_app_id: InstalledAppId) -> ConductorResult<()>;
 
+    /// Check that an app could be activated, without activating it,
+    /// returning the cells activation would create
+    async fn validate_app_activation(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>>;
+
     /// Deactivate an app
     async fn deactivate_app(
         &self,
@@ -220,13 +273,77 @@ pub trait ConductorHandleT: Send + Sync {
         cell_id: &CellId,
     ) -> ConductorResult<HashSet<InstalledAppId>>;
 
+    /// List the Cell Ids of the active Apps an app interface serves
+    async fn list_cell_ids_for_app_interface(
+        &self,
+        interface_id: &AppInterfaceId,
+    ) -> ConductorResult<Vec<CellId>>;
+
     /// Dump the cells state
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// Dump a slice of a cell's source chain, starting at `start`
+    async fn dump_cell_state_range(
+        &self,
+        cell_id: &CellId,
+        start: u32,
+        limit: u32,
+    ) -> ConductorApiResult<String>;
+
+    /// Wait until a cell has no ops left in validation or integration limbo,
+    /// giving up after `timeout`, and return where its ops are
+    async fn await_cell_integration(
+        &self,
+        cell_id: &CellId,
+        timeout: std::time::Duration,
+    ) -> ConductorApiResult<IntegrationStateDump>;
+
+    /// Report how gossip for a dna's space looked as of its last local sync
+    async fn gossip_status(&self, dna_hash: &DnaHash) -> ConductorApiResult<GossipStatus>;
+
+    /// Pause or resume gossip for a dna's space
+    async fn set_gossip_paused(&self, dna_hash: &DnaHash, paused: bool) -> ConductorApiResult<()>;
+
+    /// Subscribe to the gossip events of a dna's space, such as each
+    /// local sync finishing, for observing gossip in-process
+    async fn subscribe_gossip_events(
+        &self,
+        dna_hash: &DnaHash,
+    ) -> ConductorApiResult<tokio::sync::broadcast::Receiver<GossipEvent>>;
+
+    /// Set the storage arc of an agent in every one of its cells,
+    /// returning each cell with the arc the agent had in it before
+    async fn set_agent_arc(
+        &self,
+        agent_key: &AgentPubKey,
+        arc: DhtArc,
+    ) -> ConductorApiResult<Vec<(CellId, DhtArc)>>;
+
+    /// Fetch an op as it would be fetched for gossip, from whichever
+    /// cell of the dna holds it
+    async fn get_op_data(
+        &self,
+        dna_hash: &DnaHash,
+        op_hash: DhtOpHash,
+    ) -> ConductorApiResult<Option<DhtOp>>;
+
+    /// Dump a metadata summary of the conductor state
+    async fn dump_conductor_state(&self) -> ConductorApiResult<ConductorStateDump>;
+
+    /// Count the integrated ops of every cell belonging to an active app
+    async fn active_cell_op_counts(&self) -> ConductorApiResult<Vec<ActiveCellOpCount>>;
+
+    /// Get the head of a cell's source chain, if anything has been committed
+    async fn get_chain_head(&self, cell_id: &CellId) -> ConductorApiResult<Option<ChainHeadInfo>>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
 
+    /// Access the broadcast Sender for the signals of one cell, which leaves
+    /// out the app interfaces attached for apps the cell isn't part of
+    async fn signal_broadcaster_for_cell(&self, cell_id: &CellId) -> SignalBroadcaster;
+
     /// Get info about an installed App, whether active or inactive
     async fn get_app_info(
         &self,
@@ -282,6 +399,12 @@ pub struct ConductorHandleImpl<DS: DnaStore + 'static> {
     pub(crate) conductor: RwLock<Conductor<DS>>,
     pub(crate) keystore: KeystoreSender,
     pub(crate) holochain_p2p: holochain_p2p::HolochainP2pRef,
+    pub(crate) started_at: std::time::Instant,
+    pub(crate) recent_interface_errors: RecentInterfaceErrors,
+    pub(crate) interface_load: InterfaceLoad,
+    pub(crate) interface_counters: InterfaceCounters,
+    pub(crate) interface_websocket_config: WebsocketConfig,
+    pub(crate) idempotent_responses: IdempotentResponses,
 }
 
 #[async_trait::async_trait]
@@ -315,9 +438,12 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(())
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        config: AppInterfaceConfig,
+    ) -> ConductorResult<u16> {
         let mut lock = self.conductor.write().await;
-        lock.add_app_interface_via_handle(either::Left(port), self.clone())
+        lock.add_app_interface_via_handle(None, config, self.clone())
             .await
     }
 
@@ -357,6 +483,10 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(self.conductor.read().await.dna_store().list())
     }
 
+    async fn list_dna_zome_names(&self) -> ConductorResult<Vec<(DnaHash, Vec<ZomeName>)>> {
+        Ok(self.conductor.read().await.dna_store().list_zome_names())
+    }
+
     async fn get_dna(&self, hash: &DnaHash) -> Option<DnaFile> {
         self.conductor.read().await.dna_store().get(hash)
     }
@@ -416,7 +546,22 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                 respond.respond(Ok(async move { Ok(signature) }.boxed().into()));
             }
             _ => {
-                let cell = self.cell_by_id(cell_id).await?;
+                let cell = match self.cell_by_id(cell_id).await {
+                    Ok(cell) => cell,
+                    Err(e) => {
+                        // tell gossip the op wasn't taken, rather than
+                        // leaving the publish unanswered
+                        if let Publish { respond, .. } = event {
+                            let agent = cell_id.agent_pubkey().clone();
+                            respond.respond(Ok(async move {
+                                Err(holochain_p2p::HolochainP2pError::RoutingAgentError(agent))
+                            }
+                            .boxed()
+                            .into()));
+                        }
+                        return Err(e);
+                    }
+                };
                 cell.handle_holochain_p2p_event(event).await?;
             }
         }
@@ -453,10 +598,38 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.write().await.shutdown()
     }
 
+    async fn drain_interfaces(&self) {
+        self.conductor.write().await.drain_interfaces()
+    }
+
     fn keystore(&self) -> &KeystoreSender {
         &self.keystore
     }
 
+    fn uptime(&self) -> std::time::Duration {
+        self.started_at.elapsed()
+    }
+
+    fn recent_interface_errors(&self) -> &RecentInterfaceErrors {
+        &self.recent_interface_errors
+    }
+
+    fn interface_load(&self) -> &InterfaceLoad {
+        &self.interface_load
+    }
+
+    fn interface_counters(&self) -> &InterfaceCounters {
+        &self.interface_counters
+    }
+
+    fn interface_websocket_config(&self) -> &WebsocketConfig {
+        &self.interface_websocket_config
+    }
+
+    fn idempotent_responses(&self) -> &IdempotentResponses {
+        &self.idempotent_responses
+    }
+
     fn holochain_p2p(&self) -> &holochain_p2p::HolochainP2pRef {
         &self.holochain_p2p
     }
@@ -612,6 +785,17 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         // MD: Should we be doing `Conductor::add_cells()` here? (see below comment)
     }
 
+    async fn validate_app_activation(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>> {
+        self.conductor
+            .read()
+            .await
+            .validate_app_activation(installed_app_id)
+            .await
+    }
+
     async fn deactivate_app(
         &self,
         installed_app_id: InstalledAppId,
@@ -655,14 +839,157 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .await
     }
 
+    async fn list_cell_ids_for_app_interface(
+        &self,
+        interface_id: &AppInterfaceId,
+    ) -> ConductorResult<Vec<CellId>> {
+        self.conductor
+            .read()
+            .await
+            .list_cell_ids_for_app_interface(interface_id)
+            .await
+    }
+
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
         self.conductor.read().await.dump_cell_state(cell_id).await
     }
 
+    async fn dump_cell_state_range(
+        &self,
+        cell_id: &CellId,
+        start: u32,
+        limit: u32,
+    ) -> ConductorApiResult<String> {
+        self.conductor
+            .read()
+            .await
+            .dump_cell_state_range(cell_id, start, limit)
+            .await
+    }
+
+    async fn await_cell_integration(
+        &self,
+        cell_id: &CellId,
+        timeout: std::time::Duration,
+    ) -> ConductorApiResult<IntegrationStateDump> {
+        // How often to look at the cell's limbos while waiting
+        const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+        let env = self.get_cell_env_readonly(cell_id).await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let state = integrate_dht_ops_workflow::dump_state(env.clone())?;
+            if state.validation_limbo == 0 && state.integration_limbo == 0 {
+                return Ok(state);
+            }
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                debug!(?cell_id, ?state, "Timed out awaiting integration");
+                return Ok(state);
+            }
+            tokio::time::sleep(POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    async fn gossip_status(&self, dna_hash: &DnaHash) -> ConductorApiResult<GossipStatus> {
+        Ok(self
+            .holochain_p2p
+            .gossip_status(dna_hash.clone())
+            .await
+            .map_err(ConductorError::from)?)
+    }
+
+    async fn set_gossip_paused(&self, dna_hash: &DnaHash, paused: bool) -> ConductorApiResult<()> {
+        Ok(self
+            .holochain_p2p
+            .set_gossip_paused(dna_hash.clone(), paused)
+            .await
+            .map_err(ConductorError::from)?)
+    }
+
+    async fn subscribe_gossip_events(
+        &self,
+        dna_hash: &DnaHash,
+    ) -> ConductorApiResult<tokio::sync::broadcast::Receiver<GossipEvent>> {
+        Ok(self
+            .holochain_p2p
+            .subscribe_gossip_events(dna_hash.clone())
+            .await
+            .map_err(ConductorError::from)?)
+    }
+
+    async fn set_agent_arc(
+        &self,
+        agent_key: &AgentPubKey,
+        arc: DhtArc,
+    ) -> ConductorApiResult<Vec<(CellId, DhtArc)>> {
+        let cell_ids: Vec<CellId> = self
+            .list_cell_ids()
+            .await?
+            .into_iter()
+            .filter(|cell_id| cell_id.agent_pubkey() == agent_key)
+            .collect();
+        if cell_ids.is_empty() {
+            return Err(ConductorError::AgentHasNoCells(agent_key.clone()).into());
+        }
+        let mut previous_arcs = Vec::with_capacity(cell_ids.len());
+        for cell_id in cell_ids {
+            let previous_arc = self
+                .holochain_p2p
+                .set_agent_arc(cell_id.dna_hash().clone(), agent_key.clone(), arc)
+                .await
+                .map_err(ConductorError::from)?;
+            previous_arcs.push((cell_id, previous_arc));
+        }
+        Ok(previous_arcs)
+    }
+
+    async fn get_op_data(
+        &self,
+        dna_hash: &DnaHash,
+        op_hash: DhtOpHash,
+    ) -> ConductorApiResult<Option<DhtOp>> {
+        let cells = {
+            let conductor = self.conductor.read().await;
+            conductor
+                .list_cell_ids()
+                .await?
+                .into_iter()
+                .filter(|cell_id| cell_id.dna_hash() == dna_hash)
+                .map(|cell_id| conductor.cell_by_id(&cell_id))
+                .collect::<ConductorResult<Vec<_>>>()?
+        };
+        for cell in cells {
+            if let Some(op) = cell.get_op_data(op_hash.clone()).await? {
+                return Ok(Some(op));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn dump_conductor_state(&self) -> ConductorApiResult<ConductorStateDump> {
+        Ok(self.conductor.read().await.dump_conductor_state().await?)
+    }
+
+    async fn active_cell_op_counts(&self) -> ConductorApiResult<Vec<ActiveCellOpCount>> {
+        self.conductor.read().await.active_cell_op_counts().await
+    }
+
+    async fn get_chain_head(&self, cell_id: &CellId) -> ConductorApiResult<Option<ChainHeadInfo>> {
+        self.conductor.read().await.get_chain_head(cell_id)
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.read().await.signal_broadcaster()
     }
 
+    async fn signal_broadcaster_for_cell(&self, cell_id: &CellId) -> SignalBroadcaster {
+        self.conductor
+            .read()
+            .await
+            .signal_broadcaster_for_cell(cell_id)
+    }
+
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
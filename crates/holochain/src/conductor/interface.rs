@@ -8,10 +8,20 @@
 use crate::conductor::api::*;
 use error::InterfaceError;
 use error::InterfaceResult;
+use futures::FutureExt;
+use holochain_conductor_api::config::SignalOverflowPolicy;
+use holochain_types::app::InstalledAppId;
+use holochain_types::prelude::AppSignal;
+use holochain_types::prelude::CellId;
+use holochain_types::signal::AppSignalSeq;
 use holochain_types::signal::Signal;
 use std::convert::TryInto;
+use std::sync::Arc;
 use tokio::sync::broadcast;
+use tokio::sync::mpsc;
+use websocket::SIGNAL_BUFFER_SIZE;
 
+pub mod codec;
 #[allow(missing_docs)]
 pub mod error;
 pub mod websocket;
@@ -23,7 +33,9 @@ pub enum AppInterfaceRuntime {
     /// A websocket app interface
     Websocket {
         /// The channel for this interface to send Signals across
-        signal_tx: broadcast::Sender<Signal>,
+        signal_tx: InterfaceSignalSender,
+        /// If set, only the cells of this app send Signals to this interface
+        installed_app_id: Option<InstalledAppId>,
     },
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -36,11 +48,133 @@ pub enum AppInterfaceRuntime {
 
 impl AppInterfaceRuntime {
     /// Get the signal sender for the interface
-    pub fn signal_tx(&self) -> &broadcast::Sender<Signal> {
+    pub fn signal_tx(&self) -> InterfaceSignalSender {
         match self {
-            Self::Websocket { signal_tx, .. } => signal_tx,
+            Self::Websocket { signal_tx, .. } => signal_tx.clone(),
             #[cfg(any(test, feature = "test_utils"))]
-            Self::Test { signal_tx, .. } => signal_tx,
+            Self::Test { signal_tx, .. } => InterfaceSignalSender::DropOldest(signal_tx.clone()),
+        }
+    }
+
+    /// The app this interface only carries Signals for, if any
+    pub fn installed_app_id(&self) -> Option<&InstalledAppId> {
+        match self {
+            Self::Websocket {
+                installed_app_id, ..
+            } => installed_app_id.as_ref(),
+            #[cfg(any(test, feature = "test_utils"))]
+            Self::Test { .. } => None,
+        }
+    }
+}
+
+/// Sends Signals to every connection of one app interface,
+/// according to the interface's [`SignalOverflowPolicy`]
+#[derive(Clone, Debug)]
+pub enum InterfaceSignalSender {
+    /// Every connection subscribes to one broadcast channel.
+    /// A connection which falls behind loses its oldest signals.
+    DropOldest(broadcast::Sender<Signal>),
+    /// Every connection has its own bounded channel, and sending
+    /// waits until all of them have room.
+    /// The channels are only locked to be copied or changed, never while
+    /// waiting for room, so a connection which stops reading its signals
+    /// holds up sending but not new connections.
+    Block(Arc<parking_lot::Mutex<Vec<mpsc::Sender<Signal>>>>),
+}
+
+impl InterfaceSignalSender {
+    /// A sender with no connections yet
+    pub fn new(policy: SignalOverflowPolicy) -> Self {
+        match policy {
+            SignalOverflowPolicy::DropOldest => {
+                // This receiver is thrown away because we can produce infinite new
+                // receivers from the Sender
+                let (signal_tx, _r) = broadcast::channel(SIGNAL_BUFFER_SIZE);
+                Self::DropOldest(signal_tx)
+            }
+            SignalOverflowPolicy::Block => Self::Block(Default::default()),
+        }
+    }
+
+    /// Add a connection, which receives every signal sent from now on
+    pub fn subscribe(&self) -> InterfaceSignalReceiver {
+        match self {
+            Self::DropOldest(signal_tx) => {
+                InterfaceSignalReceiver::DropOldest(signal_tx.subscribe())
+            }
+            Self::Block(signal_txs) => {
+                let (signal_tx, signal_rx) = mpsc::channel(SIGNAL_BUFFER_SIZE);
+                let mut signal_txs = signal_txs.lock();
+                // forget connections which have gone away, which otherwise
+                // only happens when a signal is sent
+                signal_txs.retain(|signal_tx| signal_tx.closed().now_or_never().is_none());
+                signal_txs.push(signal_tx);
+                InterfaceSignalReceiver::Block(signal_rx)
+            }
+        }
+    }
+
+    /// Whether any connection would receive a signal sent now.
+    pub fn has_subscribers(&self) -> bool {
+        match self {
+            Self::DropOldest(signal_tx) => signal_tx.receiver_count() > 0,
+            Self::Block(signal_txs) => signal_txs
+                .lock()
+                .iter()
+                .any(|signal_tx| signal_tx.closed().now_or_never().is_none()),
+        }
+    }
+
+    /// Send a signal to every connection.
+    /// With [`SignalOverflowPolicy::Block`] this waits for room on every
+    /// connection, which holds up the caller but not new connections.
+    pub async fn send(&self, sig: Signal) -> InterfaceResult<()> {
+        match self {
+            Self::DropOldest(signal_tx) => {
+                signal_tx.send(sig).map_err(InterfaceError::SignalSend)?;
+            }
+            Self::Block(signal_txs) => {
+                let open = signal_txs.lock().clone();
+                let mut any_closed = false;
+                for signal_tx in open {
+                    any_closed |= signal_tx.send(sig.clone()).await.is_err();
+                }
+                if any_closed {
+                    // forget connections which have gone away
+                    signal_txs
+                        .lock()
+                        .retain(|signal_tx| signal_tx.closed().now_or_never().is_none());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The receiving end of one connection's Signals
+#[derive(Debug)]
+pub enum InterfaceSignalReceiver {
+    /// See [`InterfaceSignalSender::DropOldest`]
+    DropOldest(broadcast::Receiver<Signal>),
+    /// See [`InterfaceSignalSender::Block`]
+    Block(mpsc::Receiver<Signal>),
+}
+
+impl InterfaceSignalReceiver {
+    /// Receive the next signal, or `None` once no more can be sent
+    pub async fn recv(&mut self) -> Option<Signal> {
+        match self {
+            Self::DropOldest(signal_rx) => loop {
+                match signal_rx.recv().await {
+                    Ok(sig) => return Some(sig),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Connection fell behind, dropped oldest signals");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+            Self::Block(signal_rx) => signal_rx.recv().await,
         }
     }
 }
@@ -49,39 +183,81 @@ impl AppInterfaceRuntime {
 /// There is one Sender per attached Interface
 #[derive(Clone, Debug)]
 pub struct SignalBroadcaster {
-    senders: Vec<broadcast::Sender<Signal>>,
+    senders: Vec<InterfaceSignalSender>,
+    app_signal_seq: AppSignalCounter,
 }
 
 impl SignalBroadcaster {
     /// send the signal to the connected client
-    pub fn send(&mut self, sig: Signal) -> InterfaceResult<()> {
-        self.senders
-            .iter_mut()
-            .map(|tx| tx.send(sig.clone()))
-            .collect::<Result<Vec<_>, broadcast::error::SendError<Signal>>>()
-            .map_err(InterfaceError::SignalSend)?;
+    pub async fn send(&mut self, sig: Signal) -> InterfaceResult<()> {
+        // interfaces nobody is connected to are skipped
+        for tx in self.senders.iter().filter(|tx| tx.has_subscribers()) {
+            tx.send(sig.clone()).await?;
+        }
         Ok(())
     }
 
+    /// Whether any connection of any interface would receive a signal
+    /// sent now. When this is false there's no need to build a signal at all.
+    pub fn has_subscribers(&self) -> bool {
+        self.senders.iter().any(|tx| tx.has_subscribers())
+    }
+
+    /// send an app signal from `cell_id` to the connected clients,
+    /// numbered after the last one sent with the same [`AppSignalCounter`].
+    /// Numbering and sending happen together, so signals sent concurrently
+    /// still reach every interface in the order they were numbered.
+    /// A signal which no connection would receive is dropped unnumbered.
+    pub async fn send_app_signal(
+        &mut self,
+        cell_id: CellId,
+        signal: AppSignal,
+    ) -> InterfaceResult<()> {
+        let mut seq = self.app_signal_seq.0.lock().await;
+        if !self.has_subscribers() {
+            return Ok(());
+        }
+        let sig = Signal::App(cell_id, signal, *seq);
+        *seq = seq.next();
+        self.send(sig).await
+    }
+
     /// internal constructor
-    pub fn new(senders: Vec<broadcast::Sender<Signal>>) -> Self {
-        Self { senders }
+    pub fn new(senders: Vec<InterfaceSignalSender>) -> Self {
+        Self {
+            senders,
+            app_signal_seq: AppSignalCounter::default(),
+        }
+    }
+
+    /// number app signals with `app_signal_seq`,
+    /// which the broadcasters of one cell share
+    pub fn with_app_signal_seq(self, app_signal_seq: AppSignalCounter) -> Self {
+        Self {
+            app_signal_seq,
+            ..self
+        }
     }
 
     #[cfg(test)]
     /// A sender with nothing to send to. A placeholder for tests
     pub fn noop() -> Self {
-        Self {
-            senders: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
     #[cfg(any(test, feature = "test_utils"))]
-    /// Get a list of Signal receivers, one per sender (per interface)
+    /// Get a list of Signal receivers, one per sender (per interface),
+    /// leaving out interfaces which block on overflow
     // NB: this could become more useful by giving identifiers to interfaces
     //     a returning a HashMap instead of a Vec
     pub fn subscribe_separately(&self) -> Vec<broadcast::Receiver<Signal>> {
-        self.senders.iter().map(|s| s.subscribe()).collect()
+        self.senders
+            .iter()
+            .filter_map(|s| match s {
+                InterfaceSignalSender::DropOldest(s) => Some(s.subscribe()),
+                InterfaceSignalSender::Block(_) => None,
+            })
+            .collect()
     }
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -98,4 +274,15 @@ impl SignalBroadcaster {
     }
 }
 
+/// Counts the app signals sent by one Cell, see [`AppSignalSeq`].
+/// Clones count together, a new counter starts a new numbering.
+#[derive(Clone, Debug)]
+pub struct AppSignalCounter(Arc<tokio::sync::Mutex<AppSignalSeq>>);
+
+impl Default for AppSignalCounter {
+    fn default() -> Self {
+        Self(Arc::new(tokio::sync::Mutex::new(AppSignalSeq::start())))
+    }
+}
+
 pub use holochain_conductor_api::config::InterfaceDriver;
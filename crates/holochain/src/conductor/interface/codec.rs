@@ -0,0 +1,169 @@
+//! Codecs for the contents of the messages sent over an interface.
+//!
+//! Each connection picks its codec from the websocket subprotocol it
+//! negotiated, so the request handling itself never needs to know how
+//! the messages were encoded.
+
+use holochain_conductor_api::ADMIN_SUBPROTOCOL_V1_JSON;
+use holochain_conductor_api::APP_SUBPROTOCOL_V1_JSON;
+use holochain_conductor_api::APP_SUBPROTOCOL_V2;
+use holochain_conductor_api::APP_SUBPROTOCOL_V2_JSON;
+use holochain_serialized_bytes::prelude::*;
+use serde::de::DeserializeOwned;
+
+/// Encodes and decodes the contents of interface messages.
+pub trait WireCodec: 'static + Send + Sync {
+    /// Decode a message received from a client
+    fn decode<T: DeserializeOwned>(bytes: SerializedBytes) -> Result<T, SerializedBytesError>;
+
+    /// Encode a message to send to a client
+    fn encode<T: Serialize>(value: &T) -> Result<SerializedBytes, SerializedBytesError>;
+}
+
+/// The default codec, encoding messages as MessagePack
+pub struct MsgPackCodec;
+
+impl WireCodec for MsgPackCodec {
+    fn decode<T: DeserializeOwned>(bytes: SerializedBytes) -> Result<T, SerializedBytesError> {
+        holochain_serialized_bytes::decode(bytes.bytes())
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Result<SerializedBytes, SerializedBytesError> {
+        Ok(SerializedBytes::from(UnsafeBytes::from(
+            holochain_serialized_bytes::encode(value)?,
+        )))
+    }
+}
+
+/// Encodes messages as JSON, for clients that negotiated one of the
+/// `+json` subprotocols
+pub struct JsonCodec;
+
+impl WireCodec for JsonCodec {
+    fn decode<T: DeserializeOwned>(bytes: SerializedBytes) -> Result<T, SerializedBytesError> {
+        serde_json::from_slice(bytes.bytes())
+            .map_err(|e| SerializedBytesError::Deserialize(e.to_string()))
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Result<SerializedBytes, SerializedBytesError> {
+        Ok(SerializedBytes::from(UnsafeBytes::from(
+            serde_json::to_vec(value)
+                .map_err(|e| SerializedBytesError::Serialize(e.to_string()))?,
+        )))
+    }
+}
+
+/// Whether a connection that negotiated `subprotocol` speaks JSON
+pub fn is_json_subprotocol(subprotocol: Option<&str>) -> bool {
+    matches!(
+        subprotocol,
+        Some(ADMIN_SUBPROTOCOL_V1_JSON)
+            | Some(APP_SUBPROTOCOL_V1_JSON)
+            | Some(APP_SUBPROTOCOL_V2_JSON)
+    )
+}
+
+/// Whether a connection that negotiated `subprotocol` is sent the sequence
+/// numbers of app signals. Older clients can't decode them.
+pub fn sends_signal_seq(subprotocol: Option<&str>) -> bool {
+    matches!(
+        subprotocol,
+        Some(APP_SUBPROTOCOL_V2) | Some(APP_SUBPROTOCOL_V2_JSON)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::api::error::ExternalApiWireError;
+    use crate::conductor::api::error::SerializationError;
+    use holochain_conductor_api::AdminRequest;
+    use holochain_conductor_api::AdminResponse;
+    use holochain_conductor_api::AppRequest;
+    use matches::assert_matches;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use rand::SeedableRng;
+    use std::convert::TryFrom;
+
+    /// How many random frames each run of the seeded harness tries
+    const RANDOM_FRAMES: usize = 2000;
+
+    #[test]
+    fn msgpack_codec_matches_serialized_bytes() {
+        let request = AdminRequest::ListDnas;
+        let bytes = MsgPackCodec::encode(&request).unwrap();
+        assert_eq!(bytes, SerializedBytes::try_from(request).unwrap());
+        assert_matches!(
+            MsgPackCodec::decode::<AdminRequest>(bytes),
+            Ok(AdminRequest::ListDnas)
+        );
+    }
+
+    #[test]
+    fn json_codec_roundtrip() {
+        let bytes = JsonCodec::encode(&AdminResponse::AppsValidated).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(bytes.bytes()).unwrap();
+        assert_eq!(json["type"], "apps_validated");
+        assert_matches!(
+            JsonCodec::decode::<AdminResponse>(bytes),
+            Ok(AdminResponse::AppsValidated)
+        );
+
+        let bad = SerializedBytes::from(UnsafeBytes::from(b"not json".to_vec()));
+        assert_matches!(
+            JsonCodec::decode::<AdminRequest>(bad),
+            Err(SerializedBytesError::Deserialize(_))
+        );
+    }
+
+    /// Check that `frame` is rejected by every way a request is decoded,
+    /// with an error that reaches the client as a deserialization error
+    fn assert_rejected(frame: &[u8]) {
+        let bytes = || SerializedBytes::from(UnsafeBytes::from(frame.to_vec()));
+        let errors = vec![
+            MsgPackCodec::decode::<AdminRequest>(bytes()).map(|_| ()),
+            MsgPackCodec::decode::<AppRequest>(bytes()).map(|_| ()),
+            JsonCodec::decode::<AdminRequest>(bytes()).map(|_| ()),
+            JsonCodec::decode::<AppRequest>(bytes()).map(|_| ()),
+            AdminRequest::try_from(bytes()).map(|_| ()),
+            AppRequest::try_from(bytes()).map(|_| ()),
+        ];
+        for (i, result) in errors.into_iter().enumerate() {
+            let e = result.expect_err(&format!("decode {} accepted {:?}", i, frame));
+            assert_matches!(
+                ExternalApiWireError::from(SerializationError::from(e)),
+                ExternalApiWireError::Deserialization(_)
+            );
+        }
+    }
+
+    #[test]
+    fn random_frames_are_rejected_cleanly() {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..RANDOM_FRAMES {
+            let len = rng.gen_range(0, 256);
+            let frame: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            assert_rejected(&frame);
+        }
+    }
+
+    #[test]
+    fn truncated_frames_are_rejected_cleanly() {
+        let app_request = AppRequest::ChainHead {
+            cell_id: holochain_types::test_utils::fake_cell_id(1),
+        };
+        let frames = vec![
+            MsgPackCodec::encode(&AdminRequest::ListDnas).unwrap(),
+            JsonCodec::encode(&AdminRequest::ListDnas).unwrap(),
+            MsgPackCodec::encode(&app_request).unwrap(),
+            JsonCodec::encode(&app_request).unwrap(),
+        ];
+        for frame in frames {
+            let frame = frame.bytes();
+            for len in 0..frame.len() {
+                assert_rejected(&frame[..len]);
+            }
+        }
+    }
+}
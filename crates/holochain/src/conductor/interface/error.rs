@@ -27,6 +27,58 @@ pub enum InterfaceError {
     WebsocketError(#[from] holochain_websocket::WebsocketError),
     #[error("Failed to find free port")]
     PortError,
+    #[error("Port {port} is already in use")]
+    PortInUse { port: u16 },
+    #[error("No message received for {0:?}")]
+    IdleTimeout(std::time::Duration),
+    #[error("Connection has been open for its maximum lifetime of {0:?}")]
+    MaxLifetime(std::time::Duration),
+    #[error("The interface is shutting down")]
+    ShuttingDown,
+    #[error("The interface has too many connections")]
+    Overloaded,
+}
+
+/// Close code for a connection ending normally.
+const CLOSE_NORMAL: u16 = 1000;
+/// Close code for a connection ending because the conductor is going away.
+const CLOSE_GOING_AWAY: u16 = 1001;
+/// Close code for a connection ending because of an unexpected condition.
+const CLOSE_INTERNAL_ERROR: u16 = 1011;
+/// Close code for a connection refused because the server is overloaded.
+const CLOSE_TRY_AGAIN_LATER: u16 = 1013;
+
+impl InterfaceError {
+    /// The websocket close code and category to report to the client
+    /// when an interface connection ends because of this error.
+    pub fn close_reason(&self) -> (u16, &'static str) {
+        match self {
+            InterfaceError::RequestHandler(e) if matches!(**e, ConductorError::ShuttingDown) => {
+                (CLOSE_GOING_AWAY, "shutting_down")
+            }
+            InterfaceError::RequestHandler(_) => (CLOSE_INTERNAL_ERROR, "request_handler_error"),
+            InterfaceError::SerializedBytes(_) => (CLOSE_INTERNAL_ERROR, "serialization_error"),
+            InterfaceError::Closed
+            | InterfaceError::SendError
+            | InterfaceError::WebsocketError(_) => (CLOSE_GOING_AWAY, "connection_closed"),
+            InterfaceError::IdleTimeout(_) => (CLOSE_NORMAL, "idle_timeout"),
+            InterfaceError::MaxLifetime(_) => (CLOSE_NORMAL, "max_lifetime"),
+            InterfaceError::ShuttingDown => (CLOSE_GOING_AWAY, "shutting_down"),
+            InterfaceError::Overloaded => (CLOSE_TRY_AGAIN_LATER, "overloaded"),
+            _ => (CLOSE_INTERNAL_ERROR, "internal_error"),
+        }
+    }
+
+    /// Whether a client whose connection ended because of this error
+    /// should wait a while before reconnecting, rather than adding to
+    /// a crowd of clients all reconnecting at once.
+    pub fn suggests_reconnect_backoff(&self) -> bool {
+        match self {
+            InterfaceError::RequestHandler(e) => matches!(**e, ConductorError::ShuttingDown),
+            InterfaceError::ShuttingDown | InterfaceError::Overloaded => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<String> for InterfaceError {
@@ -1,190 +1,1287 @@
 //! Module for establishing Websocket-based Interfaces,
 //! i.e. those configured with `InterfaceDriver::Websocket`
 
+use super::codec::is_json_subprotocol;
+use super::codec::sends_signal_seq;
+use super::codec::JsonCodec;
+use super::codec::MsgPackCodec;
+use super::codec::WireCodec;
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
-use crate::conductor::manager::ManagedTaskHandle;
-use crate::conductor::manager::ManagedTaskResult;
+use crate::conductor::manager::InterfaceTaskExit;
+use crate::conductor::manager::InterfaceTaskHandle;
+use crate::conductor::manager::InterfaceTaskResult;
+use crate::conductor::state::AppInterfaceConfig;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::FutureExt;
+use holochain_conductor_api::signal_subscription::SignalKind;
+use holochain_conductor_api::signal_subscription::SignalSubscription;
+use holochain_conductor_api::AdminInterfaceConfig;
+use holochain_conductor_api::AdminRequest;
+use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::ExternalApiWireError;
+use holochain_conductor_api::InterfaceErrorInfo;
+use holochain_conductor_api::RateLimitConfig;
+use holochain_conductor_api::SignalEncodeFailurePolicy;
+use holochain_conductor_api::ZomeCallId;
+use holochain_conductor_api::ADMIN_SUBPROTOCOL_V1;
+use holochain_conductor_api::ADMIN_SUBPROTOCOL_V1_JSON;
+use holochain_conductor_api::APP_SUBPROTOCOL_V1;
+use holochain_conductor_api::APP_SUBPROTOCOL_V1_JSON;
+use holochain_conductor_api::APP_SUBPROTOCOL_V2;
+use holochain_conductor_api::APP_SUBPROTOCOL_V2_JSON;
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::SerializedBytesError;
 use holochain_types::signal::Signal;
+use holochain_types::signal::SignalV1;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::Respond;
 use holochain_websocket::WebsocketConfig;
+use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
 use holochain_websocket::WebsocketSender;
-use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
 
 use std::sync::atomic::AtomicIsize;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
 use tracing::*;
 use url2::url2;
+use url2::Url2;
 
 // TODO: This is arbitrary, choose reasonable size.
 /// Number of signals in buffer before applying
 /// back pressure.
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: isize = 400;
+/// Number of times to try re-binding the admin listener
+/// if it unexpectedly stops yielding connections.
+const MAX_REBIND_ATTEMPTS: usize = 3;
+/// Time to wait before each attempt to re-bind the admin listener.
+const REBIND_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+/// Total time an interface waits for all of its connections to finish
+/// once it has been asked to stop.
+const SHUTDOWN_DEADLINE: std::time::Duration = std::time::Duration::from_secs(1);
+/// How long a draining app connection keeps answering the zome calls
+/// it is already handling before it closes regardless.
+const DRAIN_DEADLINE: std::time::Duration = std::time::Duration::from_secs(5);
+/// How long clients are asked to wait before reconnecting after a shutdown
+/// or overload close, unless the interface is configured otherwise.
+const DEFAULT_RECONNECT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+/// How many interface errors are kept for `AdminRequest::RecentErrors`,
+/// unless the conductor is configured otherwise.
+pub const DEFAULT_RECENT_INTERFACE_ERRORS: usize = 100;
 
-/// Create a WebsocketListener to be used in interfaces
+/// The kind of interface a connection was made to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceKind {
+    /// An admin interface
+    Admin,
+    /// An app interface
+    App,
+}
+
+impl InterfaceKind {
+    /// The name of this kind of interface, as given in [`InterfaceErrorInfo`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            InterfaceKind::Admin => "admin",
+            InterfaceKind::App => "app",
+        }
+    }
+}
+
+/// What happened to a connection, as reported to a [`ConnectionHook`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// The interface accepted the connection and started handling it
+    Accepted,
+    /// The connection has finished, for whatever reason
+    Closed,
+    /// A client connected but the websocket handshake failed,
+    /// so the connection was never accepted
+    HandshakeFailed,
+    /// A signal of this kind couldn't be serialized for the connection,
+    /// which usually means the cell that emitted it has a bug.
+    /// Whether the connection is then closed depends on the
+    /// interface's [`SignalEncodeFailurePolicy`].
+    SignalEncodeFailed(SignalKind),
+    /// The interface's listener stopped and couldn't be brought back,
+    /// so it accepts no more connections.
+    /// Reported with the address the listener was bound to
+    /// rather than a remote address.
+    ListenerDied,
+}
+
+/// Called with the kind of interface and the remote address of each
+/// connection an interface accepts, and again once that connection closes,
+/// so embedders can keep their own metrics or audit log of connections.
+/// Signals which fail to serialize are reported too, so embedders can
+/// count them per [`SignalKind`].
+/// An admin interface losing its listener for good is reported too,
+/// since it can leave the conductor unmanageable.
+/// It is called on the interface's tasks so should return quickly.
+pub type ConnectionHook = Arc<dyn Fn(InterfaceKind, &str, ConnectionEvent) + Send + Sync>;
+
+/// Create a WebsocketListener to be used in interfaces,
+/// bound to `addr` which may be IPv4 or IPv6.
+/// The transport is tuned by `websocket_config`, `WebsocketConfig::default()`
+/// suits most interfaces. Its subprotocols are replaced by the admin ones.
 pub async fn spawn_websocket_listener(
-    port: u16,
+    addr: SocketAddr,
+    websocket_config: WebsocketConfig,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
     trace!("Initializing Admin interface");
     let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        websocket_url(addr),
+        Arc::new(
+            websocket_config.subprotocols(vec![ADMIN_SUBPROTOCOL_V1, ADMIN_SUBPROTOCOL_V1_JSON]),
+        ),
     )
-    .await?;
+    .await
+    .map_err(|e| bind_error(addr, e))?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// How an admin interface treats each of its connections
+#[derive(Clone, Debug, Default)]
+pub struct AdminInterfaceOptions {
+    /// If set, any request whose `type` isn't in it is rejected with
+    /// [`ExternalApiWireError::Forbidden`]
+    pub allowed_requests: Option<Vec<String>>,
+    /// If set, each connection's requests are limited by it
+    pub rate_limit: Option<RateLimitConfig>,
+    /// If set, connections that send nothing for that long are closed
+    pub idle_timeout: Option<std::time::Duration>,
+    /// If set, connections open for that long are closed,
+    /// asking the client to reconnect
+    pub max_connection_lifetime: Option<std::time::Duration>,
+    /// How long clients closed because of shutdown or overload are asked
+    /// to wait before reconnecting, see [`close_reason_text`].
+    /// `None` suggests 1 second.
+    pub reconnect_backoff: Option<std::time::Duration>,
+    /// If set, identical read-only requests handled at the same time on any
+    /// of the interface's connections share one response
+    pub coalesce_requests: bool,
+}
+
+impl From<&AdminInterfaceConfig> for AdminInterfaceOptions {
+    fn from(config: &AdminInterfaceConfig) -> Self {
+        Self {
+            allowed_requests: config.allowed_requests.clone(),
+            rate_limit: config.rate_limit,
+            idle_timeout: config.idle_timeout_ms.map(std::time::Duration::from_millis),
+            max_connection_lifetime: config
+                .max_connection_lifetime_ms
+                .map(std::time::Duration::from_millis),
+            reconnect_backoff: config
+                .reconnect_backoff_ms
+                .map(std::time::Duration::from_millis),
+            coalesce_requests: config.coalesce_requests,
+        }
+    }
+}
+
+/// What each connection to an admin interface is handled with,
+/// shared between the interface's connections
+#[derive(Clone)]
+struct AdminConnectionSettings {
+    allowed_requests: Option<Arc<HashSet<String>>>,
+    inflight: Option<InflightRequests>,
+    rate_limit: Option<RateLimitConfig>,
+    idle_timeout: Option<std::time::Duration>,
+    max_lifetime: Option<std::time::Duration>,
+    reconnect_backoff: std::time::Duration,
+}
+
+impl From<AdminInterfaceOptions> for AdminConnectionSettings {
+    fn from(options: AdminInterfaceOptions) -> Self {
+        let inflight = if options.coalesce_requests {
+            Some(InflightRequests::default())
+        } else {
+            None
+        };
+        Self {
+            allowed_requests: options
+                .allowed_requests
+                .map(|r| Arc::new(r.into_iter().collect())),
+            inflight,
+            rate_limit: options.rate_limit,
+            idle_timeout: options.idle_timeout,
+            max_lifetime: options.max_connection_lifetime,
+            reconnect_backoff: options
+                .reconnect_backoff
+                .unwrap_or(DEFAULT_RECONNECT_BACKOFF),
+        }
+    }
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
-pub fn spawn_admin_interface_task<A: InterfaceApi>(
+/// from the external client.
+/// Each connection is handled according to `options`.
+/// If the listener stops, it is re-bound with `context`'s websocket config,
+/// which should be what it was bound with by [`spawn_websocket_listener`].
+/// Either `drain_rx` or `stop_rx` firing stops the interface the same way,
+/// the task's exit saying which it was, so interfaces can be drained
+/// before the rest of the conductor is stopped.
+pub fn spawn_admin_interface_task<A>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
+    options: AdminInterfaceOptions,
+    context: InterfaceContext,
+    mut drain_rx: StopReceiver,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<ManagedTaskHandle> {
+) -> InterfaceResult<InterfaceTaskHandle>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
+    let InterfaceContext {
+        websocket_config,
+        connection_hook,
+        recent_errors,
+        mut load,
+    } = context;
+    let settings = AdminConnectionSettings::from(options);
     Ok(tokio::task::spawn(async move {
-        // Task that will kill the listener and all child connections.
-        tokio::task::spawn(
-            handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
-        );
-
+        let addr = listener_socket_addr(&handle);
+        let mut handle = handle;
+        let mut listener: futures::stream::BoxStream<'static, ListenerItem> = Box::pin(listener);
         let num_connections = Arc::new(AtomicIsize::new(0));
-        futures::pin_mut!(listener);
-        // establish a new connection to a client
-        while let Some(connection) = listener.next().await {
-            match connection {
-                Ok((_, rx_from_iface)) => {
-                    if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
-                        // Max connections so drop this connection
-                        // which will close it.
-                        continue;
-                    };
-                    tokio::task::spawn(recv_incoming_admin_msgs(
-                        api.clone(),
-                        rx_from_iface,
-                        num_connections.clone(),
-                    ));
+        let mut connections =
+            Connections::new(InterfaceKind::Admin, connection_hook, recent_errors);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let mut paused = false;
+        let exit = loop {
+            let overloaded = accept_paused(InterfaceKind::Admin, &load, &mut paused);
+            tokio::select! {
+                // Close all child connections, then the listener.
+                _ = stop_rx.recv() => {
+                    shutdown_tx.send(true).ok();
+                    break InterfaceTaskExit::Stopped;
+                }
+                // Stop accepting and close child connections once they have
+                // answered what they are handling, leaving the rest of the
+                // conductor running.
+                _ = drain_rx.recv() => {
+                    shutdown_tx.send(true).ok();
+                    break InterfaceTaskExit::Drained;
                 }
-                Err(err) => {
-                    warn!("Admin socket connection failed: {}", err);
+                // forget connections that have finished
+                Some(id) = connections.tasks.next() => {
+                    connections.finished(id);
+                }
+                // check again whether to accept once a request is answered
+                _ = load_answered(&mut load), if overloaded => {}
+                // establish a new connection to a client
+                connection = listener.next(), if !overloaded => match connection {
+                    Some(Ok((mut tx_to_iface, rx_from_iface))) => {
+                        let remote_addr = rx_from_iface.client_addr();
+                        let reporter = connections.reporter(&remote_addr);
+                        if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
+                            // Max connections so close this connection,
+                            // asking the client to come back later.
+                            reporter.error(&InterfaceError::Overloaded);
+                            close_on_error(
+                                &mut tx_to_iface,
+                                &InterfaceError::Overloaded,
+                                settings.reconnect_backoff,
+                            ).await;
+                            num_connections.fetch_sub(1, Ordering::Relaxed);
+                            continue;
+                        };
+                        if is_json_subprotocol(rx_from_iface.subprotocol()) {
+                            connections.spawn(remote_addr, recv_incoming_admin_msgs::<_, JsonCodec>(
+                                api.clone(),
+                                settings.clone(),
+                                tx_to_iface,
+                                rx_from_iface,
+                                shutdown_rx.clone(),
+                                num_connections.clone(),
+                                reporter,
+                            ));
+                        } else {
+                            connections.spawn(remote_addr, recv_incoming_admin_msgs::<_, MsgPackCodec>(
+                                api.clone(),
+                                settings.clone(),
+                                tx_to_iface,
+                                rx_from_iface,
+                                shutdown_rx.clone(),
+                                num_connections.clone(),
+                                reporter,
+                            ));
+                        }
+                    }
+                    Some(Err(err)) => connections.connection_failed(err),
+                    None => {
+                        // This shouldn't happen while we haven't been asked to stop,
+                        // but if it does, try to get the interface back
+                        // rather than leaving the conductor unmanageable.
+                        warn!(?addr, "Admin listener has returned none");
+                        match rebind_admin_listener(addr, &websocket_config).await {
+                            Some((new_handle, new_listener)) => {
+                                handle = new_handle;
+                                listener = Box::pin(new_listener);
+                            }
+                            None => {
+                                error!(
+                                    ?addr,
+                                    "Giving up on admin interface, could not re-bind listener"
+                                );
+                                connections.listener_died(addr);
+                                // close the clients still connected rather
+                                // than leaving them to be dropped
+                                shutdown_tx.send(true).ok();
+                                break InterfaceTaskExit::ListenerClosed;
+                            }
+                        }
+                    }
                 }
             }
-        }
-        ManagedTaskResult::Ok(())
+        };
+        connections.join(SHUTDOWN_DEADLINE).await;
+        handle.close();
+        InterfaceTaskResult::Ok(exit)
     }))
 }
 
+/// Try to bind a fresh admin listener on the same address,
+/// backing off between attempts.
+async fn rebind_admin_listener(
+    addr: Option<SocketAddr>,
+    websocket_config: &WebsocketConfig,
+) -> Option<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    let addr = addr?;
+    for attempt in 1..=MAX_REBIND_ATTEMPTS {
+        tokio::time::sleep(REBIND_BACKOFF).await;
+        match spawn_websocket_listener(addr, websocket_config.clone()).await {
+            Ok(listener) => {
+                info!(%addr, attempt, "Re-bound admin listener");
+                return Some(listener);
+            }
+            Err(e) => {
+                warn!(%addr, attempt, error = ?e, "Failed to re-bind admin listener");
+            }
+        }
+    }
+    None
+}
+
+/// Whether a `kind` interface should stop accepting connections
+/// because `load` is overloaded, logging when it stops and starts again.
+/// `paused` is whether it had stopped as of the last check.
+fn accept_paused(kind: InterfaceKind, load: &Option<InterfaceLoad>, paused: &mut bool) -> bool {
+    let overloaded = load.as_ref().map_or(false, InterfaceLoad::is_overloaded);
+    if overloaded != *paused {
+        let pending = load.as_ref().map_or(0, InterfaceLoad::pending);
+        if overloaded {
+            warn!(?kind, pending, "Overloaded, not accepting connections");
+        } else {
+            info!(?kind, pending, "Accepting connections again");
+        }
+        *paused = overloaded;
+    }
+    overloaded
+}
+
+/// Resolves once a request counted by `load` is answered,
+/// never if there is no `load`
+async fn load_answered(load: &mut Option<InterfaceLoad>) {
+    match load {
+        Some(load) => load.answered().await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// The websocket url to bind or connect to for `addr`.
+/// IPv6 addresses are written in brackets, e.g. `ws://[::1]:1234`.
+pub fn websocket_url(addr: SocketAddr) -> Url2 {
+    url2!("ws://{}", addr)
+}
+
+/// The error for failing to bind a listener to `addr`.
+/// An address already in use gets its own error, naming the port,
+/// so it can be reported to the client plainly.
+fn bind_error(addr: SocketAddr, e: WebsocketError) -> InterfaceError {
+    match e {
+        WebsocketError::Io(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            InterfaceError::PortInUse { port: addr.port() }
+        }
+        e => e.into(),
+    }
+}
+
+/// The socket address a listener ended up bound to.
+fn listener_socket_addr(handle: &ListenerHandle) -> Option<SocketAddr> {
+    let url = handle.local_addr();
+    // the host of an IPv6 url keeps its brackets, which is what
+    // parsing a socket address expects
+    format!("{}:{}", url.host_str()?, url.port()?).parse().ok()
+}
+
+/// The connection tasks spawned by an interface, so they can be waited on
+/// together when the interface stops.
+struct Connections {
+    kind: InterfaceKind,
+    hook: Option<ConnectionHook>,
+    recent_errors: Option<RecentInterfaceErrors>,
+    next_id: usize,
+    /// Remote address of each connection that hasn't finished yet
+    remote_addrs: HashMap<usize, String>,
+    /// Resolves to the id of each connection as it finishes
+    tasks: FuturesUnordered<BoxFuture<'static, usize>>,
+}
+
+impl Connections {
+    fn new(
+        kind: InterfaceKind,
+        hook: Option<ConnectionHook>,
+        recent_errors: Option<RecentInterfaceErrors>,
+    ) -> Self {
+        Self {
+            kind,
+            hook,
+            recent_errors,
+            next_id: 0,
+            remote_addrs: HashMap::new(),
+            tasks: FuturesUnordered::new(),
+        }
+    }
+
+    fn spawn<F>(&mut self, remote_addr: String, connection: F)
+    where
+        F: std::future::Future<Output = InterfaceResult<()>> + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let kind = self.kind;
+        let hook = self.hook.clone();
+        let recent_errors = self.recent_errors.clone();
+        if let Some(hook) = &hook {
+            hook(kind, &remote_addr, ConnectionEvent::Accepted);
+        }
+        self.remote_addrs.insert(id, remote_addr.clone());
+        // report the close from the task itself, so connections still
+        // running past the shutdown deadline are reported when they finish
+        let task = tokio::task::spawn(async move {
+            match connection.await {
+                Ok(()) => debug!(?kind, %remote_addr, "Connection closed"),
+                // the connection ran its course, nothing went wrong
+                Err(e @ InterfaceError::ShuttingDown) | Err(e @ InterfaceError::IdleTimeout(_)) => {
+                    debug!(?kind, %remote_addr, reason = %e, "Connection closed")
+                }
+                Err(e) => {
+                    warn!(?kind, %remote_addr, error = ?e, "Connection ended with an error");
+                    if let Some(recent_errors) = &recent_errors {
+                        recent_errors.record(kind, &remote_addr, &e);
+                    }
+                }
+            }
+            if let Some(hook) = hook {
+                hook(kind, &remote_addr, ConnectionEvent::Closed);
+            }
+        });
+        self.tasks.push(task.map(move |_| id).boxed());
+    }
+
+    /// Reports events on the connection from `remote_addr` to the hook
+    fn reporter(&self, remote_addr: &str) -> ConnectionReporter {
+        ConnectionReporter {
+            kind: self.kind,
+            remote_addr: remote_addr.to_string(),
+            hook: self.hook.clone(),
+            recent_errors: self.recent_errors.clone(),
+        }
+    }
+
+    /// Report a connection the listener failed to set up.
+    /// A failed handshake is down to that one client, anything else
+    /// is a problem with the listening socket itself.
+    fn connection_failed(&self, err: WebsocketError) {
+        let kind = self.kind;
+        match err {
+            WebsocketError::Handshake {
+                remote_addr,
+                reason,
+            } => {
+                info!(?kind, %remote_addr, %reason, "Websocket handshake failed");
+                if let Some(hook) = &self.hook {
+                    hook(kind, &remote_addr, ConnectionEvent::HandshakeFailed);
+                }
+                if let Some(recent_errors) = &self.recent_errors {
+                    recent_errors.record(
+                        kind,
+                        &remote_addr,
+                        format!("Websocket handshake failed: {}", reason),
+                    );
+                }
+            }
+            err => warn!(?kind, error = ?err, "Failed to accept a connection"),
+        }
+    }
+
+    /// Report that the listener bound to `addr` has stopped for good
+    fn listener_died(&self, addr: Option<SocketAddr>) {
+        let addr = addr.map(|addr| addr.to_string()).unwrap_or_default();
+        if let Some(hook) = &self.hook {
+            hook(self.kind, &addr, ConnectionEvent::ListenerDied);
+        }
+        if let Some(recent_errors) = &self.recent_errors {
+            recent_errors.record(
+                self.kind,
+                &addr,
+                "Listener has stopped and could not be re-bound",
+            );
+        }
+    }
+
+    /// Forget a connection that has finished, so an interface with
+    /// churning connections doesn't hold on to every one it has served.
+    fn finished(&mut self, id: usize) {
+        self.remote_addrs.remove(&id);
+    }
+
+    /// Wait for every connection to finish, bounded by a single `deadline`
+    /// for all of them rather than one per connection.
+    /// Connections still running after it are logged and left to finish
+    /// on their own.
+    async fn join(self, deadline: std::time::Duration) {
+        let Connections {
+            mut remote_addrs,
+            mut tasks,
+            ..
+        } = self;
+        let all_finished = async {
+            while let Some(id) = tasks.next().await {
+                remote_addrs.remove(&id);
+            }
+        };
+        if tokio::time::timeout(deadline, all_finished).await.is_err() {
+            for remote_addr in remote_addrs.values() {
+                warn!(
+                    %remote_addr,
+                    ?deadline,
+                    "Connection did not finish within the shutdown deadline"
+                );
+            }
+        }
+    }
+}
+
+/// Reports what happens on one connection to the interface's
+/// [`ConnectionHook`] and [`RecentInterfaceErrors`], if it has them
+#[derive(Clone)]
+struct ConnectionReporter {
+    kind: InterfaceKind,
+    remote_addr: String,
+    hook: Option<ConnectionHook>,
+    recent_errors: Option<RecentInterfaceErrors>,
+}
+
+impl ConnectionReporter {
+    fn report(&self, event: ConnectionEvent) {
+        if let Some(hook) = &self.hook {
+            hook(self.kind, &self.remote_addr, event);
+        }
+    }
+
+    /// Keep an error which didn't end the connection.
+    /// Errors which do are kept when the connection finishes.
+    fn error(&self, e: &InterfaceError) {
+        if let Some(recent_errors) = &self.recent_errors {
+            recent_errors.record(self.kind, &self.remote_addr, e);
+        }
+    }
+}
+
+/// The most recent errors on a conductor's interfaces, for
+/// `AdminRequest::RecentErrors`, so operators can see what has been
+/// failing when logs are off or have been rotated.
+/// Once `capacity` errors are kept, each new one replaces the oldest.
+/// Clones share the same errors.
+#[derive(Clone)]
+pub struct RecentInterfaceErrors {
+    capacity: usize,
+    errors: Arc<parking_lot::Mutex<VecDeque<InterfaceErrorInfo>>>,
+}
+
+impl RecentInterfaceErrors {
+    /// Keep up to `capacity` errors, none at all if it is 0
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            errors: Arc::new(parking_lot::Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// Keep an error which happened just now on the connection
+    /// from `remote_addr` to a `kind` interface
+    pub fn record(&self, kind: InterfaceKind, remote_addr: &str, error: impl std::fmt::Display) {
+        if self.capacity == 0 {
+            return;
+        }
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let mut errors = self.errors.lock();
+        if errors.len() == self.capacity {
+            errors.pop_front();
+        }
+        errors.push_back(InterfaceErrorInfo {
+            timestamp_ms,
+            interface: kind.name().to_string(),
+            remote_addr: remote_addr.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    /// The errors kept, oldest first
+    pub fn list(&self) -> Vec<InterfaceErrorInfo> {
+        self.errors.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for RecentInterfaceErrors {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_INTERFACE_ERRORS)
+    }
+}
+
+/// Counts of rare events on a conductor's interfaces which monitoring
+/// should alert on, reported in `AdminResponse::Pong`.
+/// Clones share the same counts.
+#[derive(Clone, Default)]
+pub struct InterfaceCounters {
+    admin_listener_died: Arc<AtomicU64>,
+    app_signal_encode_failed: Arc<AtomicU64>,
+    system_signal_encode_failed: Arc<AtomicU64>,
+}
+
+impl InterfaceCounters {
+    /// A hook counting the events reported to it, see [`ConnectionHook`]
+    pub fn hook(&self) -> ConnectionHook {
+        let counters = self.clone();
+        Arc::new(
+            move |kind: InterfaceKind, _: &str, event: ConnectionEvent| {
+                let count: &AtomicU64 = match event {
+                    ConnectionEvent::ListenerDied if kind == InterfaceKind::Admin => {
+                        &counters.admin_listener_died
+                    }
+                    ConnectionEvent::SignalEncodeFailed(signal_kind) => {
+                        counters.signal_encode_failed_count(signal_kind)
+                    }
+                    _ => return,
+                };
+                count.fetch_add(1, Ordering::Relaxed);
+            },
+        )
+    }
+
+    /// How many admin interfaces have lost their listener for good
+    pub fn admin_listener_died(&self) -> u64 {
+        self.admin_listener_died.load(Ordering::Relaxed)
+    }
+
+    /// How many signals of this kind couldn't be serialized for a connection
+    pub fn signal_encode_failed(&self, signal_kind: SignalKind) -> u64 {
+        self.signal_encode_failed_count(signal_kind)
+            .load(Ordering::Relaxed)
+    }
+
+    fn signal_encode_failed_count(&self, signal_kind: SignalKind) -> &AtomicU64 {
+        match signal_kind {
+            SignalKind::App => &self.app_signal_encode_failed,
+            SignalKind::System => &self.system_signal_encode_failed,
+        }
+    }
+}
+
+/// How many requests a conductor's interfaces are handling between them.
+/// Once there are `max_pending` or more, the conductor is overloaded and
+/// its interfaces stop accepting connections, leaving new clients queued
+/// in the OS backlog, until some of the requests have been answered.
+/// Clones share the same count.
+#[derive(Clone)]
+pub struct InterfaceLoad {
+    max_pending: Option<usize>,
+    pending: Arc<AtomicUsize>,
+    answered: Arc<tokio::sync::watch::Sender<()>>,
+    answered_rx: tokio::sync::watch::Receiver<()>,
+}
+
+impl InterfaceLoad {
+    /// Overloaded with `max_pending` requests, never if it is `None`
+    pub fn new(max_pending: Option<usize>) -> Self {
+        let (answered, answered_rx) = tokio::sync::watch::channel(());
+        Self {
+            max_pending,
+            pending: Arc::new(AtomicUsize::new(0)),
+            answered: Arc::new(answered),
+            answered_rx,
+        }
+    }
+
+    /// Count a request as pending until the returned guard is dropped
+    pub fn request_started(&self) -> PendingRequest {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        PendingRequest(self.clone())
+    }
+
+    /// How many requests are pending
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Whether connections should wait to be accepted
+    pub fn is_overloaded(&self) -> bool {
+        self.max_pending
+            .map_or(false, |max_pending| self.pending() >= max_pending)
+    }
+
+    /// Resolves once some request is answered, for rechecking the load.
+    /// It may resolve for a request answered since the last call.
+    async fn answered(&mut self) {
+        self.answered_rx.changed().await.ok();
+    }
+}
+
+/// A request counted by an [`InterfaceLoad`], until this is dropped
+pub struct PendingRequest(InterfaceLoad);
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.0.pending.fetch_sub(1, Ordering::SeqCst);
+        self.0.answered.send(()).ok();
+    }
+}
+
+/// What an interface shares with the rest of the conductor
+#[derive(Clone, Default)]
+pub struct InterfaceContext {
+    /// Tunes the transport, `WebsocketConfig::default()` suits most
+    /// interfaces. Its subprotocols are replaced by the interface's own.
+    pub websocket_config: WebsocketConfig,
+    /// If set, it is told about every connection accepted and closed,
+    /// and every signal which couldn't be serialized
+    pub connection_hook: Option<ConnectionHook>,
+    /// If set, errors on the interface's connections are kept in it
+    pub recent_errors: Option<RecentInterfaceErrors>,
+    /// If set, no connections are accepted while it is overloaded
+    pub load: Option<InterfaceLoad>,
+}
+
+/// How an app interface treats each of its connections
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AppInterfaceOptions {
+    /// If set, each connection buffers signals for that long and only
+    /// sends the most recent one from each source
+    pub signal_coalesce_window: Option<std::time::Duration>,
+    /// If set, signals are queued without awaiting unless the
+    /// connection's outgoing buffer is full
+    pub eager_signals: bool,
+    /// If set, each connection's requests are limited by it
+    pub rate_limit: Option<RateLimitConfig>,
+    /// If set, connections that send nothing for that long are closed
+    pub idle_timeout: Option<std::time::Duration>,
+    /// How long clients closed because of shutdown are asked to wait
+    /// before reconnecting, see [`close_reason_text`].
+    /// `None` suggests 1 second.
+    pub reconnect_backoff: Option<std::time::Duration>,
+    /// How signals which can't be serialized are handled
+    pub signal_encode_failure: SignalEncodeFailurePolicy,
+}
+
+impl From<&AppInterfaceConfig> for AppInterfaceOptions {
+    fn from(config: &AppInterfaceConfig) -> Self {
+        Self {
+            signal_coalesce_window: config
+                .signal_coalesce_window_ms
+                .map(std::time::Duration::from_millis),
+            eager_signals: config.eager_signals,
+            rate_limit: config.rate_limit,
+            idle_timeout: config.idle_timeout_ms.map(std::time::Duration::from_millis),
+            reconnect_backoff: config
+                .reconnect_backoff_ms
+                .map(std::time::Duration::from_millis),
+            signal_encode_failure: config.signal_encode_failure,
+        }
+    }
+}
+
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
+/// from Cells via `signal_tx`.
+/// Each connection is handled according to `options`.
+/// The transport is tuned by `context`'s websocket config, as for
+/// [`spawn_websocket_listener`], with its subprotocols replaced by the app ones.
+/// Either `drain_rx` or `stop_rx` firing stops the interface, as for
+/// [`spawn_admin_interface_task`]. When drained, each connection stops
+/// reading requests but first answers the zome calls it is handling,
+/// for up to [`DRAIN_DEADLINE`], unless `stop_rx` fires meanwhile.
+pub async fn spawn_app_interface_task<A>(
+    addr: SocketAddr,
     api: A,
-    signal_broadcaster: broadcast::Sender<Signal>,
+    signal_tx: InterfaceSignalSender,
+    options: AppInterfaceOptions,
+    context: InterfaceContext,
+    mut drain_rx: StopReceiver,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+) -> InterfaceResult<(u16, InterfaceTaskHandle)>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
     trace!("Initializing App interface");
+    let InterfaceContext {
+        websocket_config,
+        connection_hook,
+        recent_errors,
+        mut load,
+    } = context;
     let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        websocket_url(addr),
+        Arc::new(websocket_config.subprotocols(vec![
+            APP_SUBPROTOCOL_V2,
+            APP_SUBPROTOCOL_V2_JSON,
+            APP_SUBPROTOCOL_V1,
+            APP_SUBPROTOCOL_V1_JSON,
+        ])),
     )
-    .await?;
+    .await
+    .map_err(|e| bind_error(addr, e))?;
     trace!("LISTENING AT: {}", handle.local_addr());
     let port = handle
         .local_addr()
         .port()
         .ok_or(InterfaceError::PortError)?;
-    // Task that will kill the listener and all child connections.
-    tokio::task::spawn(
-        handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
-    );
     let task = tokio::task::spawn(async move {
-        // establish a new connection to a client
-        while let Some(connection) = listener.next().await {
-            match connection {
-                Ok((tx_to_iface, rx_from_iface)) => {
-                    let rx_from_cell = signal_broadcaster.subscribe();
-                    tokio::task::spawn(recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
-                        rx_from_iface,
-                        rx_from_cell,
-                        tx_to_iface,
-                    ));
+        let mut connections = Connections::new(InterfaceKind::App, connection_hook, recent_errors);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(AppShutdown::Running);
+        let mut paused = false;
+        let exit = loop {
+            let overloaded = accept_paused(InterfaceKind::App, &load, &mut paused);
+            tokio::select! {
+                // Close all child connections, then the listener.
+                _ = stop_rx.recv() => {
+                    shutdown_tx.send(AppShutdown::Stop).ok();
+                    break InterfaceTaskExit::Stopped;
+                }
+                // Stop accepting and close child connections once they have
+                // answered what they are handling, leaving the rest of the
+                // conductor running.
+                _ = drain_rx.recv() => {
+                    shutdown_tx.send(AppShutdown::Drain).ok();
+                    break InterfaceTaskExit::Drained;
                 }
-                Err(err) => {
-                    warn!("Admin socket connection failed: {}", err);
+                // forget connections that have finished
+                Some(id) = connections.tasks.next() => {
+                    connections.finished(id);
                 }
+                // check again whether to accept once a request is answered
+                _ = load_answered(&mut load), if overloaded => {}
+                // establish a new connection to a client
+                connection = listener.next(), if !overloaded => match connection {
+                    Some(Ok((tx_to_iface, rx_from_iface))) => {
+                        let rx_from_cell = signal_tx.subscribe();
+                        let remote_addr = rx_from_iface.client_addr();
+                        let reporter = connections.reporter(&remote_addr);
+                        if is_json_subprotocol(rx_from_iface.subprotocol()) {
+                            connections.spawn(remote_addr, recv_incoming_msgs_and_outgoing_signals::<_, JsonCodec>(
+                                api.clone(),
+                                rx_from_iface,
+                                rx_from_cell,
+                                tx_to_iface,
+                                shutdown_rx.clone(),
+                                options,
+                                reporter,
+                            ));
+                        } else {
+                            connections.spawn(remote_addr, recv_incoming_msgs_and_outgoing_signals::<_, MsgPackCodec>(
+                                api.clone(),
+                                rx_from_iface,
+                                rx_from_cell,
+                                tx_to_iface,
+                                shutdown_rx.clone(),
+                                options,
+                                reporter,
+                            ));
+                        }
+                    }
+                    Some(Err(err)) => connections.connection_failed(err),
+                    // The listener has been closed
+                    None => {
+                        warn!(%port, "App listener has returned none");
+                        break InterfaceTaskExit::ListenerClosed;
+                    }
+                }
+            }
+        };
+        let draining = exit == InterfaceTaskExit::Drained;
+        let deadline = if draining {
+            DRAIN_DEADLINE + SHUTDOWN_DEADLINE
+        } else {
+            SHUTDOWN_DEADLINE
+        };
+        let joined = connections.join(deadline);
+        futures::pin_mut!(joined);
+        tokio::select! {
+            _ = &mut joined => {}
+            // Being stopped while draining doesn't wait for zome calls
+            _ = stop_rx.recv(), if draining => {
+                shutdown_tx.send(AppShutdown::Stop).ok();
+                joined.await;
             }
         }
+        handle.close();
 
-        ManagedTaskResult::Ok(())
+        InterfaceTaskResult::Ok(exit)
     });
     Ok((port, task))
 }
 
 /// Polls for messages coming in from the external client.
 /// Used by Admin interface.
-async fn recv_incoming_admin_msgs<A: InterfaceApi>(
+/// If this fails, e.g. because nothing arrived within the idle timeout, the
+/// connection outlived its max lifetime or the interface shut down,
+/// the client is sent a close frame describing the error.
+async fn recv_incoming_admin_msgs<A, C: WireCodec>(
     api: A,
-    mut rx_from_iface: WebsocketReceiver,
+    settings: AdminConnectionSettings,
+    mut tx_to_iface: WebsocketSender,
+    rx_from_iface: WebsocketReceiver,
+    shutdown: tokio::sync::watch::Receiver<bool>,
     num_connections: Arc<AtomicIsize>,
-) {
-    while let Some(msg) = rx_from_iface.next().await {
-        match handle_incoming_message(msg, api.clone()).await {
-            Err(e) => error!(error = &e as &dyn std::error::Error),
+    reporter: ConnectionReporter,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
+    let reconnect_backoff = settings.reconnect_backoff;
+    let result =
+        poll_incoming_admin_msgs::<_, C>(api, settings, rx_from_iface, shutdown, reporter).await;
+    if let Err(e) = &result {
+        close_on_error(&mut tx_to_iface, e, reconnect_backoff).await;
+    }
+    num_connections.fetch_sub(1, Ordering::SeqCst);
+    result
+}
+
+/// Handles admin requests until the client goes away.
+/// A request that fails is logged and the connection carries on,
+/// unless the failure means the connection itself is broken.
+/// Once the connection has been open for its max lifetime it ends,
+/// though never while a request is being handled.
+async fn poll_incoming_admin_msgs<A, C: WireCodec>(
+    api: A,
+    settings: AdminConnectionSettings,
+    mut rx_from_iface: WebsocketReceiver,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+    reporter: ConnectionReporter,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
+    let AdminConnectionSettings {
+        allowed_requests,
+        inflight,
+        rate_limit,
+        idle_timeout,
+        max_lifetime,
+        ..
+    } = settings;
+    let mut rate_limiter = rate_limit.map(RateLimiter::new);
+    let lifetime_ended = async {
+        match max_lifetime {
+            Some(max_lifetime) => {
+                tokio::time::sleep(max_lifetime).await;
+                max_lifetime
+            }
+            None => futures::future::pending().await,
+        }
+    };
+    tokio::pin!(lifetime_ended);
+    loop {
+        let next_msg = async {
+            match idle_timeout {
+                Some(idle_timeout) => tokio::time::timeout(idle_timeout, rx_from_iface.next())
+                    .await
+                    .map_err(|_| InterfaceError::IdleTimeout(idle_timeout)),
+                None => Ok(rx_from_iface.next().await),
+            }
+        };
+        let msg = tokio::select! {
+            // shutting down goes first, so a client still sending requests
+            // is sent the shutdown close frame rather than another response
+            biased;
+            _ = shutdown.changed() => Err(InterfaceError::ShuttingDown),
+            max_lifetime = &mut lifetime_ended => Err(InterfaceError::MaxLifetime(max_lifetime)),
+            msg = next_msg => msg,
+        };
+        let msg = match msg? {
+            Some(msg) => msg,
+            None => {
+                debug!("Closing interface: message stream empty");
+                return Ok(());
+            }
+        };
+        let result = match rate_limiter.as_mut().map(RateLimiter::check) {
+            Some(Err(wait)) => {
+                respond_rate_limited::<C, _>(msg, AdminResponse::Error(rate_limited(wait))).await
+            }
+            _ => {
+                handle_incoming_admin_message::<_, C>(
+                    msg,
+                    api.clone(),
+                    allowed_requests.as_deref(),
+                    inflight.as_ref(),
+                )
+                .await
+            }
+        };
+        match result {
             Ok(()) => {}
+            // the client went away before we could respond
+            Err(InterfaceError::Closed) => return Ok(()),
+            Err(e @ InterfaceError::SendError) | Err(e @ InterfaceError::WebsocketError(_)) => {
+                return Err(e)
+            }
+            Err(e) => {
+                error!(error = &e as &dyn std::error::Error);
+                reporter.error(&e);
+            }
         }
     }
-    num_connections.fetch_sub(1, Ordering::SeqCst);
 }
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+/// If this fails, the client is sent a close frame describing the error.
+async fn recv_incoming_msgs_and_outgoing_signals<A, C: WireCodec>(
+    api: A,
+    rx_from_iface: WebsocketReceiver,
+    rx_from_cell: InterfaceSignalReceiver,
+    tx_to_iface: WebsocketSender,
+    shutdown: tokio::sync::watch::Receiver<AppShutdown>,
+    options: AppInterfaceOptions,
+    reporter: ConnectionReporter,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
+    let mut tx_close = tx_to_iface.clone();
+    let result = poll_incoming_msgs_and_outgoing_signals::<_, C>(
+        api,
+        rx_from_iface,
+        rx_from_cell,
+        tx_to_iface,
+        shutdown,
+        options,
+        reporter,
+    )
+    .await;
+    if let Err(e) = &result {
+        let reconnect_backoff = options
+            .reconnect_backoff
+            .unwrap_or(DEFAULT_RECONNECT_BACKOFF);
+        close_on_error(&mut tx_close, e, reconnect_backoff).await;
+    }
+    result
+}
+
+/// Send the client a close frame describing the error ending its connection.
+async fn close_on_error(
+    tx_to_iface: &mut WebsocketSender,
+    e: &InterfaceError,
+    reconnect_backoff: std::time::Duration,
+) {
+    let (code, category) = e.close_reason();
+    debug!(?code, category, error = ?e, "Closing interface on error");
+    // The connection may already be gone. Each connection closes itself,
+    // so a failure here never holds up closing the others on shutdown.
+    if let Err(close_error) = tx_to_iface
+        .close(code, close_reason_text(e, reconnect_backoff))
+        .await
+    {
+        debug!(?close_error, category, "Failed to send close frame");
+    }
+}
+
+/// The reason sent in the close frame of a connection ended by `e`.
+///
+/// This is `<category>: <description>`, where the category is one of the
+/// fixed strings from [`InterfaceError::close_reason`] and the description
+/// is for humans only. When the client should wait before reconnecting,
+/// i.e. on `shutting_down` and `overloaded` closes, the category is followed
+/// by a suggested backoff: `<category>; retry_after_ms=<ms>: <description>`.
+/// Clients should add some jitter of their own to the backoff.
+fn close_reason_text(e: &InterfaceError, reconnect_backoff: std::time::Duration) -> String {
+    let (_, category) = e.close_reason();
+    if e.suggests_reconnect_backoff() {
+        format!(
+            "{}; retry_after_ms={}: {}",
+            category,
+            reconnect_backoff.as_millis(),
+            e
+        )
+    } else {
+        format!("{}: {}", category, e)
+    }
+}
+
+async fn poll_incoming_msgs_and_outgoing_signals<A, C: WireCodec>(
     api: A,
     mut rx_from_iface: WebsocketReceiver,
-    mut rx_from_cell: broadcast::Receiver<Signal>,
+    mut rx_from_cell: InterfaceSignalReceiver,
     mut tx_to_iface: WebsocketSender,
-) -> InterfaceResult<()> {
+    mut shutdown: tokio::sync::watch::Receiver<AppShutdown>,
+    options: AppInterfaceOptions,
+    reporter: ConnectionReporter,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
+    let with_seq = sends_signal_seq(rx_from_iface.subprotocol());
+    let AppInterfaceOptions {
+        signal_coalesce_window,
+        eager_signals,
+        rate_limit,
+        idle_timeout,
+        signal_encode_failure,
+        ..
+    } = options;
+
+    let mut rate_limiter = rate_limit.map(RateLimiter::new);
+
+    // Set by the client with `AppRequest::SignalSubscription`,
+    // `None` sends every signal
+    let mut subscription: Option<SignalSubscription> = None;
+
+    // Zome calls made with a call id, handled alongside other messages
+    let mut in_flight = InFlightCalls::default();
+
+    // Signals waiting for the coalesce window to elapse
+    let mut pending_signals: Vec<Signal> = Vec::new();
+    let flush = tokio::time::sleep(std::time::Duration::from_secs(0));
+    futures::pin_mut!(flush);
+
+    // Reset on every incoming message, only polled if there is an idle timeout
+    let idle = tokio::time::sleep(idle_timeout.unwrap_or_default());
+    futures::pin_mut!(idle);
 
     loop {
         tokio::select! {
+            // Checked first, so once the interface is stopping the client
+            // is sent the shutdown close frame before any more signals
+            // or responses
+            biased;
+
+            // The interface is shutting down
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() == AppShutdown::Drain {
+                    // No more requests are read, but the calls already
+                    // being handled are answered unless stopped meanwhile
+                    tokio::select! {
+                        answered = in_flight.answer_all(DRAIN_DEADLINE) => answered?,
+                        _ = shutdown.changed() => {}
+                    }
+                }
+                return Err(InterfaceError::ShuttingDown);
+            },
+
             // If we receive a Signal broadcasted from a Cell, push it out
             // across the interface
             signal = rx_from_cell.recv() => {
-                if let Ok(signal) = signal {
-                    trace!(msg = "Sending signal!", ?signal);
-                    let bytes = SerializedBytes::try_from(
-                        signal
-                        // .map_err(InterfaceError::SignalReceive)?,
-                    )?;
-                    tx_to_iface.signal(bytes).await?;
+                if let Some(signal) = signal {
+                    // skip signals the client isn't interested in
+                    // before spending anything on them
+                    if !subscription.as_ref().map_or(true, |s| s.allows(&signal)) {
+                        continue;
+                    }
+                    match signal_coalesce_window {
+                        Some(window) => {
+                            if pending_signals.is_empty() {
+                                flush.as_mut().reset(tokio::time::Instant::now() + window);
+                            }
+                            coalesce_signal(&mut pending_signals, signal);
+                        }
+                        None => {
+                            let bytes = encode_signal::<C>(&signal, with_seq, signal_encode_failure, &reporter)?;
+                            if let Some(bytes) = bytes {
+                                send_signal(&mut tx_to_iface, bytes, eager_signals).await?;
+                            }
+                        }
+                    }
                 } else {
                     debug!("Closing interface: signal stream empty");
                     break;
                 }
             },
 
+            // The coalesce window has elapsed, send what we have
+            _ = &mut flush, if !pending_signals.is_empty() => {
+                for signal in pending_signals.drain(..) {
+                    let bytes = encode_signal::<C>(&signal, with_seq, signal_encode_failure, &reporter)?;
+                    if let Some(bytes) = bytes {
+                        send_signal(&mut tx_to_iface, bytes, eager_signals).await?;
+                    }
+                }
+            },
+
+            // A zome call made with a call id has been answered
+            answered = in_flight.next(), if !in_flight.is_empty() => {
+                if let Some(answered) = answered {
+                    answered?;
+                }
+            },
+
+            // Nothing has come from outside for too long
+            _ = &mut idle, if idle_timeout.is_some() => {
+                return Err(InterfaceError::IdleTimeout(idle_timeout.unwrap_or_default()));
+            },
+
             // If we receive a message from outside, handle it
             msg = rx_from_iface.next() => {
                 if let Some(msg) = msg {
-                    handle_incoming_message(msg, api.clone()).await?
+                    if let Some(idle_timeout) = idle_timeout {
+                        idle.as_mut().reset(tokio::time::Instant::now() + idle_timeout);
+                    }
+                    match rate_limiter.as_mut().map(RateLimiter::check) {
+                        Some(Err(wait)) => {
+                            respond_rate_limited::<C, _>(msg, AppResponse::Error(rate_limited(wait)))
+                                .await?
+                        }
+                        _ => {
+                            handle_incoming_app_message::<_, C>(
+                                msg,
+                                api.clone(),
+                                &mut subscription,
+                                &mut in_flight,
+                            )
+                            .await?
+                        }
+                    }
                 } else {
                     debug!("Closing interface: message stream empty");
                     break;
@@ -196,31 +1293,535 @@ async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
     Ok(())
 }
 
-/// Handles messages on all interfaces
-async fn handle_incoming_message<A>(ws_msg: WebsocketMessage, api: A) -> InterfaceResult<()>
-where
-    A: InterfaceApi,
-{
-    let (bytes, respond) = ws_msg;
-    Ok(respond
-        .respond(api.handle_request(bytes.try_into()).await?.try_into()?)
-        .await?)
+/// Serialize a signal to push out across the interface.
+/// A failure is reported to the connection hook, then either ends the
+/// connection, drops the signal by returning `None` with
+/// [`SignalEncodeFailurePolicy::Skip`], or serializes a notice in its place
+/// with [`SignalEncodeFailurePolicy::Notify`].
+/// App signals only carry their sequence number if `with_seq` is set,
+/// otherwise they are sent as a [`SignalV1`].
+fn encode_signal<C: WireCodec>(
+    signal: &Signal,
+    with_seq: bool,
+    encode_failure: SignalEncodeFailurePolicy,
+    reporter: &ConnectionReporter,
+) -> InterfaceResult<Option<SerializedBytes>> {
+    trace!(msg = "Sending signal!", ?signal);
+    let encoded = if with_seq {
+        C::encode(signal)
+    } else {
+        C::encode(&SignalV1::from(signal.clone()))
+    };
+    match encoded {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) => {
+            let signal_kind = SignalKind::of(signal);
+            reporter.report(ConnectionEvent::SignalEncodeFailed(signal_kind));
+            match encode_failure {
+                SignalEncodeFailurePolicy::Close => Err(e.into()),
+                SignalEncodeFailurePolicy::Skip => {
+                    warn!(
+                        remote_addr = %reporter.remote_addr,
+                        ?signal_kind,
+                        error = ?e,
+                        "Skipping a signal which couldn't be serialized"
+                    );
+                    Ok(None)
+                }
+                SignalEncodeFailurePolicy::Notify => match C::encode(&signal.encode_failed()) {
+                    Ok(bytes) => {
+                        warn!(
+                            remote_addr = %reporter.remote_addr,
+                            ?signal_kind,
+                            error = ?e,
+                            "Sending a notice in place of a signal which couldn't be serialized"
+                        );
+                        Ok(Some(bytes))
+                    }
+                    // not even the notice gets through, so give up on the connection
+                    Err(_) => Err(e.into()),
+                },
+            }
+        }
+    }
 }
 
-/// Test items needed by other crates
-#[cfg(any(test, feature = "test_utils"))]
-pub mod test_utils {
-    use crate::conductor::api::RealAppInterfaceApi;
-    use crate::conductor::conductor::ConductorBuilder;
-    use crate::conductor::ConductorHandle;
-    use holochain_lmdb::test_utils::test_environments;
-    use holochain_serialized_bytes::prelude::*;
-    use holochain_types::prelude::*;
-    use std::sync::Arc;
-    use tempdir::TempDir;
+/// Push a serialized signal out across the interface.
+/// An eager send only awaits if the outgoing buffer is full.
+async fn send_signal(
+    tx_to_iface: &mut WebsocketSender,
+    bytes: SerializedBytes,
+    eager: bool,
+) -> InterfaceResult<()> {
+    if eager {
+        tx_to_iface.signal_eager(bytes).await?;
+    } else {
+        tx_to_iface.signal(bytes).await?;
+    }
+    Ok(())
+}
 
-    /// One of various ways to setup an app, used somewhere...
-    pub async fn setup_app(
+/// Queue a signal to be sent when the coalesce window elapses,
+/// replacing any pending signal from the same source so only the latest is sent.
+/// The replaced app signals' sequence numbers are skipped on purpose.
+fn coalesce_signal(pending_signals: &mut Vec<Signal>, signal: Signal) {
+    let same_source = |a: &Signal, b: &Signal| match (a, b) {
+        (Signal::App(a, ..), Signal::App(b, ..)) => a == b,
+        (Signal::System(_), Signal::System(_)) => true,
+        _ => false,
+    };
+    match pending_signals.iter_mut().find(|p| same_source(p, &signal)) {
+        Some(pending) => *pending = signal,
+        None => pending_signals.push(signal),
+    }
+}
+
+/// Token bucket limiting the rate of requests on a single connection.
+struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl RateLimiter {
+    fn new(config: RateLimitConfig) -> Self {
+        let burst = config.burst.max(1) as f64;
+        Self {
+            requests_per_second: config.requests_per_second.max(1) as f64,
+            burst,
+            tokens: burst,
+            last_refill: std::time::Instant::now(),
+        }
+    }
+
+    /// Take a token for a request, or if there are none left
+    /// return how long until the next one is available.
+    fn check(&mut self) -> Result<(), std::time::Duration> {
+        let now = std::time::Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.requests_per_second).min(self.burst);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(std::time::Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.requests_per_second,
+            ))
+        }
+    }
+}
+
+fn rate_limited(wait: std::time::Duration) -> ExternalApiWireError {
+    // round up so a client retrying after exactly this long succeeds
+    let retry_after_ms = (wait.as_micros() as u64 + 999) / 1000;
+    ExternalApiWireError::RateLimited { retry_after_ms }
+}
+
+/// Respond to a message without handling it
+/// because its connection has exceeded its rate limit.
+async fn respond_rate_limited<C, R>(ws_msg: WebsocketMessage, response: R) -> InterfaceResult<()>
+where
+    C: WireCodec,
+    R: serde::Serialize,
+{
+    trace!("Rejecting rate limited request");
+    let (_, respond) = ws_msg;
+    Ok(respond.respond(C::encode(&response)?).await?)
+}
+
+/// Handles a message with nothing but the api,
+/// decoding the request and encoding the response with the given codec.
+/// Connections use [`handle_incoming_admin_message`] and
+/// [`handle_incoming_app_message`], which also apply per-connection settings.
+#[cfg(test)]
+async fn handle_incoming_message<A, C>(ws_msg: WebsocketMessage, api: A) -> InterfaceResult<()>
+where
+    A: InterfaceApi,
+    A::ApiRequest: serde::de::DeserializeOwned,
+    A::ApiResponse: serde::Serialize,
+    C: WireCodec,
+{
+    let (bytes, respond) = ws_msg;
+    let response = api.handle_request(C::decode(bytes)).await?;
+    Ok(respond.respond(C::encode(&response)?).await?)
+}
+
+/// Handles messages on app interfaces, applying signal subscriptions and
+/// zome call cancellations to the connection they were sent on rather than
+/// passing them to the api.
+/// A zome call with a call id is added to `in_flight` to be answered
+/// when it completes, instead of being waited on here.
+async fn handle_incoming_app_message<A, C>(
+    ws_msg: WebsocketMessage,
+    api: A,
+    subscription: &mut Option<SignalSubscription>,
+    in_flight: &mut InFlightCalls,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+    C: WireCodec,
+{
+    let (bytes, respond) = ws_msg;
+    let response = match C::decode(bytes) {
+        Ok(AppRequest::SignalSubscription(s)) => {
+            debug!(installed_app_id = %s.installed_app_id(), "Updating signal subscription");
+            *subscription = Some(s);
+            AppResponse::SignalSubscriptionUpdated
+        }
+        Ok(AppRequest::CancelZomeCall { call_id }) => {
+            if in_flight.cancel(call_id) {
+                debug!(call_id, "Cancelled zome call");
+                AppResponse::ZomeCallCancelled
+            } else {
+                AppResponse::Error(ExternalApiWireError::ZomeCallNotFound { call_id })
+            }
+        }
+        Ok(AppRequest::ZomeCall(call)) => match call.call_id {
+            Some(call_id) if in_flight.contains(call_id) => {
+                AppResponse::Error(ExternalApiWireError::validation(
+                    "call_id",
+                    format!("a call with id {} is already in flight", call_id),
+                ))
+            }
+            Some(call_id) => {
+                in_flight.start::<_, C>(call_id, AppRequest::ZomeCall(call), api, respond);
+                return Ok(());
+            }
+            None => api.handle_request(Ok(AppRequest::ZomeCall(call))).await?,
+        },
+        request => api.handle_request(request).await?,
+    };
+    Ok(respond.respond(C::encode(&response)?).await?)
+}
+
+/// What an app interface asks of its connections
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AppShutdown {
+    /// Carry on as normal
+    Running,
+    /// Close once the zome calls in flight are answered
+    Drain,
+    /// Close straight away
+    Stop,
+}
+
+/// The zome calls made with a call id on one app connection
+/// which haven't been answered yet.
+/// They are only driven while the connection polls [`InFlightCalls::next`]
+/// or [`InFlightCalls::answer_all`], and are dropped, unanswered,
+/// with the connection.
+#[derive(Default)]
+struct InFlightCalls {
+    calls: FuturesUnordered<BoxFuture<'static, (ZomeCallId, InterfaceResult<()>)>>,
+    cancels: HashMap<ZomeCallId, tokio::sync::oneshot::Sender<()>>,
+}
+
+impl InFlightCalls {
+    /// Handle `request` with `api`, answering it through `respond` with
+    /// the response or, if it is cancelled first, with
+    /// [`AppResponse::ZomeCallCancelled`].
+    /// Cancelling only abandons the call's workspace and response, the zome
+    /// function runs on a blocking thread and isn't interrupted. It runs to
+    /// the end, and any host calls it makes with effects outside the source
+    /// chain, such as `call_remote`, still happen.
+    fn start<A, C>(&mut self, call_id: ZomeCallId, request: AppRequest, api: A, respond: Respond)
+    where
+        A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+        C: WireCodec,
+    {
+        let (cancel, cancelled) = tokio::sync::oneshot::channel();
+        self.cancels.insert(call_id, cancel);
+        self.calls.push(
+            async move {
+                let answered = async move {
+                    // Dropping the call before it completes drops its
+                    // workspace before it is flushed, so nothing is committed.
+                    // A cancelled call is never answered with its result,
+                    // even if that is ready by the time this is polled
+                    let response = tokio::select! {
+                        biased;
+                        Ok(()) = cancelled => AppResponse::ZomeCallCancelled,
+                        response = api.handle_request(Ok(request)) => response?,
+                    };
+                    Ok(respond.respond(C::encode(&response)?).await?)
+                };
+                (call_id, answered.await)
+            }
+            .boxed(),
+        );
+    }
+
+    /// Cancel the call with this id.
+    /// False if there is no such call, or it has already completed
+    /// and is only waiting to be answered.
+    fn cancel(&mut self, call_id: ZomeCallId) -> bool {
+        self.cancels
+            .remove(&call_id)
+            .map_or(false, |cancel| cancel.send(()).is_ok())
+    }
+
+    fn contains(&self, call_id: ZomeCallId) -> bool {
+        self.cancels.contains_key(&call_id)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Drive the calls until one has been answered,
+    /// returning how answering it went.
+    async fn next(&mut self) -> Option<InterfaceResult<()>> {
+        let (call_id, answered) = self.calls.next().await?;
+        self.cancels.remove(&call_id);
+        Some(answered)
+    }
+
+    /// Drive the calls until all of them have been answered,
+    /// or `deadline` has passed, leaving the rest unanswered.
+    async fn answer_all(&mut self, deadline: std::time::Duration) -> InterfaceResult<()> {
+        let all_answered = async {
+            while let Some(answered) = self.next().await {
+                answered?;
+            }
+            InterfaceResult::Ok(())
+        };
+        match tokio::time::timeout(deadline, all_answered).await {
+            Ok(answered) => answered,
+            Err(_) => {
+                warn!(
+                    unanswered = self.calls.len(),
+                    ?deadline,
+                    "Zome calls were not answered within the drain deadline"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Handles messages on admin interfaces, rejecting any request
+/// which isn't in `allowed_requests` or fails validation
+/// before it reaches the api.
+/// If `inflight` is set, a read-only request shares the response of an
+/// identical one already being handled.
+async fn handle_incoming_admin_message<A, C>(
+    ws_msg: WebsocketMessage,
+    api: A,
+    allowed_requests: Option<&HashSet<String>>,
+    inflight: Option<&InflightRequests>,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+    C: WireCodec,
+{
+    let (bytes, respond) = ws_msg;
+    // identical requests are identical bytes in the same codec
+    let key = inflight.map(|_| bytes.bytes().to_vec());
+    let request: Result<AdminRequest, _> = C::decode(bytes);
+    let response = match (&request, allowed_requests) {
+        (Ok(r), Some(allowed)) if !allowed.contains(r.request_type()) => {
+            warn!(request_type = r.request_type(), "Forbidden admin request");
+            AdminResponse::Error(ExternalApiWireError::Forbidden(format!(
+                "{} is not permitted on this interface",
+                r.request_type()
+            )))
+        }
+        (Ok(r), _) => match (r.validate(), inflight.zip(key)) {
+            (Ok(()), Some((inflight, key))) if r.is_read_only() => {
+                inflight.handle(key, api, request).await?
+            }
+            (Ok(()), _) => api.handle_request(request).await?,
+            (Err(e), _) => {
+                debug!(request_type = r.request_type(), error = ?e, "Invalid admin request");
+                AdminResponse::Error(e)
+            }
+        },
+        _ => api.handle_request(request).await?,
+    };
+    Ok(respond.respond(C::encode(&response)?).await?)
+}
+
+/// The response to a read-only admin request, shared by every identical
+/// request which arrives while it is being handled
+type SharedAdminResponse =
+    futures::future::Shared<BoxFuture<'static, Result<AdminResponse, Arc<InterfaceError>>>>;
+
+/// The read-only admin requests being handled on one interface,
+/// by their bytes, see [`spawn_admin_interface_task`].
+/// Clones share the requests.
+#[derive(Clone, Default)]
+struct InflightRequests(Arc<parking_lot::Mutex<HashMap<Vec<u8>, SharedAdminResponse>>>);
+
+impl InflightRequests {
+    /// Handle `request` with `api`, unless an identical one is already
+    /// being handled, in which case wait for its response instead
+    async fn handle<A>(
+        &self,
+        key: Vec<u8>,
+        api: A,
+        request: Result<AdminRequest, SerializedBytesError>,
+    ) -> InterfaceResult<AdminResponse>
+    where
+        A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+    {
+        let response = {
+            let mut inflight = self.0.lock();
+            match inflight.get(&key) {
+                Some(response) => {
+                    trace!("Sharing the response to an identical admin request");
+                    response.clone()
+                }
+                None => {
+                    let requests = self.0.clone();
+                    let k = key.clone();
+                    // forgotten by the future itself, so it is even if
+                    // the connection which started it goes away first
+                    let response = async move {
+                        let response = api.handle_request(request).await.map_err(Arc::new);
+                        requests.lock().remove(&k);
+                        response
+                    }
+                    .boxed()
+                    .shared();
+                    inflight.insert(key, response.clone());
+                    response
+                }
+            }
+        };
+        response.await.map_err(|e| {
+            Arc::try_unwrap(e).unwrap_or_else(|e| InterfaceError::Other(e.to_string()))
+        })
+    }
+}
+
+/// Test items needed by other crates
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils {
+    use super::*;
+    use crate::conductor::api::RealAppInterfaceApi;
+    use crate::conductor::conductor::ConductorBuilder;
+    use crate::conductor::conductor::StopBroadcaster;
+    use crate::conductor::ConductorHandle;
+    use holochain_lmdb::test_utils::test_environments;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::prelude::*;
+    use std::sync::Arc;
+    use tempdir::TempDir;
+
+    /// An admin interface listening on a real socket on localhost,
+    /// with a client connected to it, so the whole path from accepting
+    /// connections to shutting down can be tested.
+    pub struct AdminInterfaceHarness {
+        /// The address the interface is listening on
+        pub addr: SocketAddr,
+        /// The connected client
+        pub client: WebsocketSender,
+        /// Messages for the connected client. This ends when the
+        /// interface closes the connection.
+        pub client_rx: WebsocketReceiver,
+        drain_tx: StopBroadcaster,
+        stop_tx: StopBroadcaster,
+        task: InterfaceTaskHandle,
+    }
+
+    impl AdminInterfaceHarness {
+        /// Spawn an interface for `api` on any free port and connect to it.
+        /// The options are the same as for [`spawn_admin_interface_task`].
+        pub async fn spawn<A>(
+            api: A,
+            allowed_requests: Option<Vec<String>>,
+            rate_limit: Option<RateLimitConfig>,
+            idle_timeout: Option<std::time::Duration>,
+            reconnect_backoff: Option<std::time::Duration>,
+        ) -> Self
+        where
+            A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+        {
+            let (drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+            let (stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+            let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+            let (handle, listener) = spawn_websocket_listener(addr, WebsocketConfig::default())
+                .await
+                .unwrap();
+            let addr = listener_socket_addr(&handle).unwrap();
+            let options = AdminInterfaceOptions {
+                allowed_requests,
+                rate_limit,
+                idle_timeout,
+                reconnect_backoff,
+                ..Default::default()
+            };
+            let task = spawn_admin_interface_task(
+                handle,
+                listener,
+                api,
+                options,
+                InterfaceContext::default(),
+                drain_rx,
+                stop_rx,
+            )
+            .unwrap();
+            let (client, client_rx) = Self::connect_to(addr).await;
+            Self {
+                addr,
+                client,
+                client_rx,
+                drain_tx,
+                stop_tx,
+                task,
+            }
+        }
+
+        /// Connect another client to the interface
+        pub async fn connect(&self) -> (WebsocketSender, WebsocketReceiver) {
+            Self::connect_to(self.addr).await
+        }
+
+        async fn connect_to(addr: SocketAddr) -> (WebsocketSender, WebsocketReceiver) {
+            holochain_websocket::connect(websocket_url(addr), Arc::new(WebsocketConfig::default()))
+                .await
+                .unwrap()
+        }
+
+        /// Send a request from the connected client and wait for the response
+        pub async fn request(&mut self, request: AdminRequest) -> AdminResponse {
+            self.client.request(request).await.unwrap()
+        }
+
+        /// Stop the interface, checking that its task finishes by itself,
+        /// saying it was stopped, and that the connected client gets
+        /// disconnected.
+        pub async fn shutdown(self) {
+            self.stop_tx.send(()).unwrap();
+            self.finish(InterfaceTaskExit::Stopped).await
+        }
+
+        /// Drain the interface, checking the same as [`Self::shutdown`]
+        /// except that the task says it was drained.
+        pub async fn drain(self) {
+            self.drain_tx.send(()).unwrap();
+            self.finish(InterfaceTaskExit::Drained).await
+        }
+
+        async fn finish(mut self, expected: InterfaceTaskExit) {
+            let timeout = SHUTDOWN_DEADLINE * 5;
+            let exit = tokio::time::timeout(timeout, self.task)
+                .await
+                .expect("interface did not stop in time")
+                .unwrap()
+                .unwrap();
+            assert_eq!(exit, expected);
+            let closed = tokio::time::timeout(timeout, self.client_rx.next())
+                .await
+                .expect("client was not disconnected");
+            assert!(closed.is_none());
+        }
+    }
+
+    /// One of various ways to setup an app, used somewhere...
+    pub async fn setup_app(
         cell_data: Vec<(InstalledCell, Option<SerializedBytes>)>,
         dna_store: MockDnaStore,
     ) -> (Arc<TempDir>, RealAppInterfaceApi, ConductorHandle) {
@@ -259,6 +1860,7 @@ pub mod test_utils {
 #[cfg(test)]
 pub mod test {
     use super::test_utils::setup_app;
+    use super::test_utils::AdminInterfaceHarness;
     use super::*;
     use crate::conductor::api::error::ExternalApiWireError;
     use crate::conductor::api::AdminRequest;
@@ -365,7 +1967,57 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn validation_failure() {
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::DumpStateRange {
+            cell_id: Box::new(CellId::new(fake_dna_hash(1), fake_agent_pubkey_1())),
+            start: 0,
+            limit: 0,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(
+                response,
+                AdminResponse::Error(ExternalApiWireError::Validation { field, .. })
+                if field == "limit"
+            );
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_admin_message::<_, MsgPackCodec>(msg, admin_api, None, None)
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn json_codec_request() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = SerializedBytes::from(UnsafeBytes::from(
+            br#"{"type":"list_app_interfaces"}"#.to_vec(),
+        ));
+        let respond = |bytes: SerializedBytes| {
+            let response: serde_json::Value = serde_json::from_slice(bytes.bytes()).unwrap();
+            assert_eq!(response["type"], "app_interfaces_listed");
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, JsonCodec>(msg, admin_api)
+            .await
+            .unwrap();
         conductor_handle.shutdown().await;
     }
 
@@ -393,7 +2045,9 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
         conductor_handle.shutdown().await;
     }
 
@@ -424,11 +2078,1879 @@ pub mod test {
         let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
 
         let mut dna_store = MockDnaStore::new();
-
-        dna_store
-            .expect_get()
-            .with(predicate::eq(dna_hash))
-            .returning(move |_| Some(dna.clone()));
+
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let mut request: ZomeCall =
+            crate::fixt::ZomeCallInvocationFixturator::new(crate::fixt::NamedInvocation(
+                cell_id.clone(),
+                TestWasm::Foo.into(),
+                "foo".into(),
+                ExternIO::encode(()).unwrap(),
+            ))
+            .next()
+            .unwrap()
+            .into();
+        request.cell_id = cell_id;
+        let msg = AppRequest::ZomeCallInvocation(Box::new(request));
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AppResponse::ZomeCallInvocation { .. });
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, MsgPackCodec>(msg, app_api)
+            .await
+            .unwrap();
+        // the time here should be almost the same (about +0.1ms) vs. the raw real_ribosome call
+        // the overhead of a websocket request locally is small
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_call_to_inactive_cell() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash.clone()))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+
+        // same dna, but an agent with no app installed
+        let other_cell_id = CellId::from((dna_hash, fake_agent_pubkey_2()));
+        let mut request: ZomeCall =
+            crate::fixt::ZomeCallInvocationFixturator::new(crate::fixt::NamedInvocation(
+                other_cell_id.clone(),
+                TestWasm::Foo.into(),
+                "foo".into(),
+                ExternIO::encode(()).unwrap(),
+            ))
+            .next()
+            .unwrap()
+            .into();
+        request.cell_id = other_cell_id.clone();
+        let msg = AppRequest::ZomeCall(Box::new(request));
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(
+                response,
+                AppResponse::Error(ExternalApiWireError::CellNotActive { cell_id }) if cell_id == other_cell_id
+            );
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, MsgPackCodec>(msg, app_api)
+            .await
+            .unwrap();
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn identical_read_only_admin_requests_are_coalesced() {
+        /// Counts the requests it handles, answering them once released
+        #[derive(Clone)]
+        struct CountingApi {
+            handled: Arc<AtomicUsize>,
+            released: tokio::sync::watch::Receiver<bool>,
+        }
+
+        #[async_trait::async_trait]
+        impl InterfaceApi for CountingApi {
+            type ApiRequest = AdminRequest;
+            type ApiResponse = AdminResponse;
+            async fn handle_request(
+                &self,
+                request: Result<AdminRequest, SerializedBytesError>,
+            ) -> InterfaceResult<AdminResponse> {
+                self.handled.fetch_add(1, Ordering::SeqCst);
+                let mut released = self.released.clone();
+                while !*released.borrow() {
+                    released.changed().await.unwrap();
+                }
+                match request.unwrap() {
+                    AdminRequest::ListDnas => Ok(AdminResponse::DnasListed(vec![])),
+                    _ => Ok(AdminResponse::AgentPubKeyGenerated(fake_agent_pubkey_1())),
+                }
+            }
+        }
+
+        let (release, released) = tokio::sync::watch::channel(false);
+        let handled = Arc::new(AtomicUsize::new(0));
+        let api = CountingApi {
+            handled: handled.clone(),
+            released,
+        };
+        let inflight = InflightRequests::default();
+        let (tx_response, mut rx_response) = tokio::sync::mpsc::unbounded_channel();
+        let handle = |request: AdminRequest| {
+            let tx_response = tx_response.clone();
+            let respond = move |bytes: SerializedBytes| {
+                let response: AdminResponse = bytes.try_into().unwrap();
+                tx_response.send(response).unwrap();
+                async { Ok(()) }.boxed().into()
+            };
+            let msg = (
+                request.try_into().unwrap(),
+                Respond::Request(Box::new(respond)),
+            );
+            handle_incoming_admin_message::<_, MsgPackCodec>(
+                msg,
+                api.clone(),
+                None,
+                Some(&inflight),
+            )
+            .boxed()
+        };
+
+        // get every request as far as waiting for its response
+        let mut handling = vec![
+            handle(AdminRequest::ListDnas),
+            handle(AdminRequest::ListDnas),
+            handle(AdminRequest::GenerateAgentPubKey),
+            handle(AdminRequest::GenerateAgentPubKey),
+        ];
+        for h in handling.iter_mut() {
+            assert!(h.now_or_never().is_none());
+        }
+        // the second list is answered with the first one's response,
+        // but requests which change things are always handled
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+
+        release.send(true).unwrap();
+        for result in futures::future::join_all(handling).await {
+            result.unwrap();
+        }
+        let mut responses = Vec::new();
+        while let Some(Some(response)) = rx_response.recv().now_or_never() {
+            responses.push(response);
+        }
+        assert_eq!(responses.len(), 4);
+        let listed = responses
+            .iter()
+            .filter(|r| matches!(r, AdminResponse::DnasListed(_)))
+            .count();
+        assert_eq!(listed, 2);
+
+        // a request arriving after the response was sent is handled again
+        assert!(inflight.0.lock().is_empty());
+        handle(AdminRequest::ListDnas).await.unwrap();
+        assert_eq!(handled.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancel_zome_calls() {
+        /// Answers zome calls to "stall" never, and any others straight away
+        #[derive(Clone)]
+        struct StallingApi;
+
+        #[async_trait::async_trait]
+        impl InterfaceApi for StallingApi {
+            type ApiRequest = AppRequest;
+            type ApiResponse = AppResponse;
+            async fn handle_request(
+                &self,
+                request: Result<AppRequest, SerializedBytesError>,
+            ) -> InterfaceResult<AppResponse> {
+                match request.unwrap() {
+                    AppRequest::ZomeCall(call) if call.fn_name == FunctionName::from("stall") => {
+                        futures::future::pending().await
+                    }
+                    AppRequest::ZomeCall(call) => Ok(AppResponse::ZomeCall(Box::new(call.payload))),
+                    request => Ok(AppResponse::Unimplemented(request)),
+                }
+            }
+        }
+
+        let (tx_response, mut rx_response) = tokio::sync::mpsc::unbounded_channel();
+        let msg = |request: AppRequest| -> WebsocketMessage {
+            let tx_response = tx_response.clone();
+            let respond = move |bytes: SerializedBytes| {
+                let response: AppResponse = bytes.try_into().unwrap();
+                tx_response.send(response).unwrap();
+                async { Ok(()) }.boxed().into()
+            };
+            (
+                request.try_into().unwrap(),
+                Respond::Request(Box::new(respond)),
+            )
+        };
+        let zome_call = |fn_name: &str, call_id| {
+            AppRequest::ZomeCall(Box::new(ZomeCall {
+                cell_id: CellId::new(fake_dna_hash(1), fake_agent_pubkey_1()),
+                zome_name: TestWasm::Foo.into(),
+                fn_name: fn_name.into(),
+                payload: ExternIO::encode(()).unwrap(),
+                cap: None,
+                provenance: fake_agent_pubkey_1(),
+                call_id,
+            }))
+        };
+        async fn handle(msg: WebsocketMessage, in_flight: &mut InFlightCalls) {
+            handle_incoming_app_message::<_, MsgPackCodec>(msg, StallingApi, &mut None, in_flight)
+                .await
+                .unwrap()
+        }
+        let mut in_flight = InFlightCalls::default();
+
+        // A call with an id doesn't hold up the connection
+        handle(msg(zome_call("stall", Some(1))), &mut in_flight).await;
+        handle(msg(zome_call("stall", Some(1))), &mut in_flight).await;
+        assert_matches!(
+            rx_response.recv().await,
+            Some(AppResponse::Error(ExternalApiWireError::Validation { field, .. })) if field == "call_id"
+        );
+        handle(msg(zome_call("foo", Some(2))), &mut in_flight).await;
+        assert!(rx_response.recv().now_or_never().is_none());
+
+        // Calls which have completed can't be cancelled
+        assert_matches!(in_flight.next().await, Some(Ok(())));
+        assert_matches!(rx_response.recv().await, Some(AppResponse::ZomeCall(_)));
+        handle(
+            msg(AppRequest::CancelZomeCall { call_id: 2 }),
+            &mut in_flight,
+        )
+        .await;
+        assert_matches!(
+            rx_response.recv().await,
+            Some(AppResponse::Error(ExternalApiWireError::ZomeCallNotFound {
+                call_id: 2
+            }))
+        );
+
+        // Both the stalled call and its cancellation are answered
+        handle(
+            msg(AppRequest::CancelZomeCall { call_id: 1 }),
+            &mut in_flight,
+        )
+        .await;
+        assert_matches!(
+            rx_response.recv().await,
+            Some(AppResponse::ZomeCallCancelled)
+        );
+        assert_matches!(in_flight.next().await, Some(Ok(())));
+        assert_matches!(
+            rx_response.recv().await,
+            Some(AppResponse::ZomeCallCancelled)
+        );
+        assert!(in_flight.is_empty());
+        assert!(!in_flight.cancel(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancelled_zome_calls_commit_nothing() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Create.into(), TestWasm::Create.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let chain_head_seq = || {
+            let app_api = app_api.clone();
+            let request = AppRequest::ChainHead {
+                cell_id: cell_id.clone(),
+            };
+            async move {
+                match app_api.handle_request(Ok(request)).await.unwrap() {
+                    AppResponse::ChainHead(Some(head)) => head.header_seq,
+                    other => panic!("unexpected response {:?}", other),
+                }
+            }
+        };
+        let create_entry = |call_id| {
+            AppRequest::ZomeCall(Box::new(ZomeCall {
+                cell_id: cell_id.clone(),
+                zome_name: TestWasm::Create.into(),
+                fn_name: "create_entry".into(),
+                payload: ExternIO::encode(()).unwrap(),
+                cap: None,
+                provenance: fake_agent_pubkey_1(),
+                call_id,
+            }))
+        };
+        let (tx_response, mut rx_response) = tokio::sync::mpsc::unbounded_channel();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            tx_response.send(response).unwrap();
+            async { Ok(()) }.boxed().into()
+        };
+        // a first call runs the zome's init, so only the entry
+        // is committed by the calls after it
+        assert_matches!(
+            app_api.handle_request(Ok(create_entry(None))).await,
+            Ok(AppResponse::ZomeCall(_))
+        );
+        let head_seq = chain_head_seq().await;
+
+        // start the call, then cancel it while it is running
+        let mut in_flight = InFlightCalls::default();
+        handle_incoming_app_message::<_, MsgPackCodec>(
+            (
+                create_entry(Some(1)).try_into().unwrap(),
+                Respond::Request(Box::new(respond)),
+            ),
+            app_api.clone(),
+            &mut None,
+            &mut in_flight,
+        )
+        .await
+        .unwrap();
+        assert!(in_flight.next().now_or_never().is_none());
+        assert!(in_flight.cancel(1));
+        assert_matches!(in_flight.next().await, Some(Ok(())));
+        assert_matches!(
+            rx_response.recv().await,
+            Some(AppResponse::ZomeCallCancelled)
+        );
+
+        // the zome function may still be running, but what it
+        // writes is never flushed to the source chain
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert_eq!(chain_head_seq().await, head_seq);
+
+        // the same call left to finish does commit
+        assert_matches!(
+            app_api.handle_request(Ok(create_entry(None))).await,
+            Ok(AppResponse::ZomeCall(_))
+        );
+        assert_eq!(chain_head_seq().await, head_seq + 1);
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn draining_answers_zome_calls_in_flight() {
+        /// Answers zome calls once released
+        #[derive(Clone)]
+        struct ReleasedApi {
+            started: Arc<tokio::sync::Notify>,
+            released: tokio::sync::watch::Receiver<bool>,
+        }
+
+        #[async_trait::async_trait]
+        impl InterfaceApi for ReleasedApi {
+            type ApiRequest = AppRequest;
+            type ApiResponse = AppResponse;
+            async fn handle_request(
+                &self,
+                request: Result<AppRequest, SerializedBytesError>,
+            ) -> InterfaceResult<AppResponse> {
+                self.started.notify_one();
+                let mut released = self.released.clone();
+                while !*released.borrow() {
+                    released.changed().await.unwrap();
+                }
+                match request.unwrap() {
+                    AppRequest::ZomeCall(call) => Ok(AppResponse::ZomeCall(Box::new(call.payload))),
+                    request => Ok(AppResponse::Unimplemented(request)),
+                }
+            }
+        }
+
+        use holochain_conductor_api::SignalOverflowPolicy;
+
+        observability::test_run().ok();
+        let (release, released) = tokio::sync::watch::channel(false);
+        let started = Arc::new(tokio::sync::Notify::new());
+        let api = ReleasedApi {
+            started: started.clone(),
+            released,
+        };
+        let (drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+        let (_stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+        let (port, mut task) = spawn_app_interface_task(
+            SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0)),
+            api,
+            InterfaceSignalSender::new(SignalOverflowPolicy::DropOldest),
+            AppInterfaceOptions::default(),
+            InterfaceContext::default(),
+            drain_rx,
+            stop_rx,
+        )
+        .await
+        .unwrap();
+        let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, port));
+        let (mut client, mut client_rx) =
+            holochain_websocket::connect(websocket_url(addr), Arc::new(WebsocketConfig::default()))
+                .await
+                .unwrap();
+
+        // a call made with an id is being handled when the interface drains
+        let call = AppRequest::ZomeCall(Box::new(ZomeCall {
+            cell_id: CellId::new(fake_dna_hash(1), fake_agent_pubkey_1()),
+            zome_name: TestWasm::Foo.into(),
+            fn_name: "foo".into(),
+            payload: ExternIO::encode(()).unwrap(),
+            cap: None,
+            provenance: fake_agent_pubkey_1(),
+            call_id: Some(1),
+        }));
+        let response = tokio::spawn(async move {
+            let response: AppResponse = client.request(call).await.unwrap();
+            response
+        });
+        started.notified().await;
+        drain_tx.send(()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!((&mut task).now_or_never().is_none());
+
+        // it is still answered before the connection closes
+        release.send(true).unwrap();
+        let timeout = DRAIN_DEADLINE + SHUTDOWN_DEADLINE;
+        let response = tokio::time::timeout(timeout, response)
+            .await
+            .expect("zome call was not answered")
+            .unwrap();
+        assert_matches!(response, AppResponse::ZomeCall(_));
+        let exit = tokio::time::timeout(timeout, task)
+            .await
+            .expect("interface did not drain in time")
+            .unwrap()
+            .unwrap();
+        assert_eq!(exit, InterfaceTaskExit::Drained);
+        let closed = tokio::time::timeout(timeout, client_rx.next())
+            .await
+            .expect("client was not disconnected");
+        assert!(closed.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chain_head() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .with(predicate::eq(dna_hash))
+            .returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
+        let msg = AppRequest::ChainHead { cell_id };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            // genesis has committed at least the dna, the agent validation
+            // package and the agent key
+            assert_matches!(response, AppResponse::ChainHead(Some(head)) if head.header_seq >= 2);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, MsgPackCodec>(msg, app_api)
+            .await
+            .unwrap();
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn activate_app() {
+        observability::test_run().ok();
+        let agent_key = fake_agent_pubkey_1();
+        let mut dnas = Vec::new();
+        for _i in 0..2 as u32 {
+            let zomes = vec![TestWasm::Foo.into()];
+            let def = DnaDef::unique_from_zomes(zomes.clone());
+            dnas.push(DnaFile::new(def, vec![TestWasm::Foo.into()]).await.unwrap());
+        }
+        let dna_map = dnas
+            .iter()
+            .cloned()
+            .map(|dna| (dna.dna_hash().clone(), dna))
+            .collect::<HashMap<_, _>>();
+        let dna_hashes = dna_map.keys().cloned().collect::<Vec<_>>();
+        let cell_ids_with_proofs = dna_hashes
+            .iter()
+            .cloned()
+            .map(|hash| (CellId::from((hash, agent_key.clone())), None))
+            .collect::<Vec<_>>();
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .returning(move |hash| dna_map.get(&hash).cloned());
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(cell_ids_with_proofs, dna_store).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+
+        // Activate the app
+        let msg = AdminRequest::ActivateApp {
+            installed_app_id: "test app".to_string(),
+            dry_run: false,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppActivated);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+
+        handle_incoming_message::<_, MsgPackCodec>(
+            msg,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Get the state
+        let state: ConductorState = conductor_handle.get_state_from_handle().await.unwrap();
+
+        // Check it is not in inactive apps
+        let r = state.inactive_apps.get("test app");
+        assert_eq!(r, None);
+
+        // Check it is in active apps
+        let cell_ids: HashSet<CellId> = state
+            .active_apps
+            .get("test app")
+            .unwrap()
+            .all_cells()
+            .cloned()
+            .collect();
+
+        // Collect the expected result
+        let expected = dna_hashes
+            .into_iter()
+            .map(|hash| CellId::from((hash, agent_key.clone())))
+            .collect::<HashSet<_>>();
+
+        assert_eq!(expected, cell_ids);
+
+        // Check that it is returned in get_app_info as active
+        let maybe_info = state.get_app_info(&"test app".to_string());
+        if let Some(info) = maybe_info {
+            assert_eq!(info.installed_app_id, "test app");
+            assert_matches!(info.status, InstalledAppStatus::Active);
+        } else {
+            assert!(false);
+        }
+
+        // Now deactivate app
+        let msg = AdminRequest::DeactivateApp {
+            installed_app_id: "test app".to_string(),
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppDeactivated);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+
+        handle_incoming_message::<_, MsgPackCodec>(
+            msg,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Get the state
+        let state = conductor_handle.get_state_from_handle().await.unwrap();
+
+        // Check it's removed from active
+        let r = state.active_apps.get("test app");
+        assert_eq!(r, None);
+
+        // Check it's added to inactive
+        let cell_ids: HashSet<CellId> = state
+            .inactive_apps
+            .get("test app")
+            .unwrap()
+            .all_cells()
+            .cloned()
+            .collect();
+
+        assert_eq!(expected, cell_ids);
+
+        // Check that it is returned in get_app_info as not active
+        let maybe_info = state.get_app_info(&"test app".to_string());
+        if let Some(info) = maybe_info {
+            assert_eq!(info.installed_app_id, "test app");
+            assert_matches!(info.status, InstalledAppStatus::Inactive { .. });
+        } else {
+            assert!(false);
+        }
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_interface() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            bind_address: None,
+            signal_coalesce_window_ms: None,
+            eager_signals: false,
+            rate_limit: None,
+            idle_timeout_ms: None,
+            reconnect_backoff_ms: None,
+            signal_overflow: Default::default(),
+            signal_encode_failure: Default::default(),
+            installed_app_id: None,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppInterfaceAttached { .. });
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn forbidden_request() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let allowed: HashSet<String> = vec!["list_app_interfaces".to_string()]
+            .into_iter()
+            .collect();
+
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            bind_address: None,
+            signal_coalesce_window_ms: None,
+            eager_signals: false,
+            rate_limit: None,
+            idle_timeout_ms: None,
+            reconnect_backoff_ms: None,
+            signal_overflow: Default::default(),
+            signal_encode_failure: Default::default(),
+            installed_app_id: None,
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(
+                response,
+                AdminResponse::Error(ExternalApiWireError::Forbidden(_))
+            );
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_admin_message::<_, MsgPackCodec>(
+            msg,
+            admin_api.clone(),
+            Some(&allowed),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let msg = AdminRequest::ListAppInterfaces;
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::AppInterfacesListed(v) if v.is_empty());
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_admin_message::<_, MsgPackCodec>(msg, admin_api, Some(&allowed), None)
+            .await
+            .unwrap();
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_interface_binds_ipv6() {
+        observability::test_run().ok();
+        // skip if this environment has no IPv6 loopback
+        if std::net::TcpListener::bind("[::1]:0").is_err() {
+            return;
+        }
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let (_drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+        let (_stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+
+        let addr = SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, 0));
+        let (handle, listener) = spawn_websocket_listener(addr, WebsocketConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(handle.local_addr().host_str(), Some("[::1]"));
+        let addr = listener_socket_addr(&handle).unwrap();
+        assert_eq!(addr.ip(), std::net::Ipv6Addr::LOCALHOST);
+        let _task = spawn_admin_interface_task(
+            handle,
+            listener,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            AdminInterfaceOptions::default(),
+            InterfaceContext::default(),
+            drain_rx,
+            stop_rx,
+        )
+        .unwrap();
+
+        let (mut client, _) =
+            holochain_websocket::connect(websocket_url(addr), Arc::new(WebsocketConfig::default()))
+                .await
+                .unwrap();
+        let response: AdminResponse = client
+            .request(AdminRequest::ListAppInterfaces)
+            .await
+            .unwrap();
+        assert_matches!(response, AdminResponse::AppInterfacesListed(_));
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_listener_dying_is_reported() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let (_drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+        let (_stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+
+        // the real listener keeps hold of the port, so re-binding fails
+        let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+        let (handle, _listener) = spawn_websocket_listener(addr, WebsocketConfig::default())
+            .await
+            .unwrap();
+        let addr = listener_socket_addr(&handle).unwrap();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let counters = InterfaceCounters::default();
+        let hook: ConnectionHook = {
+            let events = events.clone();
+            let counting = counters.hook();
+            Arc::new(
+                move |kind: InterfaceKind, remote_addr: &str, event: ConnectionEvent| {
+                    counting(kind, remote_addr, event);
+                    events
+                        .lock()
+                        .unwrap()
+                        .push((kind, remote_addr.to_string(), event));
+                },
+            )
+        };
+        let recent_errors = RecentInterfaceErrors::new(10);
+        let task = spawn_admin_interface_task(
+            handle,
+            futures::stream::empty::<ListenerItem>(),
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            AdminInterfaceOptions::default(),
+            InterfaceContext {
+                connection_hook: Some(hook),
+                recent_errors: Some(recent_errors.clone()),
+                ..Default::default()
+            },
+            drain_rx,
+            stop_rx,
+        )
+        .unwrap();
+
+        assert_eq!(
+            task.await.unwrap().unwrap(),
+            InterfaceTaskExit::ListenerClosed
+        );
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(
+                InterfaceKind::Admin,
+                addr.to_string(),
+                ConnectionEvent::ListenerDied
+            )]
+        );
+        assert_eq!(counters.admin_listener_died(), 1);
+        assert_matches!(
+            recent_errors.list().as_slice(),
+            [error] if error.interface == "admin" && error.remote_addr == addr.to_string()
+        );
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn overloaded_interface_stops_accepting() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let (_drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+        let (_stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+        let load = InterfaceLoad::new(Some(1));
+        let pending = load.request_started();
+        assert!(load.is_overloaded());
+
+        let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+        let (handle, listener) = spawn_websocket_listener(addr, WebsocketConfig::default())
+            .await
+            .unwrap();
+        let addr = listener_socket_addr(&handle).unwrap();
+        let _task = spawn_admin_interface_task(
+            handle,
+            listener,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            AdminInterfaceOptions::default(),
+            InterfaceContext {
+                load: Some(load.clone()),
+                ..Default::default()
+            },
+            drain_rx,
+            stop_rx,
+        )
+        .unwrap();
+
+        // the client waits in the backlog while the conductor is overloaded
+        let mut connect = Box::pin(holochain_websocket::connect(
+            websocket_url(addr),
+            Arc::new(WebsocketConfig::default()),
+        ));
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(300), &mut connect)
+                .await
+                .is_err()
+        );
+
+        // and is accepted once a request has been answered
+        drop(pending);
+        assert!(!load.is_overloaded());
+        let (mut client, _) = tokio::time::timeout(std::time::Duration::from_secs(5), connect)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: AdminResponse = client
+            .request(AdminRequest::ListAppInterfaces)
+            .await
+            .unwrap();
+        assert_matches!(response, AdminResponse::AppInterfacesListed(_));
+        assert_eq!(load.pending(), 0);
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn listener_is_tuned_by_websocket_config() {
+        observability::test_run().ok();
+        let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+        let websocket_config = WebsocketConfig::default().max_message_size(1024);
+        let (handle, listener) = spawn_websocket_listener(addr, websocket_config)
+            .await
+            .unwrap();
+        let mut listener = Box::pin(listener);
+        let addr = listener_socket_addr(&handle).unwrap();
+        let message = |len: usize| SerializedBytes::from(UnsafeBytes::from(vec![0u8; len]));
+        let within = std::time::Duration::from_secs(5);
+
+        for (len, fits) in &[(100, true), (4096, false)] {
+            let (mut client, _client_rx) = holochain_websocket::connect(
+                websocket_url(addr),
+                Arc::new(WebsocketConfig::default()),
+            )
+            .await
+            .unwrap();
+            let (_tx_to_iface, mut rx_from_iface) = listener.next().await.unwrap().unwrap();
+            client.signal(message(*len)).await.unwrap();
+            let received = tokio::time::timeout(within, rx_from_iface.next())
+                .await
+                .unwrap();
+            // too large a message ends the connection
+            assert_eq!(received.is_some(), *fits, "{} byte message", len);
+        }
+
+        handle.close();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn connections_share_one_shutdown_deadline() {
+        let mut connections = Connections::new(InterfaceKind::Admin, None, None);
+        connections.spawn("ws://127.0.0.1:1".to_string(), async { Ok(()) });
+        for i in 0..5 {
+            connections.spawn(
+                format!("ws://127.0.0.1:{}", i + 2),
+                futures::future::pending::<InterfaceResult<()>>(),
+            );
+        }
+        let deadline = std::time::Duration::from_millis(200);
+        let start = std::time::Instant::now();
+        connections.join(deadline).await;
+        // All stuck connections are waited on together, not one after another
+        assert!(start.elapsed() < deadline * 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn finished_connections_are_forgotten() {
+        let mut connections = Connections::new(InterfaceKind::Admin, None, None);
+        for _ in 0..10 {
+            for i in 0..20 {
+                connections.spawn(format!("ws://127.0.0.1:{}", i + 1), async { Ok(()) });
+            }
+            for _ in 0..20 {
+                let id = connections.tasks.next().await.unwrap();
+                connections.finished(id);
+            }
+            assert!(connections.remote_addrs.is_empty());
+            assert!(connections.tasks.is_empty());
+        }
+        assert_eq!(connections.next_id, 200);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn connection_hook_sees_accept_and_close() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook: ConnectionHook = {
+            let events = events.clone();
+            Arc::new(
+                move |kind: InterfaceKind, remote_addr: &str, event: ConnectionEvent| {
+                    events
+                        .lock()
+                        .unwrap()
+                        .push((kind, remote_addr.to_string(), event));
+                },
+            )
+        };
+        let remote_addr = "ws://127.0.0.1:1".to_string();
+        let mut connections = Connections::new(InterfaceKind::App, Some(hook), None);
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+        connections.spawn(remote_addr.clone(), async move {
+            done_rx.await.ok();
+            Ok(())
+        });
+        let accepted = (
+            InterfaceKind::App,
+            remote_addr.clone(),
+            ConnectionEvent::Accepted,
+        );
+        assert_eq!(*events.lock().unwrap(), vec![accepted.clone()]);
+
+        done_tx.send(()).unwrap();
+        connections.join(SHUTDOWN_DEADLINE).await;
+        let closed = (InterfaceKind::App, remote_addr, ConnectionEvent::Closed);
+        assert_eq!(*events.lock().unwrap(), vec![accepted, closed]);
+    }
+
+    #[test]
+    fn connection_hook_sees_failed_handshakes_only() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook: ConnectionHook = {
+            let events = events.clone();
+            Arc::new(
+                move |kind: InterfaceKind, remote_addr: &str, event: ConnectionEvent| {
+                    events
+                        .lock()
+                        .unwrap()
+                        .push((kind, remote_addr.to_string(), event));
+                },
+            )
+        };
+        let connections = Connections::new(InterfaceKind::Admin, Some(hook), None);
+        connections.connection_failed(WebsocketError::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "too many open files",
+        )));
+        assert!(events.lock().unwrap().is_empty());
+
+        connections.connection_failed(WebsocketError::Handshake {
+            remote_addr: "127.0.0.1:1".to_string(),
+            reason: "not a websocket upgrade".to_string(),
+        });
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(
+                InterfaceKind::Admin,
+                "127.0.0.1:1".to_string(),
+                ConnectionEvent::HandshakeFailed
+            )]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recent_errors_keep_the_latest_connection_errors() {
+        let recent_errors = RecentInterfaceErrors::new(2);
+        let mut connections =
+            Connections::new(InterfaceKind::App, None, Some(recent_errors.clone()));
+        // connections that ran their course aren't errors
+        connections.spawn("ws://127.0.0.1:1".to_string(), async { Ok(()) });
+        connections.spawn("ws://127.0.0.1:2".to_string(), async {
+            Err(InterfaceError::ShuttingDown)
+        });
+        connections.tasks.next().await;
+        connections.tasks.next().await;
+        // one at a time, so they are kept in order
+        for i in 3..6 {
+            connections.spawn(format!("ws://127.0.0.1:{}", i), async {
+                Err(InterfaceError::SendError)
+            });
+            connections.tasks.next().await;
+        }
+        connections.join(SHUTDOWN_DEADLINE).await;
+
+        // only the most recent are kept, oldest first
+        let errors = recent_errors.list();
+        let remote_addrs: Vec<_> = errors.iter().map(|e| e.remote_addr.as_str()).collect();
+        assert_eq!(remote_addrs, vec!["ws://127.0.0.1:4", "ws://127.0.0.1:5"]);
+        assert!(errors.iter().all(|e| e.interface == "app"
+            && e.error == InterfaceError::SendError.to_string()
+            && e.timestamp_ms > 0));
+
+        connections.connection_failed(WebsocketError::Handshake {
+            remote_addr: "127.0.0.1:6".to_string(),
+            reason: "not a websocket upgrade".to_string(),
+        });
+        let errors = recent_errors.list();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].remote_addr, "127.0.0.1:6");
+
+        // a buffer of no errors keeps nothing
+        let none_kept = RecentInterfaceErrors::new(0);
+        none_kept.record(InterfaceKind::Admin, "127.0.0.1:1", "oops");
+        assert!(none_kept.list().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_interface_over_socket() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let mut harness = AdminInterfaceHarness::spawn(
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            Some(vec!["list_app_interfaces".to_string()]),
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        assert_matches!(
+            harness.request(AdminRequest::ListAppInterfaces).await,
+            AdminResponse::AppInterfacesListed(_)
+        );
+        assert_matches!(
+            harness.request(AdminRequest::ListDnas).await,
+            AdminResponse::Error(ExternalApiWireError::Forbidden(_))
+        );
+
+        // a second client is served alongside the first
+        let (mut client, _client_rx) = harness.connect().await;
+        let response: AdminResponse = client
+            .request(AdminRequest::ListAppInterfaces)
+            .await
+            .unwrap();
+        assert_matches!(response, AdminResponse::AppInterfacesListed(_));
+
+        harness.shutdown().await;
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn draining_an_interface_leaves_the_conductor_running() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let mut harness = AdminInterfaceHarness::spawn(
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert_matches!(
+            harness.request(AdminRequest::ListAppInterfaces).await,
+            AdminResponse::AppInterfacesListed(_)
+        );
+
+        harness.drain().await;
+        // the conductor is still there to be stopped afterwards
+        assert!(conductor_handle.list_dnas().await.is_ok());
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_interface_closes_idle_connections() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let mut harness = AdminInterfaceHarness::spawn(
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            None,
+            None,
+            Some(std::time::Duration::from_millis(200)),
+            None,
+        )
+        .await;
+
+        // activity keeps the connection open
+        for _ in 0..3 {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert_matches!(
+                harness.request(AdminRequest::ListAppInterfaces).await,
+                AdminResponse::AppInterfacesListed(_)
+            );
+        }
+
+        // then going quiet gets it closed
+        let closed =
+            tokio::time::timeout(std::time::Duration::from_secs(5), harness.client_rx.next())
+                .await
+                .expect("idle connection was not closed");
+        assert!(closed.is_none());
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_recv_loop_reports_how_it_ended() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+        let (handle, listener) = spawn_websocket_listener(addr, WebsocketConfig::default())
+            .await
+            .unwrap();
+        let mut listener = Box::pin(listener);
+        let addr = listener_socket_addr(&handle).unwrap();
+        let connect = || {
+            holochain_websocket::connect(websocket_url(addr), Arc::new(WebsocketConfig::default()))
+        };
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let num_connections = Arc::new(AtomicIsize::new(3));
+
+        let recv = |tx_to_iface: WebsocketSender,
+                    rx_from_iface: WebsocketReceiver,
+                    idle_timeout: Option<std::time::Duration>,
+                    max_lifetime: Option<std::time::Duration>| {
+            let settings = AdminConnectionSettings::from(AdminInterfaceOptions {
+                idle_timeout,
+                max_connection_lifetime: max_lifetime,
+                ..Default::default()
+            });
+            recv_incoming_admin_msgs::<_, MsgPackCodec>(
+                api.clone(),
+                settings,
+                tx_to_iface,
+                rx_from_iface,
+                shutdown_rx.clone(),
+                num_connections.clone(),
+                Connections::new(InterfaceKind::Admin, None, None).reporter("127.0.0.1:1"),
+            )
+        };
+
+        // a client going away ends the connection cleanly
+        let client = connect().await.unwrap();
+        let (tx_to_iface, rx_from_iface) = listener.next().await.unwrap().unwrap();
+        drop(client);
+        assert_matches!(recv(tx_to_iface, rx_from_iface, None, None).await, Ok(()));
+
+        // an idle client is closed with an error
+        let _client = connect().await.unwrap();
+        let (tx_to_iface, rx_from_iface) = listener.next().await.unwrap().unwrap();
+        let idle_timeout = Some(std::time::Duration::from_millis(100));
+        assert_matches!(
+            recv(tx_to_iface, rx_from_iface, idle_timeout, None).await,
+            Err(InterfaceError::IdleTimeout(_))
+        );
+
+        // as is a client that has been connected for too long
+        let _client = connect().await.unwrap();
+        let (tx_to_iface, rx_from_iface) = listener.next().await.unwrap().unwrap();
+        let max_lifetime = Some(std::time::Duration::from_millis(100));
+        assert_matches!(
+            recv(tx_to_iface, rx_from_iface, None, max_lifetime).await,
+            Err(InterfaceError::MaxLifetime(_))
+        );
+
+        // whichever way it ends, the connection is no longer counted
+        assert_eq!(num_connections.load(Ordering::SeqCst), 0);
+
+        handle.close();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn coalesce_signals() {
+        let cell_a = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let cell_b = CellId::new(fake_dna_hash(2), fake_agent_pubkey_1());
+        let started = AppSignalSeq::start();
+        let app_signal = |cell_id: &CellId, n: u32| {
+            Signal::App(
+                cell_id.clone(),
+                AppSignal::new(ExternIO::encode(n).unwrap()),
+                AppSignalSeq {
+                    seq: n as u64,
+                    ..started
+                },
+            )
+        };
+
+        let mut pending = Vec::new();
+        coalesce_signal(&mut pending, app_signal(&cell_a, 1));
+        coalesce_signal(&mut pending, test_signal("first"));
+        coalesce_signal(&mut pending, app_signal(&cell_b, 1));
+        coalesce_signal(&mut pending, app_signal(&cell_a, 2));
+        coalesce_signal(&mut pending, test_signal("second"));
+
+        assert_eq!(
+            pending,
+            vec![
+                app_signal(&cell_a, 2),
+                test_signal("second"),
+                app_signal(&cell_b, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn signal_subscription_filters_by_cell_and_kind() {
+        use holochain_conductor_api::signal_subscription::*;
+
+        let cell_a = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let cell_b = CellId::new(fake_dna_hash(2), fake_agent_pubkey_1());
+        let app_signal = |cell_id: &CellId| {
+            Signal::App(
+                cell_id.clone(),
+                AppSignal::new(ExternIO::encode(()).unwrap()),
+                AppSignalSeq::start(),
+            )
+        };
+
+        let all = SignalSubscription::new("app".into(), SignalFilterSet::allow_all());
+        assert!(all.allows(&app_signal(&cell_a)));
+        assert!(all.allows(&test_signal("system")));
+
+        let app_only = all.with_kinds(vec![SignalKind::App]);
+        assert!(app_only.allows(&app_signal(&cell_a)));
+        assert!(!app_only.allows(&test_signal("system")));
+
+        let cell_a_only = SignalSubscription::new(
+            "app".into(),
+            SignalFilterSet::Include(
+                vec![(cell_a.clone(), SignalFilter::empty())]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+        assert!(cell_a_only.allows(&app_signal(&cell_a)));
+        assert!(!cell_a_only.allows(&app_signal(&cell_b)));
+        assert!(!cell_a_only.allows(&test_signal("system")));
+
+        let none = SignalSubscription::new("app".into(), SignalFilterSet::block_all());
+        assert!(!none.allows(&app_signal(&cell_a)));
+    }
+
+    #[test]
+    fn close_reason_suggests_backoff_on_shutdown_and_overload() {
+        let backoff = std::time::Duration::from_millis(2500);
+        assert_eq!(
+            close_reason_text(&InterfaceError::ShuttingDown, backoff),
+            "shutting_down; retry_after_ms=2500: The interface is shutting down"
+        );
+        assert_eq!(
+            close_reason_text(&InterfaceError::Overloaded, backoff),
+            "overloaded; retry_after_ms=2500: The interface has too many connections"
+        );
+        assert_eq!(
+            close_reason_text(
+                &InterfaceError::RequestHandler(Box::new(
+                    crate::conductor::error::ConductorError::ShuttingDown,
+                )),
+                backoff
+            )
+            .split(':')
+            .next(),
+            Some("shutting_down; retry_after_ms=2500")
+        );
+        assert_eq!(
+            close_reason_text(
+                &InterfaceError::IdleTimeout(std::time::Duration::from_secs(1)),
+                backoff
+            ),
+            "idle_timeout: No message received for 1s"
+        );
+    }
+
+    #[test]
+    fn rate_limiter_allows_burst_then_limits() {
+        let mut limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_second: 1,
+            burst: 3,
+        });
+        for _ in 0..3 {
+            assert!(limiter.check().is_ok());
+        }
+        let wait = limiter.check().unwrap_err();
+        assert!(wait <= std::time::Duration::from_secs(1));
+        assert_matches!(
+            rate_limited(wait),
+            ExternalApiWireError::RateLimited { retry_after_ms } if retry_after_ms > 0 && retry_after_ms <= 1000
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signal_overflow_policies() {
+        use holochain_conductor_api::SignalOverflowPolicy;
+
+        // A connection which falls behind skips ahead to the newest signals
+        let signal_tx = InterfaceSignalSender::new(SignalOverflowPolicy::DropOldest);
+        let mut signal_rx = signal_tx.subscribe();
+        for i in 0..SIGNAL_BUFFER_SIZE + 1 {
+            signal_tx.send(test_signal(&i.to_string())).await.unwrap();
+        }
+        assert_eq!(signal_rx.recv().await, Some(test_signal("1")));
+
+        // A connection which falls behind holds up the sender until it catches up
+        let signal_tx = InterfaceSignalSender::new(SignalOverflowPolicy::Block);
+        let mut signal_rx = signal_tx.subscribe();
+        for i in 0..SIGNAL_BUFFER_SIZE {
+            signal_tx.send(test_signal(&i.to_string())).await.unwrap();
+        }
+        let blocked = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            signal_tx.send(test_signal("last")),
+        )
+        .await;
+        assert!(blocked.is_err());
+        assert_eq!(signal_rx.recv().await, Some(test_signal("0")));
+        signal_tx.send(test_signal("last")).await.unwrap();
+
+        // Connections which have gone away no longer hold up the sender
+        drop(signal_rx);
+        for i in 0..SIGNAL_BUFFER_SIZE + 1 {
+            signal_tx.send(test_signal(&i.to_string())).await.unwrap();
+        }
+
+        // Nor are they kept around while no signals are sent
+        for _ in 0..100 {
+            drop(signal_tx.subscribe());
+        }
+        match &signal_tx {
+            InterfaceSignalSender::Block(signal_txs) => {
+                assert_eq!(signal_txs.lock().len(), 1)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_signals_are_numbered_in_order() {
+        use holochain_conductor_api::SignalOverflowPolicy;
+        const EMITTERS: u64 = 8;
+        const SIGNALS_PER_EMITTER: u64 = 50;
+
+        let cell_id = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let app_signal = |i: u64| AppSignal::new(ExternIO::encode(i).unwrap());
+        // a connection which is never skipped ahead
+        let signal_tx = InterfaceSignalSender::new(SignalOverflowPolicy::Block);
+        let mut signal_rx = signal_tx.subscribe();
+
+        // each emitter has its own broadcaster, as each zome call does,
+        // all counting with the cell's counter
+        let seq = AppSignalCounter::default();
+        let emitters: Vec<_> = (0..EMITTERS)
+            .map(|_| {
+                let mut broadcaster = SignalBroadcaster::new(vec![signal_tx.clone()])
+                    .with_app_signal_seq(seq.clone());
+                let cell_id = cell_id.clone();
+                tokio::spawn(async move {
+                    for i in 0..SIGNALS_PER_EMITTER {
+                        broadcaster
+                            .send_app_signal(cell_id.clone(), app_signal(i))
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        let mut started_at = None;
+        for expected in 0..EMITTERS * SIGNALS_PER_EMITTER {
+            let numbered = match signal_rx.recv().await {
+                Some(Signal::App(_, _, seq)) => seq,
+                r => panic!("unexpected signal {:?}", r),
+            };
+            assert_eq!(numbered.seq, expected);
+            // numbered by the one counter throughout
+            assert_eq!(
+                *started_at.get_or_insert(numbered.started_at),
+                numbered.started_at
+            );
+        }
+        for emitter in emitters {
+            emitter.await.unwrap();
+        }
+
+        // another cell counts on its own
+        let mut other = SignalBroadcaster::new(vec![signal_tx.clone()]);
+        other.send_app_signal(cell_id, app_signal(0)).await.unwrap();
+        assert_matches!(
+            signal_rx.recv().await,
+            Some(Signal::App(_, _, AppSignalSeq { seq: 0, .. }))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signals_without_subscribers_are_dropped() {
+        use holochain_conductor_api::SignalOverflowPolicy;
+
+        let cell_id = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let app_signal = || AppSignal::new(ExternIO::encode(()).unwrap());
+        let drop_oldest = InterfaceSignalSender::new(SignalOverflowPolicy::DropOldest);
+        let block = InterfaceSignalSender::new(SignalOverflowPolicy::Block);
+        let mut broadcaster = SignalBroadcaster::new(vec![drop_oldest.clone(), block.clone()]);
+        assert!(!broadcaster.has_subscribers());
+        assert!(!SignalBroadcaster::new(Vec::new()).has_subscribers());
+
+        // nobody is connected, so this is dropped without being numbered
+        broadcaster
+            .send_app_signal(cell_id.clone(), app_signal())
+            .await
+            .unwrap();
+
+        let mut signal_rx = block.subscribe();
+        assert!(block.has_subscribers());
+        assert!(!drop_oldest.has_subscribers());
+        assert!(broadcaster.has_subscribers());
+        broadcaster
+            .send_app_signal(cell_id.clone(), app_signal())
+            .await
+            .unwrap();
+        assert_matches!(
+            signal_rx.recv().await,
+            Some(Signal::App(_, _, AppSignalSeq { seq: 0, .. }))
+        );
+
+        // a connection going away stops the sending again
+        drop(signal_rx);
+        assert!(!broadcaster.has_subscribers());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_signal_receivers_dont_hold_up_the_interface() {
+        use holochain_conductor_api::SignalOverflowPolicy;
+
+        /// Answers every request as unimplemented
+        #[derive(Clone)]
+        struct UnimplementedApi;
+
+        #[async_trait::async_trait]
+        impl InterfaceApi for UnimplementedApi {
+            type ApiRequest = AppRequest;
+            type ApiResponse = AppResponse;
+            async fn handle_request(
+                &self,
+                request: Result<AppRequest, SerializedBytesError>,
+            ) -> InterfaceResult<AppResponse> {
+                Ok(AppResponse::Unimplemented(request.unwrap()))
+            }
+        }
+
+        observability::test_run().ok();
+        // a connection which never reads its signals,
+        // with a send waiting for it to make room
+        let signal_tx = InterfaceSignalSender::new(SignalOverflowPolicy::Block);
+        let _stalled = signal_tx.subscribe();
+        for i in 0..SIGNAL_BUFFER_SIZE {
+            signal_tx.send(test_signal(&i.to_string())).await.unwrap();
+        }
+        let mut blocked = {
+            let signal_tx = signal_tx.clone();
+            tokio::spawn(async move { signal_tx.send(test_signal("blocked")).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!((&mut blocked).now_or_never().is_none());
+
+        for expected in vec![InterfaceTaskExit::Stopped, InterfaceTaskExit::Drained] {
+            let (drain_tx, drain_rx) = tokio::sync::broadcast::channel(1);
+            let (stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+            let (port, task) = spawn_app_interface_task(
+                SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0)),
+                UnimplementedApi,
+                signal_tx.clone(),
+                AppInterfaceOptions::default(),
+                InterfaceContext::default(),
+                drain_rx,
+                stop_rx,
+            )
+            .await
+            .unwrap();
+
+            // new connections are still accepted and answered
+            let addr = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, port));
+            let (mut client, _client_rx) = holochain_websocket::connect(
+                websocket_url(addr),
+                Arc::new(WebsocketConfig::default()),
+            )
+            .await
+            .unwrap();
+            let request = AppRequest::AppInfo {
+                installed_app_id: "app".into(),
+            };
+            let response: AppResponse =
+                tokio::time::timeout(SHUTDOWN_DEADLINE * 5, client.request(request))
+                    .await
+                    .expect("the connection was not answered")
+                    .unwrap();
+            assert_matches!(response, AppResponse::Unimplemented(_));
+
+            // and the interface still stops
+            match expected {
+                InterfaceTaskExit::Stopped => stop_tx.send(()).unwrap(),
+                _ => drain_tx.send(()).unwrap(),
+            };
+            let exit = tokio::time::timeout(DRAIN_DEADLINE + SHUTDOWN_DEADLINE * 5, task)
+                .await
+                .expect("the interface did not stop in time")
+                .unwrap()
+                .unwrap();
+            assert_eq!(exit, expected);
+        }
+        assert!(blocked.now_or_never().is_none());
+    }
+
+    #[test]
+    fn signal_encode_failure_policies() {
+        use holochain_serialized_bytes::SerializedBytesError;
+
+        /// A codec which can't encode anything
+        struct BrokenCodec;
+
+        impl WireCodec for BrokenCodec {
+            fn decode<T: serde::de::DeserializeOwned>(
+                bytes: SerializedBytes,
+            ) -> Result<T, SerializedBytesError> {
+                MsgPackCodec::decode(bytes)
+            }
+
+            fn encode<T: serde::Serialize>(_: &T) -> Result<SerializedBytes, SerializedBytesError> {
+                Err(SerializedBytesError::Serialize("broken".to_string()))
+            }
+        }
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let counters = InterfaceCounters::default();
+        let hook: ConnectionHook = {
+            let events = events.clone();
+            let counting = counters.hook();
+            Arc::new(
+                move |kind: InterfaceKind, remote_addr: &str, event: ConnectionEvent| {
+                    counting(kind, remote_addr, event);
+                    events.lock().unwrap().push(event);
+                },
+            )
+        };
+        let reporter =
+            Connections::new(InterfaceKind::App, Some(hook), None).reporter("127.0.0.1:1");
+        let cell_id = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let app_signal = Signal::App(
+            cell_id,
+            AppSignal::new(ExternIO::encode(()).unwrap()),
+            AppSignalSeq::start(),
+        );
+
+        // Encoding that works is untouched by the policy
+        let encoded = encode_signal::<MsgPackCodec>(
+            &app_signal,
+            true,
+            SignalEncodeFailurePolicy::Close,
+            &reporter,
+        );
+        assert!(matches!(encoded, Ok(Some(_))));
+
+        let closed = encode_signal::<BrokenCodec>(
+            &app_signal,
+            true,
+            SignalEncodeFailurePolicy::Close,
+            &reporter,
+        );
+        assert!(closed.is_err());
+        let skipped = encode_signal::<BrokenCodec>(
+            &test_signal("bad"),
+            true,
+            SignalEncodeFailurePolicy::Skip,
+            &reporter,
+        );
+        assert!(matches!(skipped, Ok(None)));
+        let unnoticed = encode_signal::<BrokenCodec>(
+            &app_signal,
+            true,
+            SignalEncodeFailurePolicy::Notify,
+            &reporter,
+        );
+        assert!(unnoticed.is_err());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ConnectionEvent::SignalEncodeFailed(SignalKind::App),
+                ConnectionEvent::SignalEncodeFailed(SignalKind::System),
+                ConnectionEvent::SignalEncodeFailed(SignalKind::App),
+            ]
+        );
+        assert_eq!(counters.signal_encode_failed(SignalKind::App), 2);
+        assert_eq!(counters.signal_encode_failed(SignalKind::System), 1);
+        assert_eq!(counters.admin_listener_died(), 0);
+    }
+
+    #[test]
+    fn only_v2_connections_are_sent_signal_seq() {
+        let reporter = Connections::new(InterfaceKind::App, None, None).reporter("127.0.0.1:1");
+        let cell_id = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let app_signal = AppSignal::new(ExternIO::encode(()).unwrap());
+        let seq = AppSignalSeq {
+            seq: 7,
+            ..AppSignalSeq::start()
+        };
+        let signal = Signal::App(cell_id.clone(), app_signal.clone(), seq);
+        let encode = |with_seq| {
+            encode_signal::<MsgPackCodec>(
+                &signal,
+                with_seq,
+                SignalEncodeFailurePolicy::Close,
+                &reporter,
+            )
+            .unwrap()
+            .unwrap()
+        };
+
+        assert_eq!(
+            MsgPackCodec::decode::<Signal>(encode(true)).unwrap(),
+            signal
+        );
+        // older clients decode app signals as they always have
+        assert_eq!(
+            MsgPackCodec::decode::<SignalV1>(encode(false)).unwrap(),
+            SignalV1::App(cell_id, app_signal)
+        );
+        // and system signals look the same either way
+        assert_eq!(
+            MsgPackCodec::decode::<SignalV1>(MsgPackCodec::encode(&test_signal("system")).unwrap())
+                .unwrap(),
+            SignalV1::System(SystemSignal::Test("system".to_string()))
+        );
+
+        assert!(!sends_signal_seq(None));
+        assert!(!sends_signal_seq(Some(APP_SUBPROTOCOL_V1)));
+        assert!(sends_signal_seq(Some(APP_SUBPROTOCOL_V2)));
+        assert!(sends_signal_seq(Some(APP_SUBPROTOCOL_V2_JSON)));
+    }
+
+    #[test]
+    fn unencodable_signals_are_replaced_by_a_notice() {
+        use holochain_serialized_bytes::SerializedBytesError;
+
+        /// A codec which can't encode app signals
+        struct NoAppSignalsCodec;
+
+        impl WireCodec for NoAppSignalsCodec {
+            fn decode<T: serde::de::DeserializeOwned>(
+                bytes: SerializedBytes,
+            ) -> Result<T, SerializedBytesError> {
+                MsgPackCodec::decode(bytes)
+            }
+
+            fn encode<T: serde::Serialize>(
+                value: &T,
+            ) -> Result<SerializedBytes, SerializedBytesError> {
+                let bytes = MsgPackCodec::encode(value)?;
+                match MsgPackCodec::decode(bytes.clone()) {
+                    Ok(Signal::App(..)) => Err(SerializedBytesError::Serialize(
+                        "no app signals".to_string(),
+                    )),
+                    _ => Ok(bytes),
+                }
+            }
+        }
+
+        let reporter = Connections::new(InterfaceKind::App, None, None).reporter("127.0.0.1:1");
+        let cell_id = CellId::new(fake_dna_hash(1), fake_agent_pubkey_1());
+        let seq = AppSignalSeq {
+            seq: 7,
+            ..AppSignalSeq::start()
+        };
+        let app_signal = Signal::App(
+            cell_id.clone(),
+            AppSignal::new(ExternIO::encode(()).unwrap()),
+            seq,
+        );
+
+        let notice = encode_signal::<NoAppSignalsCodec>(
+            &app_signal,
+            true,
+            SignalEncodeFailurePolicy::Notify,
+            &reporter,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            MsgPackCodec::decode::<Signal>(notice).unwrap(),
+            Signal::System(SystemSignal::EncodeFailed {
+                cell_id: Some(cell_id),
+                seq: Some(seq),
+            })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dump_state() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        // Allow agents time to join
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+        // Get state
+        let expected = conductor_handle.dump_cell_state(&cell_id).await.unwrap();
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::DumpState {
+            cell_id: Box::new(cell_id),
+        };
+        let msg = msg.try_into().unwrap();
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::StateDumped(s) if s == expected);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dump_state_range() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
         dna_store
             .expect_add_dnas::<Vec<_>>()
             .times(1)
@@ -438,60 +3960,55 @@ pub mod test {
             .times(1)
             .return_const(());
 
-        let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
-        let mut request: ZomeCall =
-            crate::fixt::ZomeCallInvocationFixturator::new(crate::fixt::NamedInvocation(
-                cell_id.clone(),
-                TestWasm::Foo.into(),
-                "foo".into(),
-                ExternIO::encode(()).unwrap(),
-            ))
-            .next()
-            .unwrap()
-            .into();
-        request.cell_id = cell_id;
-        let msg = AppRequest::ZomeCallInvocation(Box::new(request));
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        // Allow agents time to join
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+        // Get state
+        let expected = conductor_handle
+            .dump_cell_state_range(&cell_id, 1, 2)
+            .await
+            .unwrap();
+        let dump: serde_json::Value = serde_json::from_str(&expected).unwrap();
+        assert_eq!(dump["elements"].as_array().unwrap().len(), 2);
+        assert_eq!(dump["elements"][0]["header"]["type"], "AgentValidationPkg");
+
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::DumpStateRange {
+            cell_id: Box::new(cell_id),
+            start: 1,
+            limit: 2,
+        };
         let msg = msg.try_into().unwrap();
-        let respond = |bytes: SerializedBytes| {
-            let response: AppResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AppResponse::ZomeCallInvocation { .. });
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AdminResponse::StateRangeDumped(s) if s == expected);
             async { Ok(()) }.boxed().into()
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, app_api).await.unwrap();
-        // the time here should be almost the same (about +0.1ms) vs. the raw real_ribosome call
-        // the overhead of a websocket request locally is small
-        let shutdown = handle.take_shutdown_handle().await.unwrap();
-        handle.shutdown().await;
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown().await;
         shutdown.await.unwrap().unwrap();
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn activate_app() {
+    async fn await_integration() {
         observability::test_run().ok();
-        let agent_key = fake_agent_pubkey_1();
-        let mut dnas = Vec::new();
-        for _i in 0..2 as u32 {
-            let zomes = vec![TestWasm::Foo.into()];
-            let def = DnaDef::unique_from_zomes(zomes.clone());
-            dnas.push(DnaFile::new(def, vec![TestWasm::Foo.into()]).await.unwrap());
-        }
-        let dna_map = dnas
-            .iter()
-            .cloned()
-            .map(|dna| (dna.dna_hash().clone(), dna))
-            .collect::<HashMap<_, _>>();
-        let dna_hashes = dna_map.keys().cloned().collect::<Vec<_>>();
-        let cell_ids_with_proofs = dna_hashes
-            .iter()
-            .cloned()
-            .map(|hash| (CellId::from((hash, agent_key.clone())), None))
-            .collect::<Vec<_>>();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
         let mut dna_store = MockDnaStore::new();
-        dna_store
-            .expect_get()
-            .returning(move |hash| dna_map.get(&hash).cloned());
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
         dna_store
             .expect_add_dnas::<Vec<_>>()
             .times(1)
@@ -500,137 +4017,169 @@ pub mod test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(1)
             .return_const(());
+
         let (_tmpdir, conductor_handle) =
-            setup_admin_fake_cells(cell_ids_with_proofs, dna_store).await;
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
 
-        // Activate the app
-        let msg = AdminRequest::ActivateApp {
-            installed_app_id: "test app".to_string(),
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::AwaitIntegration {
+            cell_id: Box::new(cell_id),
+            timeout_ms: 10_000,
         };
         let msg = msg.try_into().unwrap();
-        let respond = |bytes: SerializedBytes| {
+        let respond = move |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::AppActivated);
+            assert_matches!(
+                response,
+                AdminResponse::IntegrationAwaited(holochain_conductor_api::IntegrationStateDump {
+                    validation_limbo: 0,
+                    integration_limbo: 0,
+                    ..
+                })
+            );
             async { Ok(()) }.boxed().into()
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-
-        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
             .await
             .unwrap();
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap().unwrap();
+    }
 
-        // Get the state
-        let state: ConductorState = conductor_handle.get_state_from_handle().await.unwrap();
-
-        // Check it is not in inactive apps
-        let r = state.inactive_apps.get("test app");
-        assert_eq!(r, None);
-
-        // Check it is in active apps
-        let cell_ids: HashSet<CellId> = state
-            .active_apps
-            .get("test app")
-            .unwrap()
-            .all_cells()
-            .cloned()
-            .collect();
-
-        // Collect the expected result
-        let expected = dna_hashes
-            .into_iter()
-            .map(|hash| CellId::from((hash, agent_key.clone())))
-            .collect::<HashSet<_>>();
+    #[tokio::test(flavor = "multi_thread")]
+    async fn gossip_status() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
 
-        assert_eq!(expected, cell_ids);
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
 
-        // Check that it is returned in get_app_info as active
-        let maybe_info = state.get_app_info(&"test app".to_string());
-        if let Some(info) = maybe_info {
-            assert_eq!(info.installed_app_id, "test app");
-            assert_matches!(info.status, InstalledAppStatus::Active);
-        } else {
-            assert!(false);
-        }
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id, None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
 
-        // Now deactivate app
-        let msg = AdminRequest::DeactivateApp {
-            installed_app_id: "test app".to_string(),
-        };
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = AdminRequest::GossipStatus { dna_hash };
         let msg = msg.try_into().unwrap();
-        let respond = |bytes: SerializedBytes| {
+        let respond = move |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::AppDeactivated);
+            assert_matches!(response, AdminResponse::GossipStatusReported(_));
             async { Ok(()) }.boxed().into()
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-
-        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
             .await
             .unwrap();
-
-        // Get the state
-        let state = conductor_handle.get_state_from_handle().await.unwrap();
-
-        // Check it's removed from active
-        let r = state.active_apps.get("test app");
-        assert_eq!(r, None);
-
-        // Check it's added to inactive
-        let cell_ids: HashSet<CellId> = state
-            .inactive_apps
-            .get("test app")
-            .unwrap()
-            .all_cells()
-            .cloned()
-            .collect();
-
-        assert_eq!(expected, cell_ids);
-
-        // Check that it is returned in get_app_info as not active
-        let maybe_info = state.get_app_info(&"test app".to_string());
-        if let Some(info) = maybe_info {
-            assert_eq!(info.installed_app_id, "test app");
-            assert_matches!(info.status, InstalledAppStatus::Inactive { .. });
-        } else {
-            assert!(false);
-        }
-
         conductor_handle.shutdown().await;
         shutdown.await.unwrap().unwrap();
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn attach_app_interface() {
+    async fn pause_and_resume_gossip() {
         observability::test_run().ok();
-        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let cell_id = CellId::from((dna_hash.clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id, None)], dna_store).await;
+        let conductor_handle = activate(conductor_handle).await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
-        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
-        let msg = msg.try_into().unwrap();
-        let respond = |bytes: SerializedBytes| {
-            let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::AppInterfaceAttached { .. });
-            async { Ok(()) }.boxed().into()
+        let mut harness = AdminInterfaceHarness::spawn(
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        let status = || AdminRequest::GossipStatus {
+            dna_hash: dna_hash.clone(),
         };
-        let respond = Respond::Request(Box::new(respond));
-        let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+
+        assert_matches!(
+            harness.request(status()).await,
+            AdminResponse::GossipStatusReported(s) if !s.paused
+        );
+        assert_matches!(
+            harness
+                .request(AdminRequest::PauseGossip {
+                    dna_hash: dna_hash.clone()
+                })
+                .await,
+            AdminResponse::GossipPaused
+        );
+        assert_matches!(
+            harness.request(status()).await,
+            AdminResponse::GossipStatusReported(s) if s.paused
+        );
+        assert_matches!(
+            harness
+                .request(AdminRequest::ResumeGossip {
+                    dna_hash: dna_hash.clone()
+                })
+                .await,
+            AdminResponse::GossipResumed
+        );
+        assert_matches!(
+            harness.request(status()).await,
+            AdminResponse::GossipStatusReported(s) if !s.paused
+        );
+
+        harness.shutdown().await;
         conductor_handle.shutdown().await;
         shutdown.await.unwrap().unwrap();
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    async fn dump_state() {
+    async fn set_agent_arc() {
+        use kitsune_p2p::dht_arc::DhtArc;
+        use kitsune_p2p::dht_arc::MAX_HALF_LENGTH;
+        use kitsune_p2p::KitsuneBinType;
+
         observability::test_run().ok();
         let uuid = Uuid::new_v4();
         let dna = fake_dna_zomes(
             &uuid.to_string(),
             vec![("zomey".into(), TestWasm::Foo.into())],
         );
-        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let agent_key = fake_agent_pubkey_1();
+        let cell_id = CellId::from((dna.dna_hash().clone(), agent_key.clone()));
 
         let mut dna_store = MockDnaStore::new();
         dna_store.expect_get().returning(move |_| Some(dna.clone()));
@@ -647,25 +4196,65 @@ pub mod test {
             setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
         let conductor_handle = activate(conductor_handle).await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
-        // Allow agents time to join
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        let mut harness = AdminInterfaceHarness::spawn(
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+        let loc = holochain_p2p::agent_holo_to_kit(agent_key.clone()).get_loc();
+        let half = DhtArc::new(loc, MAX_HALF_LENGTH / 2);
+        let quarter = DhtArc::new(loc, MAX_HALF_LENGTH / 4);
+        let set = |agent_key: &AgentPubKey, arc| AdminRequest::SetAgentArc {
+            agent_key: agent_key.clone(),
+            arc,
+        };
 
-        // Get state
-        let expected = conductor_handle.dump_cell_state(&cell_id).await.unwrap();
+        // an agent whose arc was never set holds the full arc
+        assert_matches!(
+            harness.request(set(&agent_key, half)).await,
+            AdminResponse::AgentArcSet(previous)
+                if previous == vec![(cell_id.clone(), DhtArc::new(loc, MAX_HALF_LENGTH))]
+        );
+        assert_matches!(
+            harness.request(set(&agent_key, quarter)).await,
+            AdminResponse::AgentArcSet(previous) if previous == vec![(cell_id.clone(), half)]
+        );
+        // arcs must be centered on the agent
+        assert_matches!(
+            harness
+                .request(set(
+                    &agent_key,
+                    DhtArc::new(loc.wrapping_add(1), MAX_HALF_LENGTH / 4)
+                ))
+                .await,
+            AdminResponse::Error(_)
+        );
+        assert_matches!(
+            harness
+                .request(set(
+                    &agent_key,
+                    DhtArc {
+                        center_loc: loc.into(),
+                        half_length: MAX_HALF_LENGTH + 1,
+                    }
+                ))
+                .await,
+            AdminResponse::Error(ExternalApiWireError::Validation { .. })
+        );
+        assert_matches!(
+            harness.request(set(&fake_agent_pubkey_2(), half)).await,
+            AdminResponse::Error(_)
+        );
+        // a rejected arc leaves the one set before
+        assert_matches!(
+            harness.request(set(&agent_key, half)).await,
+            AdminResponse::AgentArcSet(previous) if previous == vec![(cell_id.clone(), quarter)]
+        );
 
-        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::DumpState {
-            cell_id: Box::new(cell_id),
-        };
-        let msg = msg.try_into().unwrap();
-        let respond = move |bytes: SerializedBytes| {
-            let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::StateDumped(s) if s == expected);
-            async { Ok(()) }.boxed().into()
-        };
-        let respond = Respond::Request(Box::new(respond));
-        let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        harness.shutdown().await;
         conductor_handle.shutdown().await;
         shutdown.await.unwrap().unwrap();
     }
@@ -803,7 +4392,9 @@ pub mod test {
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
 
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message::<_, MsgPackCodec>(msg, admin_api)
+            .await
+            .unwrap();
         rx
     }
 
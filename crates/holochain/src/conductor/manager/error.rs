@@ -42,6 +42,24 @@ pub enum ManagedTaskError {
 
     #[error(transparent)]
     Recv(#[from] tokio::sync::broadcast::error::RecvError),
+
+    #[error("The interface's listener has closed, so it is no longer accepting connections")]
+    ListenerClosed,
 }
 
 pub type ManagedTaskResult = Result<(), ManagedTaskError>;
+
+/// How an interface task ended without an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceTaskExit {
+    /// The conductor asked the interface to stop
+    Stopped,
+    /// The conductor asked the interface to drain,
+    /// while its other tasks carry on
+    Drained,
+    /// The interface's listener stopped producing connections
+    /// and couldn't be brought back
+    ListenerClosed,
+}
+
+pub type InterfaceTaskResult = Result<InterfaceTaskExit, ManagedTaskError>;
@@ -29,6 +29,8 @@ const CHANNEL_SIZE: usize = 1000;
 /// For a task to be "managed" simply means that it will shut itself down
 /// when it receives a message on the the "stop" channel passed in
 pub(crate) type ManagedTaskHandle = JoinHandle<ManagedTaskResult>;
+/// The handle of an interface task, which says why it ended
+pub(crate) type InterfaceTaskHandle = JoinHandle<InterfaceTaskResult>;
 pub(crate) type TaskManagerRunHandle = JoinHandle<TaskManagerResult>;
 
 /// A generic function to run when a task completes
@@ -49,18 +51,22 @@ pub enum TaskKind {
     Generic(OnDeath),
 }
 
+/// The handle of a managed task, with how to handle its completion
+enum TaskHandle {
+    Managed(ManagedTaskHandle, TaskKind),
+    Interface(InterfaceTaskHandle),
+}
+
 /// A message sent to the TaskManager, registering an ManagedTask of a given kind.
 pub struct ManagedTaskAdd {
-    handle: ManagedTaskHandle,
-    kind: TaskKind,
+    handle: TaskHandle,
     name: String,
 }
 
 impl ManagedTaskAdd {
     fn new(handle: ManagedTaskHandle, kind: TaskKind, name: &str) -> Self {
         ManagedTaskAdd {
-            handle,
-            kind,
+            handle: TaskHandle::Managed(handle, kind),
             name: name.to_string(),
         }
     }
@@ -85,20 +91,38 @@ impl ManagedTaskAdd {
     pub fn generic(handle: ManagedTaskHandle, f: OnDeath) -> Self {
         Self::new(handle, TaskKind::Generic(f), "unnamed")
     }
+
+    /// An interface task, which is expected to run until the conductor
+    /// stops it
+    pub(crate) fn interface(handle: InterfaceTaskHandle, name: &str) -> Self {
+        ManagedTaskAdd {
+            handle: TaskHandle::Interface(handle),
+            name: name.to_string(),
+        }
+    }
 }
 
 impl Future for ManagedTaskAdd {
     type Output = TaskOutcome;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let p = std::pin::Pin::new(&mut self.handle);
-        match JoinHandle::poll(p, cx) {
-            Poll::Ready(r) => Poll::Ready(handle_completed_task(
-                &self.kind,
-                r.unwrap_or_else(|e| Err(e.into())),
-                self.name.clone(),
-            )),
-            Poll::Pending => Poll::Pending,
+        let name = self.name.clone();
+        match &mut self.handle {
+            TaskHandle::Managed(handle, kind) => match JoinHandle::poll(Pin::new(handle), cx) {
+                Poll::Ready(r) => Poll::Ready(handle_completed_task(
+                    kind,
+                    r.unwrap_or_else(|e| Err(e.into())),
+                    name,
+                )),
+                Poll::Pending => Poll::Pending,
+            },
+            TaskHandle::Interface(handle) => match JoinHandle::poll(Pin::new(handle), cx) {
+                Poll::Ready(r) => Poll::Ready(handle_completed_interface_task(
+                    r.unwrap_or_else(|e| Err(e.into())),
+                    name,
+                )),
+                Poll::Pending => Poll::Pending,
+            },
         }
     }
 }
@@ -276,6 +300,17 @@ fn handle_completed_task(kind: &TaskKind, result: ManagedTaskResult, name: Strin
     }
 }
 
+/// An interface which was stopped is fine, but one which lost its listener
+/// or failed isn't serving anything any more, so is worth an error.
+fn handle_completed_interface_task(result: InterfaceTaskResult, name: String) -> TaskOutcome {
+    use TaskOutcome::*;
+    match result {
+        Ok(InterfaceTaskExit::Stopped) | Ok(InterfaceTaskExit::Drained) => LogInfo(name),
+        Ok(InterfaceTaskExit::ListenerClosed) => MinorError(ManagedTaskError::ListenerClosed, name),
+        Err(err) => MinorError(err, name),
+    }
+}
+
 /// Handle the result of shutting down the main thread.
 pub fn handle_shutdown(result: Result<TaskManagerResult, tokio::task::JoinError>) {
     let result = result.map_err(|e| {
@@ -307,6 +342,10 @@ pub struct TaskManagerClient {
     /// telling them to shut down
     task_stop_broadcaster: StopBroadcaster,
 
+    /// Sending a message on this channel will broadcast to all interface
+    /// tasks, telling them to drain, without stopping other managed tasks
+    interface_drain_broadcaster: StopBroadcaster,
+
     /// The main task join handle to await on.
     /// The conductor is intended to live as long as this task does.
     /// It can be moved out, hence the Option. If this is None, then the
@@ -319,11 +358,13 @@ impl TaskManagerClient {
     pub fn new(
         task_add_sender: mpsc::Sender<ManagedTaskAdd>,
         task_stop_broadcaster: StopBroadcaster,
+        interface_drain_broadcaster: StopBroadcaster,
         run_handle: TaskManagerRunHandle,
     ) -> Self {
         Self {
             task_add_sender,
             task_stop_broadcaster,
+            interface_drain_broadcaster,
             run_handle: Some(run_handle),
         }
     }
@@ -338,6 +379,11 @@ impl TaskManagerClient {
         &self.task_stop_broadcaster
     }
 
+    /// Accessor
+    pub fn interface_drain_broadcaster(&self) -> &StopBroadcaster {
+        &self.interface_drain_broadcaster
+    }
+
     /// Return the handle to be joined.
     /// This will return None if the handle was already taken.
     pub fn take_handle(&mut self) -> Option<TaskManagerRunHandle> {
@@ -443,4 +489,27 @@ mod test {
 
         handle_shutdown(main_task.await);
     }
+
+    #[tokio::test]
+    async fn interface_exits_are_told_apart() {
+        let outcome = |result: InterfaceTaskResult| async move {
+            ManagedTaskAdd::interface(tokio::spawn(async move { result }), "interface").await
+        };
+        assert!(matches!(
+            outcome(Ok(InterfaceTaskExit::Stopped)).await,
+            TaskOutcome::LogInfo(_)
+        ));
+        assert!(matches!(
+            outcome(Ok(InterfaceTaskExit::Drained)).await,
+            TaskOutcome::LogInfo(_)
+        ));
+        assert!(matches!(
+            outcome(Ok(InterfaceTaskExit::ListenerClosed)).await,
+            TaskOutcome::MinorError(ManagedTaskError::ListenerClosed, _)
+        ));
+        assert!(matches!(
+            outcome(Err(ConductorError::Todo("failed".to_string()).into())).await,
+            TaskOutcome::MinorError(ManagedTaskError::Conductor(_), _)
+        ));
+    }
 }
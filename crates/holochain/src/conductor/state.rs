@@ -2,7 +2,10 @@
 //! startups and shutdowns
 
 use holochain_conductor_api::signal_subscription::SignalSubscription;
-use holochain_conductor_api::{config::InterfaceDriver, InstalledAppInfo};
+use holochain_conductor_api::{
+    config::{InterfaceDriver, RateLimitConfig, SignalEncodeFailurePolicy, SignalOverflowPolicy},
+    InstalledAppInfo,
+};
 use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
@@ -96,6 +99,44 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// If set, signals are buffered on each connection for this many
+    /// milliseconds and only the most recent signal from each source is sent.
+    /// Clients see the sequence numbers of the replaced app signals skipped,
+    /// the same gap as for signals they missed
+    #[serde(default)]
+    pub signal_coalesce_window_ms: Option<u64>,
+
+    /// If set, signals are queued on each connection without waiting unless
+    /// its outgoing buffer is full, rather than awaiting every send
+    #[serde(default)]
+    pub eager_signals: bool,
+
+    /// If set, limits how fast each connection may send requests
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+
+    /// If set, connections which send no messages for this many
+    /// milliseconds are closed
+    #[serde(default)]
+    pub idle_timeout_ms: Option<u64>,
+
+    /// If set, how many milliseconds clients closed by shutdown are
+    /// asked to wait before reconnecting
+    #[serde(default)]
+    pub reconnect_backoff_ms: Option<u64>,
+
+    /// What to do with signals when a connection falls behind
+    #[serde(default)]
+    pub signal_overflow: SignalOverflowPolicy,
+
+    /// What to do with a signal which can't be serialized for a connection
+    #[serde(default)]
+    pub signal_encode_failure: SignalEncodeFailurePolicy,
+
+    /// If set, only the cells of this app send signals to this interface
+    #[serde(default)]
+    pub installed_app_id: Option<InstalledAppId>,
 }
 
 impl AppInterfaceConfig {
@@ -103,7 +144,18 @@ impl AppInterfaceConfig {
     pub fn websocket(port: u16) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::Websocket {
+                port,
+                bind_address: None,
+            },
+            signal_coalesce_window_ms: None,
+            eager_signals: false,
+            rate_limit: None,
+            idle_timeout_ms: None,
+            reconnect_backoff_ms: None,
+            signal_overflow: SignalOverflowPolicy::default(),
+            signal_encode_failure: SignalEncodeFailurePolicy::default(),
+            installed_app_id: None,
         }
     }
 }
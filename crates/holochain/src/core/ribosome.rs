@@ -332,6 +332,7 @@ impl ZomeCallInvocation {
             cap,
             payload,
             provenance,
+            ..
         } = call;
         let zome = conductor_api
             .get_zome(cell_id.dna_hash(), &zome_name)
@@ -365,6 +366,7 @@ impl From<ZomeCallInvocation> for ZomeCall {
             cap,
             payload,
             provenance,
+            call_id: None,
         }
     }
 }
@@ -30,6 +30,7 @@ pub fn call(
         fn_name: call.fn_name,
         payload: call.payload,
         provenance: call.provenance,
+        call_id: None,
     };
 
     // Make the call using this workspace
@@ -87,6 +88,7 @@ pub mod wasm_test {
                 fn_name: "set_access".into(),
                 payload: ExternIO::encode(()).unwrap(),
                 provenance: bob_agent_id.clone(),
+                call_id: None,
             })
             .await
             .unwrap();
@@ -101,6 +103,7 @@ pub mod wasm_test {
                 fn_name: "who_are_they_local".into(),
                 payload: ExternIO::encode(&bob_cell_id).unwrap(),
                 provenance: alice_agent_id.clone(),
+                call_id: None,
             })
             .await
             .unwrap()
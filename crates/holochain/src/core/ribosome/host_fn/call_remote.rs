@@ -127,6 +127,7 @@ pub mod wasm_test {
                 fn_name: "set_access".into(),
                 payload: ExternIO::encode(()).unwrap(),
                 provenance: bob_agent_id.clone(),
+                call_id: None,
             })
             .await
             .unwrap();
@@ -141,6 +142,7 @@ pub mod wasm_test {
                 fn_name: "whoarethey".into(),
                 payload: ExternIO::encode(&bob_agent_id).unwrap(),
                 provenance: alice_agent_id,
+                call_id: None,
             })
             .await
             .unwrap()
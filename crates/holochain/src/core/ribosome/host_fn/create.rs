@@ -348,6 +348,7 @@ pub mod wasm_test {
                 fn_name: "create_entry_multiple".into(),
                 payload: ExternIO::encode(n).unwrap(),
                 provenance: alice_agent_id.clone(),
+                call_id: None,
             })
             .await
             .unwrap()
@@ -363,6 +364,7 @@ pub mod wasm_test {
                 fn_name: "get_entry_multiple".into(),
                 payload: ExternIO::encode(n).unwrap(),
                 provenance: alice_agent_id,
+                call_id: None,
             })
             .await
             .unwrap()
@@ -1,6 +1,5 @@
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::CallContext;
-use holochain_types::signal::Signal;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 use holochain_wasmer_host::prelude::WasmError;
@@ -11,7 +10,13 @@ pub fn emit_signal(
     input: AppSignal,
 ) -> Result<(), WasmError> {
     let cell_id = call_context.host_access().cell_id().clone();
-    let signal = Signal::App(cell_id, input);
-    call_context.host_access().signal_tx().send(signal).map_err(|interface_error| WasmError::Host(interface_error.to_string()))?;
+    let mut signal_tx = call_context.host_access().signal_tx().clone();
+    // nothing is listening, e.g. no app interface is attached
+    if !signal_tx.has_subscribers() {
+        return Ok(());
+    }
+    // sending may wait on interfaces which block when full
+    tokio_helper::block_forever_on(async move { signal_tx.send_app_signal(cell_id, input).await })
+        .map_err(|interface_error| WasmError::Host(interface_error.to_string()))?;
     Ok(())
 }
@@ -43,7 +43,16 @@ pub fn standard_config() -> ConductorConfig {
         override_port: None,
     }];
     let admin_interface = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port: 0 },
+        driver: InterfaceDriver::Websocket {
+            port: 0,
+            bind_address: None,
+        },
+        allowed_requests: None,
+        rate_limit: None,
+        idle_timeout_ms: None,
+        reconnect_backoff_ms: None,
+        coalesce_requests: false,
+        max_connection_lifetime_ms: None,
     };
     ConductorConfig {
         network: Some(network),
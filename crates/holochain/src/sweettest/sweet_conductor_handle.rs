@@ -78,6 +78,7 @@ impl SweetConductorHandle {
             cap,
             provenance: provenance.clone(),
             payload,
+            call_id: None,
         };
         self.0.call_zome(call).await.map(|r| {
             unwrap_to!(r.unwrap() => ZomeCallResponse::Ok)
@@ -292,7 +292,16 @@ pub async fn setup_app_inner(
     let conductor_handle = ConductorBuilder::new()
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::Websocket {
+                    port: 0,
+                    bind_address: None,
+                },
+                allowed_requests: None,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                coalesce_requests: false,
+                max_connection_lifetime_ms: None,
             }]),
             network,
             ..Default::default()
@@ -744,6 +753,7 @@ where
         fn_name: func.into(),
         payload: ExternIO::encode(payload)?,
         provenance: cell_id.agent_pubkey().clone(),
+        call_id: None,
     })
 }
 
@@ -161,6 +161,7 @@ impl TestConductorHandle {
             cap,
             provenance,
             payload,
+            call_id: None,
         };
         let response = self.0.call_zome(call).await.unwrap().unwrap();
         unwrap_to!(response => ZomeCallResponse::Ok)
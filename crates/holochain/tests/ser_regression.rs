@@ -126,6 +126,7 @@ async fn ser_regression_test() {
         fn_name: "create_channel".into(),
         payload: ExternIO::encode(channel).unwrap(),
         provenance: alice_agent_id.clone(),
+        call_id: None,
     };
 
     let request = Box::new(invocation.clone());
@@ -155,6 +156,7 @@ async fn ser_regression_test() {
         fn_name: "create_message".into(),
         payload: ExternIO::encode(message).unwrap(),
         provenance: alice_agent_id.clone(),
+        call_id: None,
     };
 
     let request = Box::new(invocation.clone());
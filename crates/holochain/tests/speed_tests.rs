@@ -194,11 +194,22 @@ async fn speed_test(n: Option<usize>) -> TestEnvironments {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        bind_address: None,
+        signal_coalesce_window_ms: None,
+        eager_signals: false,
+        rate_limit: None,
+        idle_timeout_ms: None,
+        reconnect_backoff_ms: None,
+        signal_overflow: Default::default(),
+        signal_encode_failure: Default::default(),
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
         _ => panic!("Attach app interface failed: {:?}", response),
     };
     let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
@@ -224,6 +235,7 @@ async fn speed_test(n: Option<usize>) -> TestEnvironments {
             fn_name: func.into(),
             payload: ExternIO::encode(payload)?,
             provenance: cell_id.agent_pubkey().clone(),
+            call_id: None,
         })
     }
 
@@ -317,7 +329,16 @@ pub async fn setup_app(
     let conductor_handle = ConductorBuilder::with_mock_dna_store(dna_store)
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::Websocket {
+                    port: 0,
+                    bind_address: None,
+                },
+                allowed_requests: None,
+                rate_limit: None,
+                idle_timeout_ms: None,
+                reconnect_backoff_ms: None,
+                coalesce_requests: false,
+                max_connection_lifetime_ms: None,
             }]),
             ..Default::default()
         })
@@ -73,7 +73,16 @@ pub async fn check_started(holochain: &mut Child) {
 fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::Websocket {
+                port,
+                bind_address: None,
+            },
+            allowed_requests: None,
+            rate_limit: None,
+            idle_timeout_ms: None,
+            reconnect_backoff_ms: None,
+            coalesce_requests: false,
+            max_connection_lifetime_ms: None,
         }]),
         environment_path: environment_path.into(),
         network: None,
@@ -83,6 +92,9 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         }),
         keystore_path: None,
         use_dangerous_test_keystore: true,
+        recent_interface_errors: None,
+        max_pending_interface_requests: None,
+        interface_forwarded_for_header: None,
     }
 }
 
@@ -256,11 +268,22 @@ pub async fn attach_app_interface(
     holochain: &mut Child,
     port: Option<u16>,
 ) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        bind_address: None,
+        signal_coalesce_window_ms: None,
+        eager_signals: false,
+        rate_limit: None,
+        idle_timeout_ms: None,
+        reconnect_backoff_ms: None,
+        signal_overflow: Default::default(),
+        signal_encode_failure: Default::default(),
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = check_timeout(holochain, response, 1000).await;
     match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
         _ => panic!("Attach app interface failed: {:?}", response),
     }
 }
@@ -382,6 +405,7 @@ async fn call_zome() {
     // Activate cells
     let request = AdminRequest::ActivateApp {
         installed_app_id: "test".to_string(),
+        dry_run: false,
     };
     let response = client.request(request);
     let response = check_timeout(&mut holochain, response, 1000).await;
@@ -471,7 +495,7 @@ async fn remote_signals() -> anyhow::Result<()> {
     for mut rx in rxs {
         let r = rx.try_recv();
         // Each handle should recv a signal
-        assert_matches!(r, Ok(Signal::App(_, a)) if a == signal);
+        assert_matches!(r, Ok(Signal::App(_, a, _)) if a == signal);
     }
 
     Ok(())
@@ -522,6 +546,7 @@ async fn emit_signals() {
     // Activate cells
     let request = AdminRequest::ActivateApp {
         installed_app_id: "test".to_string(),
+        dry_run: false,
     };
     let response = admin_tx.request(request);
     let response = check_timeout(&mut holochain, response, 1000).await;
@@ -534,7 +559,16 @@ async fn emit_signals() {
     // Emit signals (the real test!)
 
     let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
-    let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    // this client is also sent the sequence numbers of app signals
+    let (_, app_rx_2) = holochain_websocket::connect(
+        url2!("ws://127.0.0.1:{}", app_port),
+        Arc::new(
+            WebsocketConfig::default()
+                .subprotocols(vec![holochain::conductor::api::APP_SUBPROTOCOL_V2]),
+        ),
+    )
+    .await
+    .unwrap();
 
     call_zome_fn(
         &mut holochain,
@@ -560,11 +594,15 @@ async fn emit_signals() {
         .unwrap();
     assert!(!msg2.is_request());
 
+    let app_signal = AppSignal::new(ExternIO::encode(()).unwrap());
     assert_eq!(
-        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
-        Signal::try_from(sig1.clone()).unwrap(),
+        SignalV1::App(cell_id.clone(), app_signal.clone()),
+        SignalV1::try_from(sig1).unwrap(),
+    );
+    assert_matches!(
+        Signal::try_from(sig2).unwrap(),
+        Signal::App(c, a, AppSignalSeq { seq: 0, .. }) if c == cell_id && a == app_signal
     );
-    assert_eq!(sig1, sig2);
 
     ///////////////////////////////////////////////////////
 
@@ -667,6 +705,48 @@ async fn conductor_admin_interface_ends_with_shutdown_inner() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn conductor_app_interface_ends_with_shutdown() {
+    observability::test_run().ok();
+
+    let tmp_dir = TempDir::new("conductor_cfg").unwrap();
+    let environment_path = tmp_dir.path().to_path_buf();
+    let config = create_config(0, environment_path);
+    let conductor_handle = Conductor::builder().config(config).build().await.unwrap();
+    let (mut admin_client, _) = websocket_client(&conductor_handle).await.unwrap();
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        bind_address: None,
+        signal_coalesce_window_ms: None,
+        eager_signals: false,
+        rate_limit: None,
+        idle_timeout_ms: None,
+        reconnect_backoff_ms: None,
+        signal_overflow: Default::default(),
+        signal_encode_failure: Default::default(),
+        installed_app_id: None,
+    };
+    let port = match admin_client.request(request).await.unwrap() {
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
+        response => panic!("Attach app interface failed: {:?}", response),
+    };
+    let (_app_client, mut app_rx): (WebsocketSender, WebsocketReceiver) =
+        holochain_websocket::connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+
+    conductor_handle.shutdown().await;
+
+    // the app connection is closed by the conductor rather than left open
+    let closed = tokio::time::timeout(Duration::from_secs(5), app_rx.next())
+        .await
+        .expect("app client was not disconnected on shutdown");
+    assert!(closed.is_none());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn too_many_open() {
     observability::test_run().ok();
@@ -2,8 +2,28 @@ use holo_hash::*;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::dht_arc::DhtArc;
 
+use crate::ActiveCellOpCount;
+use crate::ConductorStateDump;
 use crate::InstalledAppInfo;
+use crate::ZomeCallId;
+
+/// The websocket subprotocol identifying the current wire format of
+/// [`AdminRequest`] and [`AdminResponse`].
+/// A client may request it during the websocket handshake to make sure it is
+/// speaking the same version of the admin API as the conductor.
+///
+/// [`AdminRequest`]: enum.AdminRequest.html
+/// [`AdminResponse`]: enum.AdminResponse.html
+pub const ADMIN_SUBPROTOCOL_V1: &str = "holochain-admin-v1";
+
+/// The same wire format as [`ADMIN_SUBPROTOCOL_V1`], but with the contents of
+/// each message encoded as JSON instead of MessagePack, for clients such as
+/// browsers that have no convenient MessagePack support.
+///
+/// [`ADMIN_SUBPROTOCOL_V1`]: constant.ADMIN_SUBPROTOCOL_V1.html
+pub const ADMIN_SUBPROTOCOL_V1_JSON: &str = "holochain-admin-v1+json";
 
 /// Represents the available conductor functions to call over an Admin interface
 /// and will result in a corresponding [`AdminResponse`] message being sent back over the
@@ -19,6 +39,29 @@ use crate::InstalledAppInfo;
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum AdminRequest {
+    /// Ask the conductor which version it is running and which requests it
+    /// supports. Tooling can send this first to decide which features to
+    /// enable, rather than sending requests the conductor doesn't know.
+    /// Takes no arguments.
+    ///
+    /// Will be responded to with an [`AdminResponse::Info`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Info`]: enum.AdminResponse.html#variant.Info
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    Info,
+    /// Check that the interface is alive and responsive, e.g. as a
+    /// liveness probe.
+    /// This is answered without touching any database or the keystore,
+    /// and without waiting on other requests to the conductor, so it is
+    /// cheap to send often. An admin interface whose `allowed_requests`
+    /// only contains `ping` can be exposed to a health checker.
+    /// Takes no arguments.
+    ///
+    /// Will be responded to with an [`AdminResponse::Pong`]
+    ///
+    /// [`AdminResponse::Pong`]: enum.AdminResponse.html#variant.Pong
+    Ping,
     /// Set up and register one or more new Admin interfaces
     /// as specified by a list of configurations. See [`AdminInterfaceConfig`]
     /// for details on the configuration.
@@ -40,6 +83,25 @@ pub enum AdminRequest {
     /// [`AdminResponse::DnaRegistered`]: enum.AdminResponse.html#variant.DnaRegistered
     RegisterDna(Box<RegisterDnaPayload>),
 
+    /// Register a DNA which is uploaded directly over the interface,
+    /// rather than read from the conductor's filesystem.
+    /// The `dna` bytes are the contents of a `DnaBundle`, as created by `hc dna pack`.
+    /// The DNA is parsed and stored exactly as it would be for [`AdminRequest::RegisterDna`]
+    /// with a `Path` source.
+    ///
+    /// Will be responded to with an [`AdminResponse::DnaRegistered`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::RegisterDna`]: enum.AdminRequest.html#variant.RegisterDna
+    /// [`AdminResponse::DnaRegistered`]: enum.AdminResponse.html#variant.DnaRegistered
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    InstallDnaFromBytes {
+        /// The serialized `DnaBundle`
+        dna: SerializedBytes,
+        /// Properties to override when installing this Dna
+        properties: Option<YamlProperties>,
+    },
+
     /// "Clone" a DNA (in the biological sense), thus creating a new Cell.
     ///
     /// Using the provided, already-registered DNA, create a new DNA with a unique
@@ -102,8 +164,23 @@ pub enum AdminRequest {
     /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
     ListDnas,
 
+    /// List all installed `Dna`s with the names of their zomes,
+    /// so tooling can confirm an install and discover callable zomes.
+    /// Takes no arguments.
+    ///
+    /// Will be responded to with an [`AdminResponse::InstalledDnasListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::InstalledDnasListed`]: enum.AdminResponse.html#variant.InstalledDnasListed
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    ListInstalledDnas,
+
     /// Generate a new AgentPubKey.
     /// Takes no arguments.
+    /// The keypair is created in the conductor's keystore and only the public
+    /// key is returned, the private key never leaves the keystore.
+    /// Use it as the `agent_key` of an [`AdminRequest::InstallApp`] to install
+    /// and activate an app entirely over the admin interface.
     ///
     /// Will be responded to with an [`AdminResponse::AgentPubKeyGenerated`]
     /// or an [`AdminResponse::Error`]
@@ -136,14 +213,26 @@ pub enum AdminRequest {
     /// installed `App` is not activated automatically.
     ///
     /// Will be responded to with an [`AdminResponse::AppActivated`]
+    /// or an [`AdminResponse::Error`]. If any of the app's cells can't be
+    /// created, none of them are, and the error is an
+    /// [`ExternalApiWireError::ActivateAppFailed`] listing each failing DNA.
+    ///
+    /// If `dry_run` is set, the app is only checked to be installed and
+    /// inactive with every DNA it needs registered, and nothing is changed.
+    /// This is responded to with an [`AdminResponse::AppsValidated`]
     /// or an [`AdminResponse::Error`]
     ///
     /// [`AdminRequest::InstallApp`]: enum.AdminRequest.html#variant.InstallApp
     /// [`AdminResponse::AppActivated`]: enum.AdminResponse.html#variant.AppActivated
+    /// [`AdminResponse::AppsValidated`]: enum.AdminResponse.html#variant.AppsValidated
     /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`ExternalApiWireError::ActivateAppFailed`]: enum.ExternalApiWireError.html#variant.ActivateAppFailed
     ActivateApp {
         /// The InstalledAppId to activate
         installed_app_id: InstalledAppId,
+        /// Validate that the app could be activated without activating it
+        #[serde(default)]
+        dry_run: bool,
     },
     /// Changes the `App` specified by argument `installed_app_id` from an active state to an inactive state in the conductor,
     /// meaning that Zome calls can no longer be made, and the `App` will not be loaded on a
@@ -164,16 +253,73 @@ pub enum AdminRequest {
     /// Any active `App` will be callable via this interface.
     /// The successful [`AdminResponse::AppInterfaceAttached`] message will contain
     /// the port chosen by the conductor if `None` was passed.
+    /// The interface listens on `bind_address` if set, or on the IPv4
+    /// loopback address otherwise.
+    /// If `signal_coalesce_window_ms` is set, signals are buffered for that long
+    /// on each connection and only the most recent signal from each cell is sent.
+    /// The signals it replaces are never sent, so clients see gaps in the
+    /// [`AppSignalSeq`] of a cell's signals just as if they had been lost.
+    /// If `eager_signals` is set, signals are queued without waiting unless the
+    /// connection's outgoing buffer is full, trading back pressure for latency.
+    /// `signal_overflow` decides whether signals are dropped or emitting cells
+    /// wait when a connection falls behind, see [`SignalOverflowPolicy`].
+    /// `signal_encode_failure` decides whether a signal which can't be
+    /// serialized closes the connection, is skipped, or has a notice sent
+    /// in its place, see [`SignalEncodeFailurePolicy`].
+    /// If `installed_app_id` is set, only the cells of that app send their
+    /// signals to this interface, rather than every cell in the conductor.
     ///
     /// Will be responded to with an [`AdminResponse::AppInterfaceAttached`]
     /// or an [`AdminResponse::Error`]
     ///
     /// [`AdminResponse::AppInterfaceAttached`]: enum.AdminResponse.html#variant.AppInterfaceAttached
     /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`SignalOverflowPolicy`]: crate::config::SignalOverflowPolicy
+    /// [`SignalEncodeFailurePolicy`]: crate::config::SignalEncodeFailurePolicy
+    /// [`AppSignalSeq`]: holochain_types::signal::AppSignalSeq
     AttachAppInterface {
         /// Optional port, use None to let the
         /// OS choose a free port
         port: Option<u16>,
+        /// Optional address to listen on, IPv4 or IPv6,
+        /// use None to listen on the IPv4 loopback address
+        #[serde(default)]
+        bind_address: Option<std::net::IpAddr>,
+        /// Optional window in milliseconds over which to coalesce signals,
+        /// deliberately skipping the sequence numbers of those replaced,
+        /// use None to send every signal as it arrives
+        #[serde(default)]
+        signal_coalesce_window_ms: Option<u64>,
+        /// Queue signals without waiting unless the outgoing buffer is full
+        #[serde(default)]
+        eager_signals: bool,
+        /// Optional limit on how fast each connection may send requests,
+        /// use None to allow any rate
+        #[serde(default)]
+        rate_limit: Option<crate::config::RateLimitConfig>,
+        /// Optional time in milliseconds after which a connection that has
+        /// sent no messages is closed, use None to leave idle connections open
+        #[serde(default)]
+        idle_timeout_ms: Option<u64>,
+        /// How many milliseconds clients closed because the conductor is
+        /// shutting down are asked to wait before reconnecting,
+        /// see [`AdminInterfaceConfig::reconnect_backoff_ms`].
+        /// `None` suggests 1 second.
+        ///
+        /// [`AdminInterfaceConfig::reconnect_backoff_ms`]: crate::config::AdminInterfaceConfig::reconnect_backoff_ms
+        #[serde(default)]
+        reconnect_backoff_ms: Option<u64>,
+        /// What to do with signals when a connection falls behind,
+        /// defaults to dropping its oldest signals
+        #[serde(default)]
+        signal_overflow: crate::config::SignalOverflowPolicy,
+        /// What to do with a signal which can't be serialized,
+        /// defaults to closing the connection
+        #[serde(default)]
+        signal_encode_failure: crate::config::SignalEncodeFailurePolicy,
+        /// Optionally only carry signals from the cells of this app
+        #[serde(default)]
+        installed_app_id: Option<InstalledAppId>,
     },
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
     ListAppInterfaces,
@@ -189,6 +335,114 @@ pub enum AdminRequest {
         /// The `CellId` for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Dump a slice of the source chain of the `Cell` specified by argument
+    /// `cell_id`, as a string containing JSON. At most `limit` elements are
+    /// dumped, starting at sequence number `start` and moving towards the
+    /// chain head, so that large chains can be paged through.
+    ///
+    /// Will be responded to with an [`AdminResponse::StateRangeDumped`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::StateRangeDumped`]: enum.AdminResponse.html#variant.StateRangeDumped
+    DumpStateRange {
+        /// The `CellId` for which to dump state
+        cell_id: Box<CellId>,
+        /// The sequence number of the first element to dump
+        start: u32,
+        /// The maximum number of elements to dump
+        limit: u32,
+    },
+    /// Wait until the `Cell` specified by argument `cell_id` has no ops
+    /// left awaiting validation or integration, or until `timeout_ms`
+    /// milliseconds have passed, whichever comes first.
+    /// Useful in tests which need the ops committed by a zome call to be
+    /// integrated before they carry on, instead of sleeping.
+    ///
+    /// Will be responded to with an [`AdminResponse::IntegrationAwaited`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::IntegrationAwaited`]: enum.AdminResponse.html#variant.IntegrationAwaited
+    AwaitIntegration {
+        /// The `CellId` whose ops to wait for
+        cell_id: Box<CellId>,
+        /// The longest to wait in milliseconds
+        timeout_ms: u64,
+    },
+    /// Report whether gossip for the dna specified by argument `dna_hash`
+    /// has converged across the local agents running it, as of the last
+    /// local sync of its space.
+    /// Useful as a health check.
+    ///
+    /// Will be responded to with an [`AdminResponse::GossipStatusReported`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::GossipStatusReported`]: enum.AdminResponse.html#variant.GossipStatusReported
+    GossipStatus {
+        /// The `DnaHash` whose space to report on
+        dna_hash: DnaHash,
+    },
+    /// Stop gossip for the dna specified by argument `dna_hash` until it is
+    /// resumed with [`AdminRequest::ResumeGossip`], e.g. during maintenance.
+    /// While paused, neither the local agents running the dna nor any peers
+    /// are gossiped with, and [`AdminRequest::GossipStatus`] reports the
+    /// space as paused. The conductor keeps running as usual otherwise.
+    ///
+    /// Will be responded to with an [`AdminResponse::GossipPaused`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::ResumeGossip`]: enum.AdminRequest.html#variant.ResumeGossip
+    /// [`AdminRequest::GossipStatus`]: enum.AdminRequest.html#variant.GossipStatus
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::GossipPaused`]: enum.AdminResponse.html#variant.GossipPaused
+    PauseGossip {
+        /// The `DnaHash` whose space to pause gossip in
+        dna_hash: DnaHash,
+    },
+    /// Start gossip for the dna specified by argument `dna_hash` again after
+    /// an [`AdminRequest::PauseGossip`]. Gossip carries on from where it was
+    /// paused. Resuming gossip which isn't paused does nothing.
+    ///
+    /// Will be responded to with an [`AdminResponse::GossipResumed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::PauseGossip`]: enum.AdminRequest.html#variant.PauseGossip
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::GossipResumed`]: enum.AdminResponse.html#variant.GossipResumed
+    ResumeGossip {
+        /// The `DnaHash` whose space to resume gossip in
+        dna_hash: DnaHash,
+    },
+    /// Set the arc of the dht the agent specified by argument `agent_key`
+    /// stores, in every cell of that agent. The arc must be centered on the
+    /// agent's location. From the next local sync on, gossip only collects
+    /// the agent's ops in the arc, and the agent's info is published again
+    /// declaring it. Agents whose arc was never set store the full arc.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentArcSet`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::AgentArcSet`]: enum.AdminResponse.html#variant.AgentArcSet
+    SetAgentArc {
+        /// The agent whose arc to set
+        agent_key: AgentPubKey,
+        /// The arc the agent stores from now on
+        arc: DhtArc,
+    },
+    /// Dump a summary of the conductor's own state: the cells of all
+    /// active apps with their `DnaHash` and `AgentPubKey`, the inactive apps
+    /// and the attached app interfaces.
+    /// Takes no arguments.
+    ///
+    /// Will be responded to with an [`AdminResponse::ConductorStateDumped`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::ConductorStateDumped`]: enum.AdminResponse.html#variant.ConductorStateDumped
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    DumpConductorState,
     /// Add a list [AgentInfoSigned] to this conductor's peer store.
     /// This is another way of finding peers on a dht.
     ///
@@ -214,6 +468,294 @@ pub enum AdminRequest {
         /// Optionally choose a specific agent info
         cell_id: Option<CellId>,
     },
+    /// List the most recent errors on this conductor's admin and app
+    /// interfaces, oldest first, so you can see what has been failing
+    /// without enabling verbose tracing or going through the logs.
+    /// How many are kept is set by `recent_interface_errors` in the
+    /// conductor config.
+    /// Takes no arguments.
+    ///
+    /// Will be responded to with an [`AdminResponse::RecentErrorsListed`]
+    ///
+    /// [`AdminResponse::RecentErrorsListed`]: enum.AdminResponse.html#variant.RecentErrorsListed
+    RecentErrors,
+    /// Fetch the data behind one op, the same way it is fetched when
+    /// gossiping it, for diagnosing why a space isn't in sync.
+    ///
+    /// Will be responded to with an [`AdminResponse::OpDataFetched`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::OpDataFetched`]: enum.AdminResponse.html#variant.OpDataFetched
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    GetOpData {
+        /// The `DnaHash` of the space the op is in
+        dna_hash: DnaHash,
+        /// The op to fetch
+        op_hash: DhtOpHash,
+    },
+    /// Run `request` at most once for `key`. If the conductor has already
+    /// answered a request with the same key in the last ten minutes, that
+    /// response is returned again instead of running `request`.
+    ///
+    /// Use a fresh key for each mutating request, e.g. an
+    /// [`AdminRequest::ActivateApp`], so it can be retried safely after the
+    /// connection drops before the response arrives. Failed requests aren't
+    /// remembered, so retrying one runs it again.
+    ///
+    /// Will be responded to with whatever `request` is responded to with.
+    /// An interface's `allowed_requests` are checked against `request`.
+    ///
+    /// [`AdminRequest::ActivateApp`]: enum.AdminRequest.html#variant.ActivateApp
+    Idempotent {
+        /// Identifies this request across retries
+        key: String,
+        /// The request to run, which can't itself be `Idempotent`
+        request: Box<AdminRequest>,
+    },
+}
+
+impl AdminRequest {
+    /// The serialized `type` tags of every variant of [`AdminRequest`].
+    /// Keep in sync with the enum when adding requests.
+    /// `"idempotent"` isn't listed, as it is never the
+    /// [`AdminRequest::request_type`] of a request.
+    ///
+    /// [`AdminRequest`]: enum.AdminRequest.html
+    pub const SUPPORTED_REQUESTS: &'static [&'static str] = &[
+        "info",
+        "ping",
+        "add_admin_interfaces",
+        "register_dna",
+        "install_dna_from_bytes",
+        "create_clone_cell",
+        "install_app",
+        "install_app_bundle",
+        "list_dnas",
+        "list_installed_dnas",
+        "generate_agent_pub_key",
+        "list_cell_ids",
+        "list_active_apps",
+        "activate_app",
+        "deactivate_app",
+        "attach_app_interface",
+        "list_app_interfaces",
+        "dump_state",
+        "dump_state_range",
+        "await_integration",
+        "gossip_status",
+        "pause_gossip",
+        "resume_gossip",
+        "set_agent_arc",
+        "dump_conductor_state",
+        "add_agent_info",
+        "request_agent_info",
+        "recent_errors",
+        "get_op_data",
+    ];
+
+    /// The serialized `type` tag of this request,
+    /// one of [`AdminRequest::SUPPORTED_REQUESTS`].
+    /// For an [`AdminRequest::Idempotent`] this is the type of the request it
+    /// wraps, which is what gets run.
+    ///
+    /// [`AdminRequest::Idempotent`]: enum.AdminRequest.html#variant.Idempotent
+    /// [`AdminRequest::SUPPORTED_REQUESTS`]: enum.AdminRequest.html#associatedconstant.SUPPORTED_REQUESTS
+    pub fn request_type(&self) -> &'static str {
+        use AdminRequest::*;
+        match self {
+            Info => "info",
+            Ping => "ping",
+            AddAdminInterfaces(_) => "add_admin_interfaces",
+            RegisterDna(_) => "register_dna",
+            InstallDnaFromBytes { .. } => "install_dna_from_bytes",
+            CreateCloneCell(_) => "create_clone_cell",
+            InstallApp(_) => "install_app",
+            InstallAppBundle(_) => "install_app_bundle",
+            ListDnas => "list_dnas",
+            ListInstalledDnas => "list_installed_dnas",
+            GenerateAgentPubKey => "generate_agent_pub_key",
+            ListCellIds => "list_cell_ids",
+            ListActiveApps => "list_active_apps",
+            ActivateApp { .. } => "activate_app",
+            DeactivateApp { .. } => "deactivate_app",
+            AttachAppInterface { .. } => "attach_app_interface",
+            ListAppInterfaces => "list_app_interfaces",
+            DumpState { .. } => "dump_state",
+            DumpStateRange { .. } => "dump_state_range",
+            AwaitIntegration { .. } => "await_integration",
+            GossipStatus { .. } => "gossip_status",
+            PauseGossip { .. } => "pause_gossip",
+            ResumeGossip { .. } => "resume_gossip",
+            SetAgentArc { .. } => "set_agent_arc",
+            DumpConductorState => "dump_conductor_state",
+            AddAgentInfo { .. } => "add_agent_info",
+            RequestAgentInfo { .. } => "request_agent_info",
+            RecentErrors => "recent_errors",
+            GetOpData { .. } => "get_op_data",
+            Idempotent { request, .. } => request.request_type(),
+        }
+    }
+
+    /// Whether handling this request leaves the conductor as it was,
+    /// so two identical ones handled at once can share a response.
+    pub fn is_read_only(&self) -> bool {
+        use AdminRequest::*;
+        match self {
+            Info
+            | Ping
+            | ListDnas
+            | ListInstalledDnas
+            | ListCellIds
+            | ListActiveApps
+            | ListAppInterfaces
+            | DumpState { .. }
+            | DumpStateRange { .. }
+            | AwaitIntegration { .. }
+            | GossipStatus { .. }
+            | DumpConductorState
+            | RequestAgentInfo { .. }
+            | RecentErrors
+            | GetOpData { .. } => true,
+            AddAdminInterfaces(_)
+            | RegisterDna(_)
+            | InstallDnaFromBytes { .. }
+            | CreateCloneCell(_)
+            | InstallApp(_)
+            | InstallAppBundle(_)
+            | GenerateAgentPubKey
+            | ActivateApp { .. }
+            | DeactivateApp { .. }
+            | AttachAppInterface { .. }
+            | PauseGossip { .. }
+            | ResumeGossip { .. }
+            | SetAgentArc { .. }
+            | AddAgentInfo { .. } => false,
+            Idempotent { request, .. } => request.is_read_only(),
+        }
+    }
+
+    /// Check the fields of a request that decoded successfully but
+    /// whose values could never be handled, such as an empty app id,
+    /// so the client gets an [`ExternalApiWireError::Validation`]
+    /// naming the field instead of a failure from deep in the conductor.
+    ///
+    /// [`ExternalApiWireError::Validation`]: enum.ExternalApiWireError.html#variant.Validation
+    pub fn validate(&self) -> Result<(), ExternalApiWireError> {
+        use AdminRequest::*;
+        match self {
+            AddAdminInterfaces(configs) => {
+                if configs.is_empty() {
+                    return Err(ExternalApiWireError::validation(
+                        "interfaces",
+                        "must not be empty",
+                    ));
+                }
+                for config in configs {
+                    for request_type in config.allowed_requests.iter().flatten() {
+                        if !Self::SUPPORTED_REQUESTS.contains(&request_type.as_str()) {
+                            return Err(ExternalApiWireError::validation(
+                                "allowed_requests",
+                                format!("unknown request type {}", request_type),
+                            ));
+                        }
+                    }
+                    validate_idle_timeout(config.idle_timeout_ms)?;
+                }
+            }
+            InstallDnaFromBytes { dna, .. } => {
+                if dna.bytes().is_empty() {
+                    return Err(ExternalApiWireError::validation("dna", "must not be empty"));
+                }
+            }
+            CreateCloneCell(payload) => {
+                validate_app_id(&payload.installed_app_id)?;
+                if payload.slot_id.is_empty() {
+                    return Err(ExternalApiWireError::validation(
+                        "slot_id",
+                        "must not be empty",
+                    ));
+                }
+            }
+            InstallApp(payload) => {
+                validate_app_id(&payload.installed_app_id)?;
+                if payload.dnas.is_empty() {
+                    return Err(ExternalApiWireError::validation(
+                        "dnas",
+                        "must not be empty",
+                    ));
+                }
+            }
+            InstallAppBundle(payload) => {
+                if let Some(installed_app_id) = &payload.installed_app_id {
+                    validate_app_id(installed_app_id)?;
+                }
+            }
+            ActivateApp {
+                installed_app_id, ..
+            }
+            | DeactivateApp { installed_app_id } => validate_app_id(installed_app_id)?,
+            AttachAppInterface {
+                idle_timeout_ms,
+                installed_app_id,
+                ..
+            } => {
+                validate_idle_timeout(*idle_timeout_ms)?;
+                if let Some(installed_app_id) = installed_app_id {
+                    validate_app_id(installed_app_id)?;
+                }
+            }
+            DumpStateRange { limit, .. } => {
+                if *limit == 0 {
+                    return Err(ExternalApiWireError::validation(
+                        "limit",
+                        "must be greater than 0",
+                    ));
+                }
+            }
+            SetAgentArc { arc, .. } => {
+                if arc.half_length > kitsune_p2p::dht_arc::MAX_HALF_LENGTH {
+                    return Err(ExternalApiWireError::validation(
+                        "arc",
+                        "must not be longer than the whole dht",
+                    ));
+                }
+            }
+            Idempotent { key, request } => {
+                if key.is_empty() {
+                    return Err(ExternalApiWireError::validation("key", "must not be empty"));
+                }
+                if let Idempotent { .. } = **request {
+                    return Err(ExternalApiWireError::validation(
+                        "request",
+                        "must not be idempotent itself",
+                    ));
+                }
+                request.validate()?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+fn validate_app_id(installed_app_id: &str) -> Result<(), ExternalApiWireError> {
+    if installed_app_id.trim().is_empty() {
+        return Err(ExternalApiWireError::validation(
+            "installed_app_id",
+            "must not be empty",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_idle_timeout(idle_timeout_ms: Option<u64>) -> Result<(), ExternalApiWireError> {
+    if idle_timeout_ms == Some(0) {
+        return Err(ExternalApiWireError::validation(
+            "idle_timeout_ms",
+            "must be greater than 0, use None to leave idle connections open",
+        ));
+    }
+    Ok(())
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -225,10 +767,48 @@ pub enum AdminRequest {
 /// `{ type: 'app_interface_attached', data: { port: 4000 } }`
 ///
 /// [`AdminRequest`]: enum.AdminRequest.html
-#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum AdminResponse {
+    /// The succesful response to an [`AdminRequest::Info`].
+    ///
+    /// [`AdminRequest::Info`]: enum.AdminRequest.html#variant.Info
+    Info {
+        /// The version of the holochain conductor
+        conductor_version: String,
+        /// The wire format spoken on this interface,
+        /// matching the websocket subprotocol [`ADMIN_SUBPROTOCOL_V1`]
+        ///
+        /// [`ADMIN_SUBPROTOCOL_V1`]: constant.ADMIN_SUBPROTOCOL_V1.html
+        wire_version: String,
+        /// The `type` tags of every [`AdminRequest`] this conductor handles
+        ///
+        /// [`AdminRequest`]: enum.AdminRequest.html
+        supported_requests: Vec<String>,
+    },
+
+    /// The response to an [`AdminRequest::Ping`].
+    ///
+    /// [`AdminRequest::Ping`]: enum.AdminRequest.html#variant.Ping
+    Pong {
+        /// How long the conductor has been running, in milliseconds.
+        /// A probe seeing this go down knows the conductor has restarted.
+        uptime_ms: u64,
+        /// How many of the conductor's admin interfaces have lost their
+        /// listener for good, so can no longer be connected to.
+        /// Anything but 0 is worth alerting on.
+        #[serde(default)]
+        admin_listeners_died: u64,
+        /// How many app signals couldn't be serialized for a connection
+        /// on any of the conductor's interfaces, since it started.
+        /// Anything but 0 usually means a cell emits a bad signal.
+        #[serde(default)]
+        app_signal_encode_failures: u64,
+        /// Likewise for system signals
+        #[serde(default)]
+        system_signal_encode_failures: u64,
+    },
+
     /// Can occur in response to any [`AdminRequest`].
     ///
     /// There has been an error during the handling of the request.
@@ -239,8 +819,10 @@ pub enum AdminResponse {
     Error(ExternalApiWireError),
 
     /// The successful response to an [`AdminRequest::RegisterDna`]
+    /// or an [`AdminRequest::InstallDnaFromBytes`]
     ///
     /// [`AdminRequest::RegisterDna`]: enum.AdminRequest.html#variant.RegisterDna
+    /// [`AdminRequest::InstallDnaFromBytes`]: enum.AdminRequest.html#variant.InstallDnaFromBytes
     DnaRegistered(DnaHash),
 
     /// The successful response to an [`AdminRequest::InstallApp`].
@@ -296,6 +878,13 @@ pub enum AdminResponse {
     /// [`AdminRequest::ListDnas`]: enum.AdminRequest.html#variant.ListDnas
     DnasListed(Vec<DnaHash>),
 
+    /// The successful response to an [`AdminRequest::ListInstalledDnas`].
+    ///
+    /// Contains the hash and zome names of every installed `Dna`
+    ///
+    /// [`AdminRequest::ListInstalledDnas`]: enum.AdminRequest.html#variant.ListInstalledDnas
+    InstalledDnasListed(Vec<InstalledDnaInfo>),
+
     /// The succesful response to an [`AdminRequest::ListCellIds`].
     ///
     /// Contains a list of all the `Cell` ids in the conductor
@@ -314,12 +903,17 @@ pub enum AdminResponse {
     ///
     /// `AppInterfaceApi` successfully attached.
     /// Contains the port number that was selected (if not specified) by Holochain
-    /// for running this App interface
+    /// for running this App interface, along with the cells which were active
+    /// at the time it was attached
     ///
     /// [`AdminRequest::AttachAppInterface`]: enum.AdminRequest.html#variant.AttachAppInterface
     AppInterfaceAttached {
         /// Networking port of the new `AppInterfaceApi`
         port: u16,
+        /// Every cell belonging to an active app, with how many ops it had
+        /// integrated when the interface was attached.
+        #[serde(default)]
+        active_cells: Vec<ActiveCellOpCount>,
     },
 
     /// The list of attached app interfaces.
@@ -332,6 +926,14 @@ pub enum AdminResponse {
     /// [`AdminRequest::ActivateApp`]: enum.AdminRequest.html#variant.ActivateApp
     AppActivated,
 
+    /// The succesful response to an [`AdminRequest::ActivateApp`]
+    /// with `dry_run` set.
+    ///
+    /// It means the `App` could be activated, but nothing was changed.
+    ///
+    /// [`AdminRequest::ActivateApp`]: enum.AdminRequest.html#variant.ActivateApp
+    AppsValidated,
+
     /// The succesful response to an [`AdminRequest::DeactivateApp`].
     ///
     /// It means the `App` was deactivated successfully.
@@ -347,6 +949,57 @@ pub enum AdminResponse {
     /// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
     StateDumped(String),
 
+    /// The succesful response to an [`AdminRequest::DumpStateRange`].
+    ///
+    /// The result contains a string of serialized JSON data with the requested
+    /// source chain elements in sequence order. Fewer than `limit` elements
+    /// means the end of the chain was reached.
+    ///
+    /// [`AdminRequest::DumpStateRange`]: enum.AdminRequest.html#variant.DumpStateRange
+    StateRangeDumped(String),
+
+    /// The succesful response to an [`AdminRequest::AwaitIntegration`].
+    ///
+    /// Contains how many of the cell's ops are integrated, and how many are
+    /// still in validation or integration limbo. The limbo counts are only
+    /// non-zero if the wait timed out.
+    ///
+    /// [`AdminRequest::AwaitIntegration`]: enum.AdminRequest.html#variant.AwaitIntegration
+    IntegrationAwaited(crate::IntegrationStateDump),
+
+    /// The succesful response to an [`AdminRequest::GossipStatus`].
+    ///
+    /// The space is converged if [`GossipStatus::is_converged`] holds,
+    /// otherwise `outstanding_ops` estimates how far off it is.
+    ///
+    /// [`AdminRequest::GossipStatus`]: enum.AdminRequest.html#variant.GossipStatus
+    /// [`GossipStatus::is_converged`]: kitsune_p2p::actor::GossipStatus::is_converged
+    GossipStatusReported(kitsune_p2p::actor::GossipStatus),
+
+    /// The succesful response to an [`AdminRequest::PauseGossip`].
+    ///
+    /// [`AdminRequest::PauseGossip`]: enum.AdminRequest.html#variant.PauseGossip
+    GossipPaused,
+
+    /// The succesful response to an [`AdminRequest::ResumeGossip`].
+    ///
+    /// [`AdminRequest::ResumeGossip`]: enum.AdminRequest.html#variant.ResumeGossip
+    GossipResumed,
+
+    /// The succesful response to an [`AdminRequest::SetAgentArc`].
+    ///
+    /// Each of the agent's cells, with the arc the agent stored in it before.
+    ///
+    /// [`AdminRequest::SetAgentArc`]: enum.AdminRequest.html#variant.SetAgentArc
+    AgentArcSet(Vec<(CellId, DhtArc)>),
+
+    /// The succesful response to an [`AdminRequest::DumpConductorState`].
+    ///
+    /// Contains metadata about the conductor's cells and interfaces.
+    ///
+    /// [`AdminRequest::DumpConductorState`]: enum.AdminRequest.html#variant.DumpConductorState
+    ConductorStateDumped(ConductorStateDump),
+
     /// The succesful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -360,6 +1013,48 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::RequestAgentInfo`]: enum.AdminRequest.html#variant.RequestAgentInfo
     AgentInfoRequested(Vec<AgentInfoSigned>),
+
+    /// The response to an [`AdminRequest::RecentErrors`].
+    ///
+    /// The most recent interface errors, oldest first.
+    ///
+    /// [`AdminRequest::RecentErrors`]: enum.AdminRequest.html#variant.RecentErrors
+    RecentErrorsListed(Vec<InterfaceErrorInfo>),
+
+    /// The successful response to an [`AdminRequest::GetOpData`].
+    ///
+    /// The op, or `None` if no cell of the dna on this conductor holds it.
+    ///
+    /// [`AdminRequest::GetOpData`]: enum.AdminRequest.html#variant.GetOpData
+    OpDataFetched(Option<DhtOp>),
+}
+
+/// Info about an installed `Dna`, returned as part of
+/// [`AdminResponse::InstalledDnasListed`]
+///
+/// [`AdminResponse::InstalledDnasListed`]: enum.AdminResponse.html#variant.InstalledDnasListed
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct InstalledDnaInfo {
+    /// The hash of the `Dna`
+    pub dna_hash: DnaHash,
+    /// The names of the `Dna`'s zomes, in the order they are defined
+    pub zome_names: Vec<ZomeName>,
+}
+
+/// An error on one of the conductor's interfaces, returned as part of
+/// [`AdminResponse::RecentErrorsListed`]
+///
+/// [`AdminResponse::RecentErrorsListed`]: enum.AdminResponse.html#variant.RecentErrorsListed
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct InterfaceErrorInfo {
+    /// When the error happened, in milliseconds since the unix epoch
+    pub timestamp_ms: u64,
+    /// The kind of interface it happened on, `admin` or `app`
+    pub interface: String,
+    /// The address of the client it happened with
+    pub remote_addr: String,
+    /// What went wrong
+    pub error: String,
 }
 
 /// Error type that goes over the websocket wire.
@@ -377,12 +1072,70 @@ pub enum ExternalApiWireError {
     Deserialization(String),
     /// The dna path provided was invalid
     DnaReadError(String),
+    /// There is no file at the dna path provided
+    DnaFileNotFound {
+        /// The path that was given
+        path: std::path::PathBuf,
+    },
+    /// The file at the dna path provided could be read,
+    /// but isn't a valid `DnaBundle`
+    DnaParseError {
+        /// The path that was given
+        path: std::path::PathBuf,
+        /// Why it couldn't be parsed
+        reason: String,
+    },
     /// There was an error in the ribosome
     RibosomeError(String),
     /// Error activating app
     ActivateApp(String),
+    /// Some of an app's cells could not be created, so the app was not activated.
+    /// The cells that were created have been cleaned up again.
+    ActivateAppFailed {
+        /// The app that failed to activate
+        installed_app_id: InstalledAppId,
+        /// The DNA hash of each cell that failed, with why it failed
+        failed_dnas: Vec<(DnaHash, String)>,
+    },
     /// The zome call is unauthorized
     ZomeCallUnauthorized(String),
+    /// The request is not permitted on this interface
+    Forbidden(String),
+    /// An interface couldn't be attached because its port is already in use
+    PortInUse {
+        /// The port that was asked for
+        port: u16,
+    },
+    /// The requested cell is not part of any active app,
+    /// so nothing was called
+    CellNotActive {
+        /// The cell that was requested
+        cell_id: CellId,
+    },
+    /// The connection has sent too many requests and this one was not handled,
+    /// it may be retried after `retry_after_ms` milliseconds
+    RateLimited {
+        /// How long to wait before the next request will be accepted
+        retry_after_ms: u64,
+    },
+    /// The request decoded but one of its fields has a value that can't be
+    /// handled, so nothing was done.
+    /// Unlike [`ExternalApiWireError::Deserialization`] the request was
+    /// well-formed, so retrying with a corrected value may succeed.
+    ///
+    /// [`ExternalApiWireError::Deserialization`]: enum.ExternalApiWireError.html#variant.Deserialization
+    Validation {
+        /// The name of the offending field
+        field: String,
+        /// What is wrong with its value
+        reason: String,
+    },
+    /// No zome call with this id is in flight on the connection,
+    /// so there was nothing to cancel
+    ZomeCallNotFound {
+        /// The id that was asked for
+        call_id: ZomeCallId,
+    },
 }
 
 impl ExternalApiWireError {
@@ -392,4 +1145,14 @@ impl ExternalApiWireError {
         // this version intended for users.
         ExternalApiWireError::InternalError(e.to_string())
     }
+
+    /// A [`ExternalApiWireError::Validation`] error for `field`.
+    ///
+    /// [`ExternalApiWireError::Validation`]: enum.ExternalApiWireError.html#variant.Validation
+    pub fn validation(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        ExternalApiWireError::Validation {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
 }
@@ -2,6 +2,39 @@ use crate::{signal_subscription::SignalSubscription, ExternalApiWireError};
 use holo_hash::AgentPubKey;
 use holochain_types::prelude::*;
 
+/// The websocket subprotocol identifying the first wire format of
+/// [`AppRequest`] and [`AppResponse`].
+/// A client may request it during the websocket handshake to make sure it is
+/// speaking the same version of the app API as the conductor.
+/// Clients which request no subprotocol get this format too.
+/// App signals are sent as a [`SignalV1`], without their sequence number.
+///
+/// [`AppRequest`]: enum.AppRequest.html
+/// [`AppResponse`]: enum.AppResponse.html
+pub const APP_SUBPROTOCOL_V1: &str = "holochain-app-v1";
+
+/// The same wire format as [`APP_SUBPROTOCOL_V1`], but with the contents of
+/// each message encoded as JSON instead of MessagePack.
+///
+/// [`APP_SUBPROTOCOL_V1`]: constant.APP_SUBPROTOCOL_V1.html
+pub const APP_SUBPROTOCOL_V1_JSON: &str = "holochain-app-v1+json";
+
+/// The websocket subprotocol identifying the current wire format of
+/// [`AppRequest`] and [`AppResponse`].
+/// It only differs from [`APP_SUBPROTOCOL_V1`] in sending each app signal
+/// as a [`Signal`] with its [`AppSignalSeq`].
+///
+/// [`AppRequest`]: enum.AppRequest.html
+/// [`AppResponse`]: enum.AppResponse.html
+/// [`APP_SUBPROTOCOL_V1`]: constant.APP_SUBPROTOCOL_V1.html
+pub const APP_SUBPROTOCOL_V2: &str = "holochain-app-v2";
+
+/// The same wire format as [`APP_SUBPROTOCOL_V2`], but with the contents of
+/// each message encoded as JSON instead of MessagePack.
+///
+/// [`APP_SUBPROTOCOL_V2`]: constant.APP_SUBPROTOCOL_V2.html
+pub const APP_SUBPROTOCOL_V2_JSON: &str = "holochain-app-v2+json";
+
 /// Represents the available Conductor functions to call over an App interface
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
@@ -39,11 +72,69 @@ pub enum AppRequest {
     /// DEPRECATED. Use `ZomeCall`.
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Update signal subscriptions.
+    /// Get the head of the source chain of the `Cell` specified by argument `cell_id`,
+    /// without dumping the whole chain.
+    /// Useful for cheaply polling whether a chain has changed.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// Will be responded to with an [`AppResponse::ChainHead`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::ChainHead`]: enum.AppResponse.html#variant.ChainHead
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    ChainHead {
+        /// The `CellId` whose chain head to get
+        cell_id: CellId,
+    },
+
+    /// List the `CellId`s of the active apps this interface serves.
+    /// If it was attached for one app, only that app's cells are listed.
+    /// Lets a client find the cells to call into right after connecting.
+    ///
+    /// Will be responded to with an [`AppResponse::CellsListed`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`AppResponse::CellsListed`]: enum.AppResponse.html#variant.CellsListed
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    ListCells,
+
+    /// Update the signal subscription of the connection this is sent on.
+    /// From then on only the signals the [`SignalSubscription`] allows,
+    /// by the cell they came from and by their kind, are sent to it.
+    ///
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`SignalSubscription`]: ../signal_subscription/struct.SignalSubscription.html
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
+
+    /// Cancel a zome call still in flight on the connection this is sent on,
+    /// made with an [`AppRequest::ZomeCall`] whose [`ZomeCall::call_id`]
+    /// is `call_id`.
+    /// The cancelled call is answered with an
+    /// [`AppResponse::ZomeCallCancelled`] instead of its result, and won't
+    /// commit anything to the source chain unless it already has.
+    /// Cancelling doesn't stop the zome function itself, which runs to the
+    /// end: only what it writes to the source chain and its result are
+    /// thrown away, anything else it does, such as a `call_remote`,
+    /// still happens.
+    /// Other requests on the connection carry on.
+    ///
+    /// Will be responded to with an [`AppResponse::ZomeCallCancelled`]
+    /// or, if no call with that id is in flight on this connection, e.g.
+    /// because it has already completed, an [`AppResponse::Error`] with
+    /// [`ExternalApiWireError::ZomeCallNotFound`]
+    ///
+    /// [`AppRequest::ZomeCall`]: enum.AppRequest.html#variant.ZomeCall
+    /// [`ZomeCall::call_id`]: struct.ZomeCall.html#structfield.call_id
+    /// [`AppResponse::ZomeCallCancelled`]: enum.AppResponse.html#variant.ZomeCallCancelled
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`ExternalApiWireError::ZomeCallNotFound`]: error/enum.ExternalApiWireError.html#variant.ZomeCallNotFound
+    CancelZomeCall {
+        /// The id the call was made with
+        call_id: ZomeCallId,
+    },
 }
 
 /// Responses to requests received on an App interface
@@ -83,6 +174,50 @@ pub enum AppResponse {
 
     /// DEPRECATED. See `ZomeCall`.
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::ChainHead`].
+    ///
+    /// Option will be `None` if nothing has been committed to the chain yet.
+    ///
+    /// [`AppRequest::ChainHead`]: enum.AppRequest.html#variant.ChainHead
+    ChainHead(Option<ChainHeadInfo>),
+
+    /// The successful response to an [`AppRequest::ListCells`].
+    ///
+    /// [`AppRequest::ListCells`]: enum.AppRequest.html#variant.ListCells
+    CellsListed(Vec<CellId>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
+
+    /// The response to an [`AppRequest::ZomeCall`] which was cancelled,
+    /// and the successful response to the [`AppRequest::CancelZomeCall`]
+    /// which cancelled it.
+    ///
+    /// [`AppRequest::ZomeCall`]: enum.AppRequest.html#variant.ZomeCall
+    /// [`AppRequest::CancelZomeCall`]: enum.AppRequest.html#variant.CancelZomeCall
+    ZomeCallCancelled,
+}
+
+/// Identifies a zome call made over an app interface so it can be cancelled
+/// with [`AppRequest::CancelZomeCall`].
+/// Chosen by the client, and only needs to be unique among the calls
+/// in flight on one connection.
+///
+/// [`AppRequest::CancelZomeCall`]: enum.AppRequest.html#variant.CancelZomeCall
+pub type ZomeCallId = u64;
+
+/// The head of a cell's source chain, returned as part of [`AppResponse::ChainHead`]
+///
+/// [`AppResponse::ChainHead`]: enum.AppResponse.html#variant.ChainHead
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ChainHeadInfo {
+    /// The hash of the most recent header on the chain
+    pub header_hash: HeaderHash,
+    /// The sequence number of that header
+    pub header_seq: u32,
 }
 
 /// The data provided across an App interface in order to make a zome call
@@ -107,6 +242,14 @@ pub struct ZomeCall {
     /// is making this ZomeCall over this interface. Until we do, the caller simply
     /// provides this data and Holochain trusts them.
     pub provenance: AgentPubKey,
+    /// Set to be able to cancel the call with [`AppRequest::CancelZomeCall`]
+    /// while it is in flight.
+    /// A call with an id is handled alongside the other requests on its
+    /// connection, rather than in the order it was sent.
+    ///
+    /// [`AppRequest::CancelZomeCall`]: enum.AppRequest.html#variant.CancelZomeCall
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<ZomeCallId>,
 }
 
 #[allow(missing_docs)]
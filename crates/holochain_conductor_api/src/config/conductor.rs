@@ -57,6 +57,27 @@ pub struct ConductorConfig {
 
     /// Config options for the network module. Optional.
     pub network: Option<holochain_p2p::kitsune_p2p::KitsuneP2pConfig>,
+
+    /// How many of the most recent interface errors to keep for
+    /// `AdminRequest::RecentErrors`. Defaults to 100.
+    #[serde(default)]
+    pub recent_interface_errors: Option<usize>,
+
+    /// While the conductor's interfaces are handling this many requests
+    /// between them, they stop accepting new connections, which wait in
+    /// the OS backlog until some of the requests have been answered.
+    /// Connections are always accepted if this isn't set.
+    #[serde(default)]
+    pub max_pending_interface_requests: Option<usize>,
+
+    /// A header, such as `X-Forwarded-For`, which a reverse proxy in front
+    /// of the conductor's interfaces sets to the real client's address.
+    /// When set, that address is logged and reported for each connection
+    /// instead of the proxy's. Only set this if clients can't reach the
+    /// interfaces except through the proxy, since they can put anything
+    /// in the header.
+    #[serde(default)]
+    pub interface_forwarded_for_header: Option<String>,
     //
     //
     // /// Which signals to emit
@@ -129,6 +150,9 @@ pub mod tests {
                 keystore_path: None,
                 admin_interfaces: None,
                 use_dangerous_test_keystore: false,
+                recent_interface_errors: None,
+                max_pending_interface_requests: None,
+                interface_forwarded_for_header: None,
             }
         );
     }
@@ -211,6 +235,9 @@ pub mod tests {
             ConductorConfig {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 use_dangerous_test_keystore: true,
+                recent_interface_errors: None,
+                max_pending_interface_requests: None,
+                interface_forwarded_for_header: None,
                 dpki: Some(DpkiConfig {
                     instance_id: "some_id".into(),
                     init_params: "some_params".into()
@@ -218,7 +245,16 @@ pub mod tests {
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 keystore_path: None,
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::Websocket {
+                        port: 1234,
+                        bind_address: None,
+                    },
+                    allowed_requests: None,
+                    rate_limit: None,
+                    idle_timeout_ms: None,
+                    reconnect_backoff_ms: None,
+                    coalesce_requests: false,
+                    max_connection_lifetime_ms: None,
                 }]),
                 network: Some(network_config),
             }
@@ -249,6 +285,9 @@ pub mod tests {
                 keystore_path: Some(PathBuf::from("/path/to/keystore").into()),
                 admin_interfaces: None,
                 use_dangerous_test_keystore: true,
+                recent_interface_errors: None,
+                max_pending_interface_requests: None,
+                interface_forwarded_for_header: None,
             }
         );
     }
@@ -1,5 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
 
 /// Information neeeded to spawn an Admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
@@ -7,11 +10,123 @@ pub struct AdminInterfaceConfig {
     /// By what means will the interface be exposed?
     /// Current only option is a local websocket running on a configurable port.
     pub driver: InterfaceDriver,
+    /// If set, only requests whose `type` is in this list are handled on this
+    /// interface, anything else is rejected with
+    /// [`ExternalApiWireError::Forbidden`].
+    /// Useful for exposing a read-only interface to monitoring tools.
+    /// `None` allows every request.
+    ///
+    /// [`ExternalApiWireError::Forbidden`]: crate::ExternalApiWireError::Forbidden
+    #[serde(default)]
+    pub allowed_requests: Option<Vec<String>>,
+    /// If set, limits how fast each connection to this interface may send
+    /// requests. See [`RateLimitConfig`].
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// If set, a connection to this interface which sends no messages
+    /// for this many milliseconds is closed with an idle timeout reason.
+    /// `None` leaves idle connections open.
+    #[serde(default)]
+    pub idle_timeout_ms: Option<u64>,
+    /// How many milliseconds clients are asked to wait before reconnecting
+    /// when their connection is closed because the conductor is shutting
+    /// down or the interface has too many connections.
+    /// `None` suggests 1 second.
+    ///
+    /// The suggestion is part of the close frame's reason, which is always
+    /// `<category>: <description>` where only the category is meant to be
+    /// parsed. For the `shutting_down` and `overloaded` categories it is
+    /// `<category>; retry_after_ms=<ms>: <description>` instead. Clients
+    /// should add their own random jitter to the suggested backoff.
+    #[serde(default)]
+    pub reconnect_backoff_ms: Option<u64>,
+    /// If set, a read-only request which arrives, on any connection to this
+    /// interface, while an identical one is still being handled is not
+    /// handled again but answered with the same response.
+    /// Useful when many clients poll for the same expensive state dump.
+    /// See [`AdminRequest::is_read_only`].
+    ///
+    /// [`AdminRequest::is_read_only`]: crate::AdminRequest::is_read_only
+    #[serde(default)]
+    pub coalesce_requests: bool,
+    /// If set, a connection to this interface which has been open for this
+    /// many milliseconds is closed with a `max_lifetime` reason, once any
+    /// request it is waiting on has been answered. The client should then
+    /// reconnect, which bounds how long a leaked connection stays useful.
+    /// `None` lets connections stay open for as long as they like.
+    #[serde(default)]
+    pub max_connection_lifetime_ms: Option<u64>,
     // /// How long will this interface be accessible between authentications?
     // /// TODO: implement once we have authentication
     // _session_duration_seconds: Option<u32>,
 }
 
+/// Limits the rate at which a single connection to an interface may send
+/// requests, so one client can't starve the others.
+///
+/// Each connection gets a bucket holding up to `burst` requests which refills
+/// at `requests_per_second`. A request arriving when the bucket is empty is
+/// not handled and is responded to with [`ExternalApiWireError::RateLimited`].
+///
+/// [`ExternalApiWireError::RateLimited`]: crate::ExternalApiWireError::RateLimited
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// How many requests per second the bucket refills by.
+    /// A value of 0 is treated as 1.
+    pub requests_per_second: u32,
+    /// The most requests that can be sent at once after a quiet period.
+    /// A value of 0 is treated as 1.
+    pub burst: u32,
+}
+
+/// What an app interface does when a connection's signal buffer is full
+/// because its client isn't reading signals as fast as cells emit them.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalOverflowPolicy {
+    /// The connection loses its oldest buffered signals to make room.
+    /// Emitting a signal never waits.
+    DropOldest,
+    /// Emitting a signal waits until every connection has room for it,
+    /// so no signal is lost.
+    ///
+    /// **Beware:** a client which stops reading signals stalls every cell
+    /// emitting to its interface, and a client which waits on a zome call
+    /// before reading signals can deadlock with a cell emitting during
+    /// that call. Only use this with clients that always read signals.
+    /// The interface itself still accepts new connections, and can still
+    /// be stopped or drained.
+    Block,
+}
+
+impl Default for SignalOverflowPolicy {
+    fn default() -> Self {
+        SignalOverflowPolicy::DropOldest
+    }
+}
+
+/// What an app interface does with a signal it fails to serialize for a
+/// connection, which usually means the emitting cell has a bug.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalEncodeFailurePolicy {
+    /// Close the connection with an error.
+    Close,
+    /// Drop the signal and carry on, so one malformed signal
+    /// doesn't cut the client off from every other signal.
+    Skip,
+    /// Send a `SystemSignal::EncodeFailed` in place of the signal, so the
+    /// client learns it missed one without losing its connection.
+    /// The connection is only closed if even that can't be serialized.
+    Notify,
+}
+
+impl Default for SignalEncodeFailurePolicy {
+    fn default() -> Self {
+        SignalEncodeFailurePolicy::Notify
+    }
+}
+
 /// Configuration for interfaces, specifying the means by which an interface
 /// should be opened.
 ///
@@ -24,6 +139,10 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// The address to bind the WebsocketListener to, IPv4 or IPv6.
+        /// Defaults to the IPv4 loopback address `127.0.0.1`.
+        #[serde(default)]
+        bind_address: Option<IpAddr>,
     },
 }
 
@@ -31,7 +150,21 @@ impl InterfaceDriver {
     /// Get the port for this driver.
     pub fn port(&self) -> u16 {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port, .. } => *port,
+        }
+    }
+
+    /// Get the address this driver binds to.
+    pub fn bind_address(&self) -> IpAddr {
+        match self {
+            InterfaceDriver::Websocket { bind_address, .. } => {
+                bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            }
         }
     }
+
+    /// Get the full socket address this driver binds to.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address(), self.port())
+    }
 }
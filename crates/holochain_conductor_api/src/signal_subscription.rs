@@ -1,7 +1,9 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
+use holochain_types::signal::Signal;
 use holochain_zome_types::cell::CellId;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
@@ -11,6 +13,62 @@ pub struct SignalSubscription {
     installed_app_id: InstalledAppId,
     /// Fine-grained per-cell filters
     filters: SignalFilterSet,
+    /// If set, only signals of these kinds are sent,
+    /// on top of the per-cell filters
+    #[serde(default)]
+    kinds: Option<HashSet<SignalKind>>,
+}
+
+impl SignalSubscription {
+    /// Subscribe to the signals of an App allowed by `filters`, of any kind
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+            kinds: None,
+        }
+    }
+
+    /// Only subscribe to signals of these kinds
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = SignalKind>) -> Self {
+        self.kinds = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// The app for which to manage subscription
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Whether this subscription lets `signal` through,
+    /// both by its kind and by the cell it came from
+    pub fn allows(&self, signal: &Signal) -> bool {
+        let kind_allowed = match &self.kinds {
+            Some(kinds) => kinds.contains(&SignalKind::of(signal)),
+            None => true,
+        };
+        kind_allowed && self.filters.allows(signal)
+    }
+}
+
+/// The kinds of [`Signal`], for subscribing to only some of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    /// Signals emitted by a Cell with `emit_signal`
+    App,
+    /// Signals from the Holochain system itself
+    System,
+}
+
+impl SignalKind {
+    /// The kind of `signal`
+    pub fn of(signal: &Signal) -> Self {
+        match signal {
+            Signal::App(..) => SignalKind::App,
+            Signal::System(_) => SignalKind::System,
+        }
+    }
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -46,6 +104,22 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether `signal` is let through.
+    /// System signals come from no Cell, so they are only let through
+    /// by an Exclude filter.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match (self, signal) {
+            (SignalFilterSet::Include(cells), Signal::App(cell_id, ..)) => {
+                cells.contains_key(cell_id)
+            }
+            (SignalFilterSet::Exclude(cells), Signal::App(cell_id, ..)) => {
+                !cells.contains_key(cell_id)
+            }
+            (SignalFilterSet::Include(_), Signal::System(_)) => false,
+            (SignalFilterSet::Exclude(_), Signal::System(_)) => true,
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
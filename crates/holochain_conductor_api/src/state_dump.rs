@@ -1,6 +1,7 @@
 use holo_hash::AgentPubKey;
 use holo_hash::DnaHash;
 use holochain_state::source_chain::SourceChainJsonDump;
+use holochain_types::prelude::InstalledAppId;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -32,6 +33,40 @@ pub struct IntegrationStateDump {
     pub integrated: usize,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A metadata-only summary of the whole conductor's state.
+/// Unlike [`JsonDump`] this does not look inside any cell.
+pub struct ConductorStateDump {
+    /// Every cell belonging to an active app.
+    pub active_cells: Vec<ActiveCellDump>,
+    /// The ids of apps which are installed but not active.
+    pub inactive_apps: Vec<InstalledAppId>,
+    /// The ports of all attached app interfaces.
+    pub app_interfaces: Vec<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A cell which is part of an active app.
+pub struct ActiveCellDump {
+    /// The app this cell belongs to.
+    pub installed_app_id: InstalledAppId,
+    /// The dna of this cell.
+    pub dna_hash: DnaHash,
+    /// The agent of this cell.
+    pub agent_pub_key: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A rough measure of how much data an active cell holds.
+pub struct ActiveCellOpCount {
+    /// The dna of this cell.
+    pub dna_hash: DnaHash,
+    /// The agent of this cell.
+    pub agent_pub_key: AgentPubKey,
+    /// Ops this cell has integrated, including rejected ones.
+    pub op_count: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// State dump of all the peer info
 pub struct P2pStateDump {
@@ -399,29 +399,44 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         from_agent: Arc<kitsune_p2p::KitsuneAgent>,
         op_hash: Arc<kitsune_p2p::KitsuneOpHash>,
         op_data: Vec<u8>,
-    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<bool> {
         let space = DnaHash::from_kitsune(&space);
         let to_agent = AgentPubKey::from_kitsune(&to_agent);
         let _from_agent = AgentPubKey::from_kitsune(&from_agent);
         let op_hash = DhtOpHash::from_kitsune(&op_hash);
         let op_data =
             crate::wire::WireDhtOpData::decode(op_data).map_err(HolochainP2pError::from)?;
-        self.handle_incoming_publish(
+        let publish = self.handle_incoming_publish(
             space,
             to_agent,
             op_data.from_agent,
             false,
             op_data.dht_hash,
             vec![(op_hash, op_data.op_data)],
-        )
+        )?;
+        // once published the op is held by the cell, or queued for validation
+        Ok(async move {
+            match publish.await {
+                Ok(()) => Ok(true),
+                // the cell isn't running, so `to_agent` doesn't have the op
+                Err(kitsune_p2p::KitsuneP2pError::RoutingAgentError(agent)) => {
+                    tracing::debug!(?agent, "gossiped op not published, cell isn't running");
+                    Ok(false)
+                }
+                Err(e) => Err(e),
+            }
+        }
+        .boxed()
+        .into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_fetch_op_hashes_for_constraints(
         &mut self,
         input: kitsune_p2p::event::FetchOpHashesForConstraintsEvt,
-    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<Vec<Arc<kitsune_p2p::KitsuneOpHash>>>
-    {
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<
+        Vec<(Arc<kitsune_p2p::KitsuneOpHash>, Option<i64>)>,
+    > {
         let kitsune_p2p::event::FetchOpHashesForConstraintsEvt {
             space,
             agent,
@@ -440,7 +455,13 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 .fetch_op_hashes_for_constraints(space, agent, dht_arc, since, until)
                 .await?
                 .into_iter()
-                .map(|h| h.into_kitsune())
+                .map(|(h, when_integrated)| {
+                    let when_integrated_ms = when_integrated
+                        .0
+                        .saturating_mul(1000)
+                        .saturating_add(when_integrated.1 as i64 / 1_000_000);
+                    (h.into_kitsune(), Some(when_integrated_ms))
+                })
                 .collect())
         }
         .boxed()
@@ -835,4 +856,63 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::actor::GossipStatus> {
+        let space = dna_hash.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.gossip_status(space).await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_set_gossip_paused(
+        &mut self,
+        dna_hash: DnaHash,
+        paused: bool,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.set_gossip_paused(space, paused).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_subscribe_gossip_events(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<tokio::sync::broadcast::Receiver<kitsune_p2p::actor::GossipEvent>>
+    {
+        let space = dna_hash.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.subscribe_gossip_events(space).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_set_agent_arc(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        arc: dht_arc::DhtArc,
+    ) -> HolochainP2pHandlerResult<dht_arc::DhtArc> {
+        let space = dna_hash.into_kitsune();
+        let agent = agent_pub_key.into_kitsune();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.set_agent_arc(space, agent, arc).await?) }
+                .boxed()
+                .into(),
+        )
+    }
 }
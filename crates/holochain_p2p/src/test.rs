@@ -103,6 +103,34 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<()> {
         Err("stub".into())
     }
+    fn handle_gossip_status(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::actor::GossipStatus> {
+        Err("stub".into())
+    }
+    fn handle_set_gossip_paused(
+        &mut self,
+        dna_hash: DnaHash,
+        paused: bool,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+    fn handle_subscribe_gossip_events(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<tokio::sync::broadcast::Receiver<kitsune_p2p::actor::GossipEvent>>
+    {
+        Err("stub".into())
+    }
+    fn handle_set_agent_arc(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        arc: dht_arc::DhtArc,
+    ) -> HolochainP2pHandlerResult<dht_arc::DhtArc> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -256,6 +256,18 @@ ghost_actor::ghost_chan! {
 
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, from_agent: AgentPubKey, receipt: SerializedBytes) -> ();
+
+        /// Report how gossip for a dna looked as of its last local sync.
+        fn gossip_status(dna_hash: DnaHash) -> kitsune_p2p::actor::GossipStatus;
+
+        /// Pause or resume gossip for a dna.
+        fn set_gossip_paused(dna_hash: DnaHash, paused: bool) -> ();
+
+        /// Subscribe to gossip events for a dna, as they happen.
+        fn subscribe_gossip_events(dna_hash: DnaHash) -> tokio::sync::broadcast::Receiver<kitsune_p2p::actor::GossipEvent>;
+
+        /// Set the storage arc of an agent for a dna, returning the arc it had before.
+        fn set_agent_arc(dna_hash: DnaHash, agent_pub_key: AgentPubKey, arc: dht_arc::DhtArc) -> dht_arc::DhtArc;
     }
 }
 
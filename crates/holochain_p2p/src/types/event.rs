@@ -151,13 +151,14 @@ ghost_actor::ghost_chan! {
         ) -> ();
 
         /// The p2p module wishes to query our DhtOpHash store.
+        /// Each hash comes with when its op was integrated.
         fn fetch_op_hashes_for_constraints(
             dna_hash: DnaHash,
             to_agent: AgentPubKey,
             dht_arc: kitsune_p2p::dht_arc::DhtArc,
             since: holochain_types::Timestamp,
             until: holochain_types::Timestamp,
-        ) -> Vec<holo_hash::DhtOpHash>;
+        ) -> Vec<(holo_hash::DhtOpHash, holochain_types::Timestamp)>;
 
         /// The p2p module needs access to the content for a given set of DhtOpHashes.
         fn fetch_op_hash_data(
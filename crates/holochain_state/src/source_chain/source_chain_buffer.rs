@@ -22,12 +22,44 @@ pub struct SourceChainBuf {
 
 // TODO fix this.  We shouldn't really have nil values but this would
 // show if the database is corrupted and doesn't have an element
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct SourceChainJsonDump {
     pub elements: Vec<Option<SourceChainJsonElement>>,
     pub published_ops_count: usize,
 }
 
+impl SourceChainJsonDump {
+    fn push(&mut self, maybe_element: Option<Element>) {
+        match maybe_element {
+            None => self.elements.push(None),
+            Some(element) => {
+                let ops = produce_op_lights_from_elements(vec![&element]).unwrap();
+                self.published_ops_count += if element
+                    .header()
+                    .entry_type()
+                    .map(|e| *e.visibility() == EntryVisibility::Public)
+                    .unwrap_or(true)
+                {
+                    ops.len()
+                } else {
+                    ops.into_iter()
+                        .filter(|op| !matches!(&op, DhtOpLight::StoreEntry(_, _, _)))
+                        .count()
+                };
+                let (signed, entry) = element.into_inner();
+                let (header, signature) = signed.into_header_and_signature();
+                let (header, header_address) = header.into_inner();
+                self.elements.push(Some(SourceChainJsonElement {
+                    signature,
+                    header_address,
+                    header,
+                    entry: entry.into_option(),
+                }));
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SourceChainJsonElement {
     pub signature: Signature,
@@ -191,44 +223,31 @@ impl SourceChainBuf {
     /// dump the entire source chain as a pretty-printed json string
     pub async fn dump_state(&self) -> Result<SourceChainJsonDump, SourceChainError> {
         let mut iter = self.iter_back();
-        let mut elements = Vec::new();
-        let mut published_ops_count = 0;
+        let mut dump = SourceChainJsonDump::default();
 
         while let Some(h) = iter.next()? {
-            let maybe_element = self.get_element(h.header_address())?;
-            match maybe_element {
-                None => elements.push(None),
-                Some(element) => {
-                    let ops = produce_op_lights_from_elements(vec![&element]).unwrap();
-                    published_ops_count += if element
-                        .header()
-                        .entry_type()
-                        .map(|e| *e.visibility() == EntryVisibility::Public)
-                        .unwrap_or(true)
-                    {
-                        ops.len()
-                    } else {
-                        ops.into_iter()
-                            .filter(|op| !matches!(&op, DhtOpLight::StoreEntry(_, _, _)))
-                            .count()
-                    };
-                    let (signed, entry) = element.into_inner();
-                    let (header, signature) = signed.into_header_and_signature();
-                    let (header, header_address) = header.into_inner();
-                    elements.push(Some(SourceChainJsonElement {
-                        signature,
-                        header_address,
-                        header,
-                        entry: entry.into_option(),
-                    }));
-                }
-            }
+            dump.push(self.get_element(h.header_address())?);
         }
 
-        Ok(SourceChainJsonDump {
-            elements,
-            published_ops_count,
-        })
+        Ok(dump)
+    }
+
+    /// dump at most `limit` elements of the source chain, starting at
+    /// sequence index `start` and moving forward. Returns fewer than `limit`
+    /// elements once the end of the chain is reached.
+    pub async fn dump_state_range(
+        &self,
+        start: u32,
+        limit: u32,
+    ) -> Result<SourceChainJsonDump, SourceChainError> {
+        let end = std::cmp::min(start.saturating_add(limit) as usize, self.len()) as u32;
+        let mut dump = SourceChainJsonDump::default();
+
+        for i in start..end {
+            dump.push(self.get_at_index(i)?);
+        }
+
+        Ok(dump)
     }
 
     /// Commit the genesis entries to this source chain, making the chain ready
@@ -488,6 +507,53 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn source_chain_buffer_dump_range() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+
+        let (_agent_pubkey, dna_header, dna_entry, agent_header, agent_entry) = fixtures();
+
+        {
+            let mut store = SourceChainBuf::new(arc.clone().into()).unwrap();
+            store
+                .put_raw(dna_header.as_content().clone(), dna_entry)
+                .await?;
+            store
+                .put_raw(agent_header.as_content().clone(), agent_entry)
+                .await?;
+
+            arc.guard()
+                .with_commit(|writer| store.flush_to_txn(writer))?;
+        }
+
+        {
+            let store = SourceChainBuf::new(arc.clone().into()).unwrap();
+
+            // The range moves forward from the start of the chain
+            let dump = store.dump_state_range(0, 1).await?;
+            assert_eq!(dump.elements.len(), 1);
+            assert_eq!(
+                &dump.elements[0].as_ref().unwrap().header,
+                dna_header.as_content()
+            );
+
+            // A range past the head is cut short
+            let dump = store.dump_state_range(1, 10).await?;
+            assert_eq!(dump.elements.len(), 1);
+            assert_eq!(
+                &dump.elements[0].as_ref().unwrap().header,
+                agent_header.as_content()
+            );
+
+            let dump = store.dump_state_range(2, 10).await?;
+            assert!(dump.elements.is_empty());
+            assert_eq!(dump.published_ops_count, 0);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_header_cas_roundtrip() {
         let test_env = test_cell_env();
@@ -17,6 +17,8 @@ pub trait DnaStore: Default + Send + Sync {
     /// List all DNAs in the store
     // TODO: FAST: Make this return an iterator to avoid allocating
     fn list(&self) -> Vec<DnaHash>;
+    /// List all DNAs in the store with the names of their zomes
+    fn list_zome_names(&self) -> Vec<(DnaHash, Vec<ZomeName>)>;
     /// Get a particular DnaFile
     fn get(&self, hash: &DnaHash) -> Option<DnaFile>;
     /// Get a particular EntryDef
@@ -11,12 +11,70 @@ use holochain_zome_types::prelude::*;
 /// an Interface
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum Signal {
+    /// Signal from a Cell, generated by `emit_signal`,
+    /// with its [`AppSignalSeq`]
+    App(CellId, AppSignal, AppSignalSeq),
+    /// System-defined signals
+    System(SystemSignal),
+}
+
+/// A [`Signal`] as it is sent to clients of the `holochain-app-v1` app
+/// interface subprotocol, or of no subprotocol, which predate
+/// [`AppSignalSeq`]: app signals are sent without their sequence number.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
+pub enum SignalV1 {
     /// Signal from a Cell, generated by `emit_signal`
     App(CellId, AppSignal),
     /// System-defined signals
     System(SystemSignal),
 }
 
+impl From<Signal> for SignalV1 {
+    fn from(signal: Signal) -> Self {
+        match signal {
+            Signal::App(cell_id, app_signal, _) => Self::App(cell_id, app_signal),
+            Signal::System(system_signal) => Self::System(system_signal),
+        }
+    }
+}
+
+/// The position of an app signal among those emitted by its Cell.
+/// The numbering is only kept in memory, so it starts again at 0, with a new
+/// `started_at`, whenever the Cell is started again, e.g. when its conductor
+/// restarts.
+/// A client seeing a `seq` more than one past the previous one from the
+/// same Cell and `started_at` has missed signals in between, e.g. because
+/// its connection fell behind, and may want to resync. A different
+/// `started_at` means the numbering was restarted, and the client can't
+/// tell how many signals it missed.
+/// Coalescing signals on an interface skips numbers on purpose.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppSignalSeq {
+    /// When the Cell started this numbering
+    pub started_at: Timestamp,
+    /// 0 for the first signal since `started_at`, then one more for each
+    /// signal after it, in the order they are sent to the interfaces
+    pub seq: u64,
+}
+
+impl AppSignalSeq {
+    /// The number of the first signal of a numbering starting now
+    pub fn start() -> Self {
+        Self {
+            started_at: crate::timestamp::now(),
+            seq: 0,
+        }
+    }
+
+    /// The number of the signal after this one
+    pub fn next(self) -> Self {
+        Self {
+            seq: self.seq + 1,
+            ..self
+        }
+    }
+}
+
 /// A Signal which originates from within the Holochain system, as opposed to
 /// from within a Cell
 ///
@@ -27,6 +85,26 @@ pub enum SystemSignal {
     /// Since we have no real system signals, we use a test signal for testing
     /// TODO: replace instances of this with something real
     Test(String),
+    /// Sent in place of a signal which couldn't be serialized for an
+    /// interface, so its clients know they missed one.
+    EncodeFailed {
+        /// The Cell which emitted the signal, if it was an app signal
+        cell_id: Option<CellId>,
+        /// The [`AppSignalSeq`] of the signal, if it was an app signal
+        seq: Option<AppSignalSeq>,
+    },
+}
+
+impl Signal {
+    /// The [`SystemSignal::EncodeFailed`] to send in place of this signal
+    /// when it can't be serialized
+    pub fn encode_failed(&self) -> Signal {
+        let (cell_id, seq) = match self {
+            Signal::App(cell_id, _, seq) => (Some(cell_id.clone()), Some(*seq)),
+            Signal::System(_) => (None, None),
+        };
+        SystemSignal::EncodeFailed { cell_id, seq }.into()
+    }
 }
 
 /// Create a test signal
@@ -19,6 +19,8 @@ pub enum WebsocketError {
     RespTimeout,
     #[error("The websocket connection has shutdown")]
     Shutdown,
+    #[error("Websocket handshake with {remote_addr} failed: {reason}")]
+    Handshake { remote_addr: String, reason: String },
 }
 
 pub type WebsocketResult<T> = Result<T, WebsocketError>;
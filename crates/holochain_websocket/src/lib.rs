@@ -83,6 +83,9 @@ use std::sync::Arc;
 use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
+use tungstenite::http::HeaderValue;
 use url2::Url2;
 use util::url_to_addr;
 use websocket::Websocket;
@@ -119,20 +122,39 @@ pub async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    if !config.subprotocols.is_empty() {
+        request.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_str(&config.subprotocols.join(", "))
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        );
+    }
+    let (socket, response) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
+    let subprotocol = response
+        .headers()
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|accepted| {
+            config
+                .subprotocols
+                .iter()
+                .find(|s| **s == accepted)
+                .copied()
+        });
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, valve, subprotocol, None)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
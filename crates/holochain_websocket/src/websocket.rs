@@ -121,10 +121,15 @@ impl Task {
 impl Websocket {
     #[instrument(skip(config, socket, listener_shutdown))]
     /// Create the ends of this websocket channel.
+    /// `subprotocol` is the one negotiated during the handshake, if any.
+    /// `forwarded_for` is the client address a proxy forwarded the
+    /// connection for, if any.
     pub fn create_ends(
         config: Arc<WebsocketConfig>,
         socket: ToFromSocket,
         listener_shutdown: Valve,
+        subprotocol: Option<&'static str>,
+        forwarded_for: Option<String>,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
         let remote_addr = url2::url2!(
             "{}#{}",
@@ -173,7 +178,13 @@ impl Websocket {
             pair_shutdown_handle.clone(),
         );
         // Create the receiver end.
-        let receiver = WebsocketReceiver::new(rx_from_websocket, remote_addr, pair_shutdown_handle);
+        let receiver = WebsocketReceiver::new(
+            rx_from_websocket,
+            remote_addr,
+            subprotocol,
+            forwarded_for,
+            pair_shutdown_handle,
+        );
         Ok((sender, receiver))
     }
 
@@ -307,6 +318,18 @@ impl Websocket {
                 // Map outgoing messages to wire messages.
                 let msg = match msg {
                     OutgoingMessage::Close => return Task::exit(),
+                    OutgoingMessage::CloseWithReason(code, reason) => {
+                        // Send our own close frame instead of
+                        // the generic one sent on exit.
+                        to_socket
+                            .send(tungstenite::Message::Close(Some(CloseFrame {
+                                code: CloseCode::from(code),
+                                reason: reason.into(),
+                            })))
+                            .await
+                            .ok();
+                        return Task::exit_now();
+                    }
                     OutgoingMessage::Signal(msg) => WireMessage::Signal {
                         data: UnsafeBytes::from(msg).into(),
                     },
@@ -1,7 +1,12 @@
 //! defines a builder-style config struct for setting up websockets
 
 /// A builder-style config struct for setting up websockets.
-#[derive(Debug)]
+///
+/// There is no compression option: the tungstenite version this is built on
+/// has no support for websocket extensions, so a client offering
+/// `permessage-deflate` is accepted without it and every frame is sent
+/// uncompressed.
+#[derive(Clone, Debug)]
 pub struct WebsocketConfig {
     /// Scheme to use for urls - e.g. "ws" or "wss". [default = "ws"]
     pub scheme: &'static str,
@@ -26,6 +31,22 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Websocket subprotocols (`Sec-WebSocket-Protocol`) to negotiate.
+    /// A listener accepts the first of these a client requests and rejects
+    /// clients which only request others. Clients which request no
+    /// subprotocol are always accepted. A connecting client requests all of these.
+    /// [default = none, no negotiation]
+    pub subprotocols: Vec<&'static str>,
+
+    /// A header, such as `X-Forwarded-For`, which a reverse proxy in front
+    /// of a listener sets to the address of the client it is forwarding.
+    /// The first address in it is the accepted connection's
+    /// [`forwarded_for`](crate::WebsocketReceiver::forwarded_for).
+    /// Any client connecting directly can set this header to anything,
+    /// so it is only fit for logging.
+    /// [default = none, the header isn't read]
+    pub forwarded_for_header: Option<String>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +59,8 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            subprotocols: Vec::new(),
+            forwarded_for_header: None,
         }
     }
 }
@@ -78,6 +101,18 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn subprotocols(mut self, subprotocols: Vec<&'static str>) -> Self {
+        self.subprotocols = subprotocols;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn forwarded_for_header(mut self, header: Option<String>) -> Self {
+        self.forwarded_for_header = header;
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -1,13 +1,17 @@
 use futures::stream::BoxStream;
 use futures::StreamExt;
 use futures::TryStreamExt;
-use std::io::Error;
-use std::io::ErrorKind;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
+use tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
+use tungstenite::http::HeaderValue;
+use tungstenite::http::StatusCode;
 
 use url2::Url2;
 
@@ -237,12 +241,40 @@ async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
+    let remote_addr = socket.peer_addr()?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    let mut negotiated = None;
+    let mut forwarded_for = None;
+    let negotiate = |request: &Request, mut response: Response| {
+        forwarded_for = forwarded_for_addr(request, config.forwarded_for_header.as_deref());
+        let requested = request
+            .headers()
+            .get(SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|v| v.to_str().ok());
+        match negotiate_subprotocol(&config.subprotocols, requested) {
+            Ok(Some(subprotocol)) => {
+                negotiated = Some(subprotocol);
+                response.headers_mut().insert(
+                    SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_static(subprotocol),
+                );
+                Ok(response)
+            }
+            Ok(None) => Ok(response),
+            Err(reason) => {
+                tracing::warn!(%reason, "rejecting websocket connection");
+                let mut response = ErrorResponse::new(Some(reason));
+                *response.status_mut() = StatusCode::BAD_REQUEST;
+                Err(response)
+            }
+        }
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        negotiate,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
@@ -251,7 +283,49 @@ async fn connect(
         }),
     )
     .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    .map_err(|e| WebsocketError::Handshake {
+        remote_addr: remote_addr.to_string(),
+        reason: e.to_string(),
+    })?;
+
+    if let Some(forwarded_for) = &forwarded_for {
+        tracing::debug!(%remote_addr, %forwarded_for, "connection was forwarded");
+    }
+
+    Websocket::create_ends(config, socket, valve, negotiated, forwarded_for)
+}
 
-    Websocket::create_ends(config, socket, valve)
+/// The client address a proxy put in the first entry of `header`,
+/// if the request has it.
+fn forwarded_for_addr(request: &Request, header: Option<&str>) -> Option<String> {
+    let value = request.headers().get(header?)?.to_str().ok()?;
+    let addr = value.split(',').next()?.trim();
+    if addr.is_empty() {
+        None
+    } else {
+        Some(addr.to_string())
+    }
+}
+
+/// Pick the first subprotocol requested by a client which we support.
+/// Returns `Ok(None)` if there is nothing to negotiate, or an error reason
+/// if the client only requested subprotocols we don't support.
+fn negotiate_subprotocol(
+    supported: &[&'static str],
+    requested: Option<&str>,
+) -> Result<Option<&'static str>, String> {
+    let requested = match requested {
+        Some(requested) if !supported.is_empty() => requested,
+        _ => return Ok(None),
+    };
+    for r in requested.split(',').map(str::trim) {
+        if let Some(s) = supported.iter().find(|s| **s == r) {
+            return Ok(Some(*s));
+        }
+    }
+    Err(format!(
+        "Unsupported websocket subprotocol requested: '{}', expected one of: '{}'",
+        requested,
+        supported.join(", ")
+    ))
 }
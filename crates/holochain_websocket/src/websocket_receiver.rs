@@ -38,6 +38,8 @@ use crate::WebsocketResult;
 pub struct WebsocketReceiver {
     rx_from_websocket: Valved<Valved<RxFromWebsocket>>,
     remote_addr: Url2,
+    subprotocol: Option<&'static str>,
+    forwarded_for: Option<String>,
     handle: Option<ReceiverHandle>,
     __pair_shutdown: Arc<PairShutdown>,
 }
@@ -85,6 +87,8 @@ impl WebsocketReceiver {
     pub(crate) fn new(
         rx_from_websocket: Valved<RxFromWebsocket>,
         remote_addr: Url2,
+        subprotocol: Option<&'static str>,
+        forwarded_for: Option<String>,
         pair_shutdown: Arc<PairShutdown>,
     ) -> Self {
         let (shutdown, rx_from_websocket_valved) = Valved::new(rx_from_websocket);
@@ -92,6 +96,8 @@ impl WebsocketReceiver {
         Self {
             rx_from_websocket: rx_from_websocket_valved,
             remote_addr,
+            subprotocol,
+            forwarded_for,
             handle,
             __pair_shutdown: pair_shutdown,
         }
@@ -105,6 +111,23 @@ impl WebsocketReceiver {
     pub fn remote_addr(&self) -> &Url2 {
         &self.remote_addr
     }
+    /// get the subprotocol negotiated for this websocket, if any.
+    pub fn subprotocol(&self) -> Option<&'static str> {
+        self.subprotocol
+    }
+    /// get the client address a proxy forwarded this websocket for,
+    /// see [`WebsocketConfig::forwarded_for_header`](crate::WebsocketConfig::forwarded_for_header).
+    pub fn forwarded_for(&self) -> Option<&str> {
+        self.forwarded_for.as_deref()
+    }
+    /// get the address of the client, as forwarded by a proxy if there
+    /// was one, otherwise the remote url.
+    pub fn client_addr(&self) -> String {
+        match &self.forwarded_for {
+            Some(forwarded_for) => forwarded_for.clone(),
+            None => self.remote_addr.to_string(),
+        }
+    }
 }
 
 impl futures::stream::Stream for WebsocketReceiver {
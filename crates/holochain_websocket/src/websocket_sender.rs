@@ -92,6 +92,7 @@ impl RegisterResponse {
 /// A message going **out** to the external socket.
 pub(crate) enum OutgoingMessage {
     Close,
+    CloseWithReason(u16, String),
     Signal(SerializedBytes),
     Request(SerializedBytes, RegisterResponse, TxStaleRequest),
     Response(Option<SerializedBytes>, u64),
@@ -203,6 +204,51 @@ impl WebsocketSender {
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
+    /// Same as [`WebsocketSender::signal`] but first attempts a non-blocking send,
+    /// only awaiting if the outgoing buffer is full.
+    /// Bursts of small signals can then be queued without yielding to the
+    /// scheduler for each one.
+    pub async fn signal_eager<I, E>(&mut self, msg: I) -> WebsocketResult<()>
+    where
+        I: std::fmt::Debug,
+        WebsocketError: From<E>,
+        SerializedBytes: TryFrom<I, Error = E>,
+    {
+        use tokio::sync::mpsc::error::TrySendError;
+        tracing::trace!("Sending");
+        let msg = OutgoingMessage::Signal(msg.try_into()?);
+
+        match self.tx_to_websocket.try_send(msg) {
+            Ok(()) => (),
+            Err(TrySendError::Full(msg)) => {
+                tracing::trace!("Buffer full, waiting to send");
+                self.tx_to_websocket
+                    .send(msg)
+                    .await
+                    .map_err(|_| WebsocketError::Shutdown)?;
+            }
+            Err(TrySendError::Closed(_)) => return Err(WebsocketError::Shutdown),
+        }
+
+        tracing::trace!("Sent");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    /// Close the connection, sending the other side a close frame with the given
+    /// [close code](https://tools.ietf.org/html/rfc6455#section-7.4.1) and reason
+    /// so it can tell why the connection ended.
+    /// The reason is truncated to fit in a control frame.
+    pub async fn close(&mut self, code: u16, reason: String) -> WebsocketResult<()> {
+        let msg = OutgoingMessage::CloseWithReason(code, truncate_close_reason(reason));
+        self.tx_to_websocket
+            .send(msg)
+            .await
+            .map_err(|_| WebsocketError::Shutdown)?;
+        Ok(())
+    }
+
     #[cfg(test)]
     pub(crate) async fn debug(&mut self) -> WebsocketResult<(Vec<u64>, u64)> {
         let (tx_resp, rx_resp) = tokio::sync::oneshot::channel();
@@ -215,6 +261,21 @@ impl WebsocketSender {
     }
 }
 
+/// Control frames can only carry 125 bytes of payload,
+/// two of which are the close code.
+const MAX_CLOSE_REASON_LEN: usize = 123;
+
+fn truncate_close_reason(mut reason: String) -> String {
+    if reason.len() > MAX_CLOSE_REASON_LEN {
+        let mut end = MAX_CLOSE_REASON_LEN;
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        reason.truncate(end);
+    }
+    reason
+}
+
 impl StaleRequest {
     /// To remove responses we need the channel to the websocket
     /// and the id of the request.
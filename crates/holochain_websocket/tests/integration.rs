@@ -179,6 +179,42 @@ async fn can_send_signal() {
     jh.await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_eager_signals() {
+    observability::test_run().ok();
+    const NUM_SIGNALS: usize = 1000;
+    let (handle, mut listener) = server().await;
+    let jh = tokio::task::spawn(async move {
+        let (mut sender, receiver) = listener.next().await.unwrap().unwrap();
+
+        // - Signal client in a burst, more than fits in the outgoing buffer
+        for i in 0..NUM_SIGNALS {
+            sender
+                .signal_eager(TestString(format!("Hey {} from server", i)))
+                .await
+                .unwrap();
+        }
+
+        // - Keep the connection open until the client has everything
+        (sender, receiver)
+    });
+
+    // - Connect client
+    let binding = handle.local_addr().clone();
+    let (_sender, mut receiver) = connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+
+    // - Receive every signal in order
+    for i in 0..NUM_SIGNALS {
+        let (msg, _) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, format!("Hey {} from server", i));
+    }
+
+    let _pair = jh.await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_send_request() {
     observability::test_run().ok();
@@ -452,3 +488,206 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subprotocol_negotiation() {
+    observability::test_run().ok();
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default().subprotocols(vec!["test-v1"])),
+    )
+    .await
+    .unwrap();
+    let (tx_negotiated, mut rx_negotiated) = tokio::sync::mpsc::unbounded_channel();
+    tokio::task::spawn(async move {
+        while let Some(Ok((_, receiver))) = listener.next().await {
+            tx_negotiated.send(receiver.subprotocol()).unwrap();
+        }
+    });
+    let binding = handle.local_addr().clone();
+
+    // supported subprotocol
+    let (_, receiver) = connect(
+        binding.clone(),
+        Arc::new(WebsocketConfig::default().subprotocols(vec!["test-v2", "test-v1"])),
+    )
+    .await
+    .expect("Failed to connect with a supported subprotocol");
+    assert_eq!(receiver.subprotocol(), Some("test-v1"));
+    assert_eq!(rx_negotiated.recv().await.unwrap(), Some("test-v1"));
+
+    // no subprotocol requested
+    let (_, receiver) = connect(binding.clone(), Arc::new(WebsocketConfig::default()))
+        .await
+        .expect("Failed to connect without a subprotocol");
+    assert_eq!(receiver.subprotocol(), None);
+    assert_eq!(rx_negotiated.recv().await.unwrap(), None);
+
+    // only unsupported subprotocols requested
+    let r = connect(
+        binding,
+        Arc::new(WebsocketConfig::default().subprotocols(vec!["test-v2"])),
+    )
+    .await;
+    assert!(r.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forwarded_client_addresses_are_read_from_the_configured_header() {
+    use tungstenite::client::IntoClientRequest;
+    observability::test_run().ok();
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(
+            WebsocketConfig::default().forwarded_for_header(Some("X-Forwarded-For".to_string())),
+        ),
+    )
+    .await
+    .unwrap();
+    let (tx_accepted, mut rx_accepted) = tokio::sync::mpsc::unbounded_channel();
+    tokio::task::spawn(async move {
+        while let Some(Ok((_, receiver))) = listener.next().await {
+            tx_accepted
+                .send((
+                    receiver.forwarded_for().map(str::to_string),
+                    receiver.client_addr(),
+                    receiver.remote_addr().to_string(),
+                ))
+                .unwrap();
+        }
+    });
+    let connect_with = |forwarded_for: Option<&'static str>| {
+        let mut request = handle.local_addr().as_str().into_client_request().unwrap();
+        if let Some(forwarded_for) = forwarded_for {
+            request
+                .headers_mut()
+                .insert("x-forwarded-for", forwarded_for.parse().unwrap());
+        }
+        tokio_tungstenite::connect_async(request)
+    };
+
+    // the first address is the client's, the rest are proxies
+    let _socket = connect_with(Some("203.0.113.7, 10.0.0.1")).await.unwrap();
+    let (forwarded_for, client_addr, _) = rx_accepted.recv().await.unwrap();
+    assert_eq!(forwarded_for.as_deref(), Some("203.0.113.7"));
+    assert_eq!(client_addr, "203.0.113.7");
+
+    // without the header, the socket's address is used
+    let _socket = connect_with(None).await.unwrap();
+    let (forwarded_for, client_addr, remote_addr) = rx_accepted.recv().await.unwrap();
+    assert_eq!(forwarded_for, None);
+    assert_eq!(client_addr, remote_addr);
+
+    // an empty header is ignored
+    let _socket = connect_with(Some(" ")).await.unwrap();
+    let (forwarded_for, _, _) = rx_accepted.recv().await.unwrap();
+    assert_eq!(forwarded_for, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_with_reason() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let jh = tokio::task::spawn(async move {
+        let (mut sender, receiver) = listener.next().await.unwrap().unwrap();
+        sender
+            .close(1011, "serialization_error: bad bytes".to_string())
+            .await
+            .unwrap();
+        (sender, receiver)
+    });
+
+    // - Connect a plain client so we can see the close frame
+    let (mut socket, _) = tokio_tungstenite::connect_async(handle.local_addr().as_str())
+        .await
+        .unwrap();
+    let mut frame = None;
+    while let Some(Ok(msg)) = socket.next().await {
+        if let tungstenite::Message::Close(f) = msg {
+            frame = f;
+            break;
+        }
+    }
+    let frame = frame.expect("Expected a close frame with a reason");
+    assert_eq!(u16::from(frame.code), 1011);
+    assert_eq!(frame.reason, "serialization_error: bad bytes");
+
+    let _pair = jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_handshake_is_reported_as_such() {
+    use tokio::io::AsyncWriteExt;
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let addr = format!(
+        "{}:{}",
+        handle.local_addr().host_str().unwrap(),
+        handle.local_addr().port().unwrap()
+    );
+
+    // a client that doesn't speak websocket
+    let mut socket = tokio::net::TcpStream::connect(addr).await.unwrap();
+    socket
+        .write_all(b"not a websocket upgrade\r\n\r\n")
+        .await
+        .unwrap();
+    let local_addr = socket.local_addr().unwrap().to_string();
+
+    let r = listener.next().await.unwrap();
+    match r {
+        Err(WebsocketError::Handshake { remote_addr, .. }) => assert_eq!(remote_addr, local_addr),
+        r => panic!("expected a handshake error, got {:?}", r.map(|_| ())),
+    }
+
+    // the listener keeps accepting connections after a failed handshake
+    let binding = handle.local_addr().clone();
+    let jh = tokio::task::spawn(async move { listener.next().await.unwrap().is_ok() });
+    connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    assert!(jh.await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn compression_is_not_negotiated() {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let addr = format!(
+        "{}:{}",
+        handle.local_addr().host_str().unwrap(),
+        handle.local_addr().port().unwrap()
+    );
+
+    // a client offering permessage-deflate
+    let mut socket = tokio::net::TcpStream::connect(&addr).await.unwrap();
+    let request = format!(
+        "GET / HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits\r\n\
+         \r\n",
+        addr
+    );
+    socket.write_all(request.as_bytes()).await.unwrap();
+    assert!(listener.next().await.unwrap().is_ok());
+
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        let mut byte = [0u8];
+        socket.read_exact(&mut byte).await.unwrap();
+        response.push(byte[0]);
+    }
+    let response = String::from_utf8(response).unwrap().to_lowercase();
+    assert!(response.starts_with("http/1.1 101"), "{}", response);
+    assert!(
+        !response.contains("sec-websocket-extensions"),
+        "{}",
+        response
+    );
+}
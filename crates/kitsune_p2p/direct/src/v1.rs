@@ -679,7 +679,7 @@ async fn handle_gossip(
     _from_agent: Arc<KitsuneAgent>,
     op_hash: Arc<KitsuneOpHash>,
     op_data: Vec<u8>,
-) -> KdResult<()> {
+) -> KdResult<bool> {
     let entry = KdEntrySigned::from_wire(op_data.into_boxed_slice())
         .await
         .map_err(KdError::other)?;
@@ -692,13 +692,13 @@ async fn handle_gossip(
 
     kdirect.persist.store_entry(root, to_agent, entry).await?;
 
-    Ok(())
+    Ok(true)
 }
 
 async fn handle_fetch_op_hashes_for_constraints(
     kdirect: Arc<Kd1>,
     input: FetchOpHashesForConstraintsEvt,
-) -> KdResult<Vec<Arc<KitsuneOpHash>>> {
+) -> KdResult<Vec<(Arc<KitsuneOpHash>, Option<i64>)>> {
     let FetchOpHashesForConstraintsEvt {
         space,
         agent,
@@ -724,7 +724,8 @@ async fn handle_fetch_op_hashes_for_constraints(
 
     Ok(entries
         .into_iter()
-        .map(|e| e.hash().clone().to_kitsune_op_hash())
+        // entries don't record when they were received
+        .map(|e| (e.hash().clone().to_kitsune_op_hash(), None))
         .collect())
 }
 
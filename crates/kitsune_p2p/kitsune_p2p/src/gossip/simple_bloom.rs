@@ -15,6 +15,10 @@ use std::sync::Arc;
 /// with the constant in PoolBuf which cannot be set at runtime)
 const MAX_SEND_BUF_BYTES: usize = 16000;
 
+/// how many gossip events a subscriber can fall behind by
+/// before it starts missing them
+const GOSSIP_EVENT_CAPACITY: usize = 64;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum MetaOpKey {
     /// data key type
@@ -59,11 +63,229 @@ impl MetaOpData {
     }
 }
 
+impl MetaOpKey {
+    /// the dht location of this key
+    fn loc(&self) -> u32 {
+        match self {
+            MetaOpKey::Op(h) => h.get_loc(),
+            MetaOpKey::Agent(a, _) => a.get_loc(),
+        }
+    }
+
+    /// which of `chunk_count` equal dht location subranges this key falls in
+    fn chunk_index(&self, chunk_count: usize) -> usize {
+        ((self.loc() as u64 * chunk_count as u64) >> 32) as usize
+    }
+}
+
 type KeySet = HashSet<Arc<MetaOpKey>>;
-type HasMap = HashMap<Arc<KitsuneAgent>, KeySet>;
 type DataMap = HashMap<Arc<MetaOpKey>, Arc<MetaOpData>>;
+/// when ops were received, in utc epoch milliseconds
+type OpReceivedAt = HashMap<Arc<MetaOpKey>, i64>;
 type BloomFilter = bloomfilter::Bloom<Arc<MetaOpKey>>;
 
+/// The false positive rate every bloom chunk is sized for: a key we don't
+/// hold is reported as held at most 1 in 100 times, unless the chunk was
+/// capped at the max chunk size, see [BloomChunks::rebuild].
+/// We can get 1 in 1000 for ~2x the filter size, but may not be worth it.
+/// 1 in 100 pretty much guarantees full sync after two communications.
+/// Checked by the `blooms_meet_the_target_false_positive_rate` test.
+const TGT_FP: f64 = 0.01;
+
+/// What we tell peers we hold: bloom filters, or for an op set smaller
+/// than `gossip_bloom_min_key_count`, the exact keys.
+pub(crate) enum KeyFilter {
+    Bloom(BloomChunks),
+    Exact(KeySet),
+}
+
+impl KeyFilter {
+    /// mark a key as held
+    pub(crate) fn set(&mut self, key: &Arc<MetaOpKey>) {
+        match self {
+            KeyFilter::Bloom(bloom) => bloom.set(key),
+            KeyFilter::Exact(keys) => {
+                keys.insert(key.clone());
+            }
+        }
+    }
+
+    /// check if a key may be held, exact keys have no false positives
+    pub(crate) fn check(&self, key: &Arc<MetaOpKey>) -> bool {
+        match self {
+            KeyFilter::Bloom(bloom) => bloom.check(key),
+            KeyFilter::Exact(keys) => keys.contains(key),
+        }
+    }
+
+    /// the bloom filters, if these aren't exact keys
+    pub(crate) fn into_bloom(self) -> Option<BloomChunks> {
+        match self {
+            KeyFilter::Bloom(bloom) => Some(bloom),
+            KeyFilter::Exact(_) => None,
+        }
+    }
+}
+
+/// One bloom filter per dht location subrange, see [MetaOpKey::chunk_index].
+/// There is always at least one chunk.
+pub(crate) struct BloomChunks(Vec<BloomFilter>);
+
+impl BloomChunks {
+    /// empty filters for `chunk_count` subranges
+    pub(crate) fn empty(chunk_count: usize) -> Self {
+        Self(
+            (0..std::cmp::max(chunk_count, 1))
+                .map(|_| bloomfilter::Bloom::new(1, 1))
+                .collect(),
+        )
+    }
+
+    /// build one filter per subrange, each sized for the keys that fall in it.
+    /// if `max_chunk_bytes` is not 0, no filter is larger than it,
+    /// see [BloomChunks::rebuild]
+    pub(crate) fn build(key_set: &KeySet, chunk_count: usize, max_chunk_bytes: usize) -> Self {
+        let mut chunks = Self(Vec::new());
+        chunks.rebuild(key_set, chunk_count, max_chunk_bytes);
+        chunks
+    }
+
+    /// clear these filters and repopulate them with `key_set`,
+    /// as [BloomChunks::build] would, but keeping the existing
+    /// allocation for any subrange whose filter is still a good fit.
+    /// if `max_chunk_bytes` is not 0, more than `chunk_count` chunks are
+    /// used when the filters would otherwise be larger than it, and a chunk
+    /// that still would be is capped at it, at a higher false positive rate
+    pub(crate) fn rebuild(&mut self, key_set: &KeySet, chunk_count: usize, max_chunk_bytes: usize) {
+        // how much larger than needed (in eighths) a filter can be
+        // and still be reused, rather than sending the extra bits around
+        const REUSE_SLACK_EIGHTHS: u64 = 2;
+
+        let chunk_count = chunk_count_for(key_set.len(), chunk_count, max_chunk_bytes);
+        let mut partitions: Vec<Vec<&Arc<MetaOpKey>>> = vec![Vec::new(); chunk_count];
+        for key in key_set.iter() {
+            partitions[key.chunk_index(chunk_count)].push(key);
+        }
+
+        self.0
+            .resize_with(chunk_count, || bloomfilter::Bloom::new(1, 1));
+
+        for (bloom, keys) in self.0.iter_mut().zip(partitions) {
+            let mut want_bytes = if keys.is_empty() {
+                1
+            } else {
+                BloomFilter::compute_bitmap_size(keys.len(), TGT_FP)
+            };
+            if max_chunk_bytes > 0 && want_bytes > max_chunk_bytes {
+                tracing::warn!(
+                    key_count = %keys.len(),
+                    %want_bytes,
+                    %max_chunk_bytes,
+                    "bloom chunk capped at the max chunk size, its false positive rate will be above target",
+                );
+                want_bytes = max_chunk_bytes;
+            }
+            let want_bits = want_bytes as u64 * 8;
+            let have_bits = bloom.number_of_bits();
+            // a larger filter than needed only lowers the false positive
+            // rate, so keeping the old hash function count is fine
+            if have_bits >= want_bits
+                && have_bits <= want_bits + want_bits * REUSE_SLACK_EIGHTHS / 8
+            {
+                bloom.clear();
+            } else if keys.is_empty() {
+                *bloom = bloomfilter::Bloom::new(1, 1);
+            } else {
+                *bloom = bloomfilter::Bloom::new(want_bytes, keys.len());
+            }
+            for key in keys {
+                bloom.set(key);
+            }
+        }
+    }
+
+    /// mark a key as held
+    pub(crate) fn set(&mut self, key: &Arc<MetaOpKey>) {
+        let idx = key.chunk_index(self.0.len());
+        self.0[idx].set(key);
+    }
+
+    /// check if a key may be held
+    pub(crate) fn check(&self, key: &Arc<MetaOpKey>) -> bool {
+        self.0[key.chunk_index(self.0.len())].check(key)
+    }
+
+    /// total number of bits across all chunks
+    pub(crate) fn bit_count(&self) -> u64 {
+        self.0.iter().map(|b| b.number_of_bits()).sum()
+    }
+
+    /// total bitmap size in bytes across all chunks,
+    /// i.e. roughly what this costs to send
+    pub(crate) fn byte_count(&self) -> u64 {
+        self.0.iter().map(|b| (b.number_of_bits() + 7) / 8).sum()
+    }
+
+    /// roughly how many keys these filters can hold in total
+    /// before exceeding the target false positive rate
+    pub(crate) fn capacity(&self) -> u64 {
+        let ln2_sq = std::f64::consts::LN_2 * std::f64::consts::LN_2;
+        self.0
+            .iter()
+            .map(|b| (b.number_of_bits() as f64 * ln2_sq / -TGT_FP.ln()) as u64)
+            .sum()
+    }
+
+    /// the largest number of hash functions used by any chunk
+    pub(crate) fn hash_fn_count(&self) -> u32 {
+        self.0
+            .iter()
+            .map(|b| b.number_of_hash_functions())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// encode every chunk for sending over the wire
+    pub(crate) fn encode(&self) -> Vec<PoolBuf> {
+        self.0.iter().map(encode_bloom_filter).collect()
+    }
+
+    /// decode chunks received over the wire,
+    /// `None` if the remote sent no chunks at all
+    pub(crate) fn decode(filters: &[PoolBuf]) -> Option<Self> {
+        if filters.is_empty() {
+            return None;
+        }
+        Some(Self(
+            filters.iter().map(|f| decode_bloom_filter(f)).collect(),
+        ))
+    }
+}
+
+/// how many chunks to build a bloom for `key_count` keys with.
+/// `chunk_count`, unless that would make the filters larger than
+/// `max_chunk_bytes` each, in which case enough chunks that they aren't
+fn chunk_count_for(key_count: usize, chunk_count: usize, max_chunk_bytes: usize) -> usize {
+    let chunk_count = std::cmp::max(chunk_count, 1);
+    if max_chunk_bytes == 0 || key_count == 0 {
+        return chunk_count;
+    }
+    let total_bytes = BloomFilter::compute_bitmap_size(key_count, TGT_FP);
+    let needed = (total_bytes + max_chunk_bytes - 1) / max_chunk_bytes;
+    if needed > chunk_count {
+        tracing::info!(
+            %key_count,
+            %total_bytes,
+            %max_chunk_bytes,
+            configured_chunk_count = %chunk_count,
+            chunk_count = %needed,
+            "splitting local bloom into more chunks to keep each under the max chunk size",
+        );
+        return needed;
+    }
+    chunk_count
+}
+
 pub(crate) fn encode_bloom_filter(bloom: &BloomFilter) -> PoolBuf {
     let bitmap: Vec<u8> = bloom.bitmap();
     let bitmap_bits: u64 = bloom.number_of_bits();
@@ -105,6 +327,77 @@ pub(crate) fn decode_bloom_filter(bloom: &[u8]) -> BloomFilter {
     bloomfilter::Bloom::from_existing(&bloom[44..], bitmap_bits, k_num, sip_keys)
 }
 
+/// A hash of encoded bloom filters, identifying them to a peer that
+/// has already been sent them. This is FNV-1a, which unlike the std
+/// hasher is stable across builds, as the peer may be running another one.
+pub(crate) fn bloom_hash(filters: &[PoolBuf]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for filter in filters {
+        // include the lengths, so where one filter ends is part of the hash
+        for byte in (filter.len() as u64)
+            .to_le_bytes()
+            .iter()
+            .chain(filter.iter())
+        {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// The filters last received from a peer, see `remote_blooms`
+pub(crate) type RemoteBlooms = (std::time::Instant, Option<u64>, Option<KeyFilter>);
+
+/// forget the filters of peers which sent them longer than `ttl` ago
+pub(crate) fn expire_remote_blooms(
+    remote_blooms: &mut HashMap<Tx2Cert, RemoteBlooms>,
+    ttl: std::time::Duration,
+) {
+    remote_blooms.retain(|_, (received_at, _, _)| received_at.elapsed() < ttl);
+}
+
+/// If `filters` are the ones last sent to `cert`, the hash to send in their
+/// place. Otherwise `None`, and `filters` are recorded as the last sent.
+pub(crate) fn unchanged_bloom_hash(
+    sent_bloom_hashes: &mut HashMap<Tx2Cert, u64>,
+    cert: &Tx2Cert,
+    filters: &[PoolBuf],
+) -> Option<u64> {
+    let hash = bloom_hash(filters);
+    if sent_bloom_hashes.get(cert) == Some(&hash) {
+        return Some(hash);
+    }
+    sent_bloom_hashes.insert(cert.clone(), hash);
+    None
+}
+
+/// An initiate sending `filters`, in the single filter form peers
+/// from before chunking understand if there is only one
+pub(crate) fn initiate_with_filters(mut filters: Vec<PoolBuf>) -> GossipWire {
+    match filters.len() {
+        1 => GossipWire::initiate(filters.remove(0)),
+        _ => GossipWire::initiate_chunked(filters),
+    }
+}
+
+/// An accept sending `filters`, see [initiate_with_filters]
+pub(crate) fn accept_with_filters(mut filters: Vec<PoolBuf>) -> GossipWire {
+    match filters.len() {
+        1 => GossipWire::accept(filters.remove(0)),
+        _ => GossipWire::accept_chunked(filters),
+    }
+}
+
+mod has_map;
+use has_map::*;
+mod op_order;
+use op_order::*;
+mod snapshot;
+use snapshot::*;
 mod step_2_local_sync_inner;
 use step_2_local_sync_inner::*;
 mod step_3_initiate_inner;
@@ -115,16 +408,65 @@ use step_4_com_loop_inner::*;
 kitsune_p2p_types::write_codec_enum! {
     /// SimpleBloom Gossip Wire Protocol Codec
     codec GossipWire {
-        /// Initiate a round of gossip with a remote node
+        /// Initiate a round of gossip with a remote node,
+        /// sending our bloom filter, when it is a single chunk.
         Initiate(0x10) {
             filter.0: PoolBuf,
         },
 
-        /// Accept an incoming round of gossip from a remote node
+        /// Accept an incoming round of gossip from a remote node,
+        /// sending our bloom filter, when it is a single chunk.
         Accept(0x20) {
             filter.0: PoolBuf,
         },
 
+        /// Initiate a round of gossip with a remote node,
+        /// sending one bloom filter per dht location subrange.
+        /// Only sent when there is more than one, as peers
+        /// from before chunking can't decode it.
+        InitiateChunked(0x13) {
+            filters.0: Vec<PoolBuf>,
+        },
+
+        /// Accept an incoming round of gossip from a remote node,
+        /// sending one bloom filter per dht location subrange.
+        /// Only sent when there is more than one.
+        AcceptChunked(0x23) {
+            filters.0: Vec<PoolBuf>,
+        },
+
+        /// Initiate a round of gossip with a remote node, whose
+        /// filters are unchanged since the ones with this hash
+        /// were last sent to it.
+        InitiateUnchanged(0x11) {
+            bloom_hash.0: u64,
+        },
+
+        /// Accept an incoming round of gossip from a remote node, whose
+        /// filters are unchanged since the ones with this hash
+        /// were last sent to it.
+        AcceptUnchanged(0x21) {
+            bloom_hash.0: u64,
+        },
+
+        /// Initiate a round of gossip with a remote node, sending
+        /// the keys of all our ops, there being too few for a bloom.
+        InitiateExact(0x12) {
+            keys.0: Vec<Arc<MetaOpKey>>,
+        },
+
+        /// Accept an incoming round of gossip from a remote node, sending
+        /// the keys of all our ops, there being too few for a bloom.
+        AcceptExact(0x22) {
+            keys.0: Vec<Arc<MetaOpKey>>,
+        },
+
+        /// The filters with this hash are not the last ones
+        /// received from us, the next round must send them in full.
+        BloomUnknown(0x40) {
+            bloom_hash.0: u64,
+        },
+
         /// Send a chunks of gossip meta op data,
         /// if "finished" this will be the final chunk.
         Chunk(0x30) {
@@ -151,15 +493,58 @@ pub(crate) struct SimpleBloomModInner {
     evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
 
     local_agents: HashSet<Arc<KitsuneAgent>>,
-    local_bloom: BloomFilter,
+    local_bloom: KeyFilter,
+    /// the bloom from before the last local sync,
+    /// handed to the next local sync to rebuild in place
+    spare_bloom: Option<BloomChunks>,
     local_data_map: DataMap,
+    /// ops a fetch for `local_data_map` found missing since the last
+    /// local sync, so they aren't fetched again until the next one
+    known_missing_ops: KeySet,
     local_key_set: KeySet,
+    /// when the ops in `local_key_set` were received, where known
+    /// and the `gossip_op_order` goes by it
+    local_op_received_at: OpReceivedAt,
+    local_sync_has_remaining_work: bool,
+    /// signed agent infos last queried from the agent store,
+    /// and when they were queried
+    agent_info_cache: Option<(std::time::Instant, Vec<AgentInfoSigned>)>,
+    /// when we last logged that a local sync found nothing to sync
+    last_converged_heartbeat: std::time::Instant,
+    /// where the bloom snapshot for this space is saved, if anywhere
+    bloom_snapshot_path: Option<std::path::PathBuf>,
+    /// the snapshot loaded at startup, handed to the first local sync
+    bloom_snapshot: Option<BloomSnapshot>,
+    /// how many keys the last saved snapshot was taken from
+    bloom_snapshot_key_count: Option<usize>,
+    /// what each local agent held as of the last local sync,
+    /// so the next one only fetches what they integrated since
+    op_watermarks: OpWatermarks,
+    /// the storage arcs local agents have been set to
+    agent_arcs: AgentArcs,
+    /// when a local sync last fetched all of the local agents' op hashes
+    last_full_local_fetch: std::time::Instant,
+    /// how gossip looked as of the last local sync
+    status: actor::GossipStatus,
+    /// while set, no gossip happens at all
+    paused: bool,
+    /// gossip events go out to subscribers on this
+    events: tokio::sync::broadcast::Sender<actor::GossipEvent>,
 
     remote_metrics: HashMap<Tx2Cert, NodeInfo>,
 
     last_initiate_check: std::time::Instant,
     initiate_tgt: Option<Tx2Cert>,
 
+    /// the hash of the filters last sent to each peer,
+    /// so unchanged filters aren't sent again
+    sent_bloom_hashes: HashMap<Tx2Cert, u64>,
+    /// the filters last received from each peer, with when they were
+    /// received and their hash, for when the peer tells us they are
+    /// unchanged. exact keys have no hash, they are always sent in full.
+    /// see [expire_remote_blooms]
+    remote_blooms: HashMap<Tx2Cert, RemoteBlooms>,
+
     incoming: Vec<(Tx2ConHnd<wire::Wire>, GossipWire)>,
 
     last_outgoing: std::time::Instant,
@@ -184,6 +569,22 @@ impl SimpleBloomModInner {
             / tuning_params.gossip_output_target_mbps
         ) as u64;
 
+        let local_bloom = KeyFilter::Bloom(BloomChunks::empty(
+            tuning_params.gossip_bloom_chunk_count as usize,
+        ));
+
+        if !is_known_op_order(&tuning_params.gossip_op_order) {
+            tracing::warn!(
+                gossip_op_order = %tuning_params.gossip_op_order,
+                "unknown gossip op order, using agent-info-first",
+            );
+        }
+
+        let bloom_snapshot_path = bloom_snapshot_path(&tuning_params, &space);
+        let bloom_snapshot = bloom_snapshot_path
+            .as_ref()
+            .and_then(|path| BloomSnapshot::load(path, &tuning_params));
+
         // pick an old instant for initialization
         let old = std::time::Instant::now()
             .checked_sub(std::time::Duration::from_secs(60 * 60 * 24))
@@ -196,15 +597,33 @@ impl SimpleBloomModInner {
             evt_sender,
 
             local_agents: HashSet::new(),
-            local_bloom: bloomfilter::Bloom::new(1, 1),
+            local_bloom,
+            spare_bloom: None,
             local_data_map: HashMap::new(),
+            known_missing_ops: HashSet::new(),
             local_key_set: HashSet::new(),
+            local_op_received_at: HashMap::new(),
+            local_sync_has_remaining_work: false,
+            agent_info_cache: None,
+            last_converged_heartbeat: old,
+            bloom_snapshot_path,
+            bloom_snapshot,
+            bloom_snapshot_key_count: None,
+            op_watermarks: OpWatermarks::default(),
+            agent_arcs: HashMap::new(),
+            last_full_local_fetch: old,
+            status: actor::GossipStatus::default(),
+            paused: false,
+            events: tokio::sync::broadcast::channel(GOSSIP_EVENT_CAPACITY).0,
 
             remote_metrics: HashMap::new(),
 
             last_initiate_check: old,
             initiate_tgt: None,
 
+            sent_bloom_hashes: HashMap::new(),
+            remote_blooms: HashMap::new(),
+
             incoming: Vec::new(),
 
             last_outgoing: old,
@@ -311,6 +730,11 @@ impl SimpleBloomMod {
 
     fn step_1_check(&self) -> CheckResult {
         match self.0.share_mut(|i, _| {
+            // while paused, leave everything as it is for when we resume
+            if i.paused {
+                return Ok(CheckResult::NotReady);
+            }
+
             // first, if we don't have any local agents, there's
             // no point in doing any gossip logic
             if i.local_agents.is_empty() {
@@ -343,6 +767,12 @@ impl SimpleBloomMod {
                 }
             }
 
+            // if the last local sync ran out of budget, carry on
+            // with it rather than waiting out the iteration delay
+            if i.initiate_tgt.is_none() && i.local_sync_has_remaining_work {
+                return Ok(CheckResult::SyncAndInitiate);
+            }
+
             if i.initiate_tgt.is_none()
                 && i.last_initiate_check.elapsed().as_millis() as u32
                     > i.tuning_params.gossip_loop_iteration_delay_ms
@@ -357,30 +787,159 @@ impl SimpleBloomMod {
     }
 
     async fn step_2_local_sync(&self) -> KitsuneResult<bool> {
-        let (space, evt_sender, local_agents) = self.0.share_mut(|i, _| {
+        let (
+            space,
+            evt_sender,
+            tuning_params,
+            local_agents,
+            cached_agent_infos,
+            spare_bloom,
+            bloom_snapshot,
+            op_watermarks,
+            op_received_at,
+            agent_arcs,
+        ) = self.0.share_mut(|i, _| {
+            let ttl_ms = i.tuning_params.gossip_agent_info_cache_ttl_ms as u128;
+            let cached_agent_infos = i
+                .agent_info_cache
+                .as_ref()
+                .filter(|(queried_at, _)| queried_at.elapsed().as_millis() < ttl_ms)
+                .map(|(_, agent_infos)| agent_infos.clone());
+            let full_fetch_ms = i.tuning_params.gossip_local_sync_full_fetch_interval_ms as u128;
+            // without watermarks every agent's op hashes are fetched
+            if i.last_full_local_fetch.elapsed().as_millis() >= full_fetch_ms {
+                i.last_full_local_fetch = std::time::Instant::now();
+                i.op_watermarks.clear();
+            }
             Ok((
                 i.space.clone(),
                 i.evt_sender.clone(),
+                i.tuning_params.clone(),
                 i.local_agents.clone(),
+                cached_agent_infos,
+                i.spare_bloom.take(),
+                i.bloom_snapshot.take(),
+                std::mem::take(&mut i.op_watermarks),
+                std::mem::take(&mut i.local_op_received_at),
+                i.agent_arcs.clone(),
             ))
         })?;
 
-        let (data_map, key_set, bloom) =
-            match step_2_local_sync_inner(space, evt_sender, local_agents).await {
-                Err(e) => {
-                    tracing::warn!("gossip error: {:?}", e);
-                    return Ok(false);
+        let res = step_2_local_sync_inner(
+            space,
+            evt_sender,
+            tuning_params.clone(),
+            local_agents,
+            LocalSyncInput {
+                cached_agent_infos,
+                reuse_bloom: spare_bloom,
+                snapshot: bloom_snapshot,
+                op_watermarks,
+                op_received_at,
+                agent_arcs,
+                ..Default::default()
+            },
+        )
+        .await;
+        let LocalSyncResult {
+            data_map,
+            key_set,
+            bloom,
+            has_remaining_work,
+            diverged_op_count,
+            stats,
+            queried_agent_infos,
+            covered_until_utc_epoch_s: _,
+            op_watermarks,
+            op_received_at,
+            error,
+        } = res;
+        let converged = match error {
+            Some(e) => {
+                // keep what was synced so far, the rest is retried next round
+                tracing::warn!("gossip error, local sync incomplete: {:?}", e);
+                false
+            }
+            None => diverged_op_count == 0 && !has_remaining_work,
+        };
+        // a snapshot may only claim what every local agent was found to
+        // hold, so it needs every agent's ops fetched and handed around
+        let complete = converged
+            && stats.failed_agent_fetch_count == 0
+            && !tuning_params.gossip_skip_local_sync;
+        let key_set_size = key_set.len();
+        let status = actor::GossipStatus {
+            last_local_sync_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_millis() as u64),
+            local_agent_count: stats.agent_arcs.len(),
+            synced_ops: diverged_op_count,
+            blooms_match: stats.outstanding_op_count == 0,
+            outstanding_ops: stats.outstanding_op_count,
+            evt_channel_stall_ms: stats.evt_channel_stall.as_millis() as u64,
+            // whether we are paused is filled in as the status is read
+            ..Default::default()
+        };
+
+        let to_save = self.0.share_mut(move |i, _| {
+            tracing::debug!(space = ?i.space, ?stats, "local sync stats");
+            let heartbeat_ms = i.tuning_params.gossip_converged_heartbeat_interval_ms as u128;
+            if converged
+                && heartbeat_ms > 0
+                && i.last_converged_heartbeat.elapsed().as_millis() >= heartbeat_ms
+            {
+                i.last_converged_heartbeat = std::time::Instant::now();
+                tracing::info!(
+                    space = ?i.space,
+                    local_agent_count = %i.local_agents.len(),
+                    %key_set_size,
+                    "gossip converged, local sync found nothing to sync",
+                );
+            }
+            // only snapshot a complete sync, and only when the keys changed
+            let to_save = match &i.bloom_snapshot_path {
+                Some(path) if complete && i.bloom_snapshot_key_count != Some(key_set_size) => {
+                    i.bloom_snapshot_key_count = Some(key_set_size);
+                    Some((
+                        path.clone(),
+                        BloomSnapshot::new(&i.tuning_params, &op_watermarks),
+                    ))
                 }
-                Ok(r) => r,
+                _ => None,
             };
-
-        self.0.share_mut(move |i, _| {
             i.local_data_map = data_map;
+            i.known_missing_ops.clear();
             i.local_key_set = key_set;
-            i.local_bloom = bloom;
-            Ok(())
+            i.spare_bloom = std::mem::replace(&mut i.local_bloom, bloom).into_bloom();
+            i.local_sync_has_remaining_work = has_remaining_work;
+            i.op_watermarks = op_watermarks;
+            i.local_op_received_at = op_received_at;
+            // only build the event if someone is listening
+            if i.events.receiver_count() > 0 {
+                let _ = i.events.send(actor::GossipEvent::LocalSyncFinished {
+                    status: actor::GossipStatus {
+                        paused: i.paused,
+                        ..status.clone()
+                    },
+                    stats,
+                });
+            }
+            i.status = status;
+            if let Some(agent_infos) = queried_agent_infos {
+                i.agent_info_cache = Some((std::time::Instant::now(), agent_infos));
+            }
+            Ok(to_save)
         })?;
 
+        if let Some((path, snapshot)) = to_save {
+            match tokio::task::spawn_blocking(move || snapshot.save(&path)).await {
+                Ok(Ok(())) => (),
+                Ok(Err(e)) => tracing::warn!(?e, "failed to save gossip bloom snapshot"),
+                Err(e) => tracing::warn!(?e, "gossip bloom snapshot task failed"),
+            }
+        }
+
         Ok(true)
     }
 
@@ -450,6 +1009,8 @@ impl SimpleBloomMod {
                     tracing::warn!("failed to send outgoing: {:?} {:?}", cert, e);
                     self.0.share_mut(move |i, _| {
                         i.last_outgoing = std::time::Instant::now();
+                        // the filters we last sent may not have arrived
+                        i.sent_bloom_hashes.remove(&cert);
                         i.remote_metrics.insert(
                             cert,
                             NodeInfo {
@@ -499,6 +1060,10 @@ impl AsGossipModule for SimpleBloomMod {
         use kitsune_p2p_types::codec::*;
         let (_, gossip) = GossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
         self.0.share_mut(move |i, _| {
+            // it would only pile up until we resume
+            if i.paused {
+                return Ok(());
+            }
             i.incoming.push((con, gossip));
             if i.incoming.len() > 20 {
                 tracing::warn!(
@@ -513,6 +1078,7 @@ impl AsGossipModule for SimpleBloomMod {
     fn local_agent_join(&self, a: Arc<KitsuneAgent>) {
         let _ = self.0.share_mut(move |i, _| {
             i.local_agents.insert(a);
+            i.agent_info_cache = None;
             Ok(())
         });
     }
@@ -520,6 +1086,47 @@ impl AsGossipModule for SimpleBloomMod {
     fn local_agent_leave(&self, a: Arc<KitsuneAgent>) {
         let _ = self.0.share_mut(move |i, _| {
             i.local_agents.remove(&a);
+            i.agent_arcs.remove(&a);
+            i.agent_info_cache = None;
+            Ok(())
+        });
+    }
+
+    fn status(&self) -> actor::GossipStatus {
+        self.0
+            .share_mut(|i, _| {
+                Ok(actor::GossipStatus {
+                    paused: i.paused,
+                    ..i.status.clone()
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<actor::GossipEvent> {
+        match self.0.share_mut(|i, _| Ok(i.events.subscribe())) {
+            Ok(events) => events,
+            // closed, so there will be no more events
+            Err(_) => tokio::sync::broadcast::channel(1).1,
+        }
+    }
+
+    fn set_paused(&self, paused: bool) {
+        let _ = self.0.share_mut(move |i, _| {
+            if i.paused != paused {
+                tracing::info!(space = ?i.space, %paused, "gossip paused state changed");
+            }
+            i.paused = paused;
+            Ok(())
+        });
+    }
+
+    fn set_agent_arc(&self, a: Arc<KitsuneAgent>, arc: dht_arc::DhtArc) {
+        let _ = self.0.share_mut(move |i, _| {
+            // the watermark only covers the old arc,
+            // so the next local sync fetches this agent's ops again
+            i.op_watermarks.remove(&a);
+            i.agent_arcs.insert(a, arc);
             Ok(())
         });
     }
@@ -547,3 +1154,247 @@ impl AsGossipModuleFactory for SimpleBloomModFact {
 pub fn factory() -> GossipModuleFactory {
     GossipModuleFactory(Arc::new(SimpleBloomModFact))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_blooms_are_sent_as_their_hash() {
+        let cert = |n: u8| Tx2Cert::from(vec![n; 32]);
+        let filters = |bytes: &[&[u8]]| -> Vec<PoolBuf> {
+            bytes
+                .iter()
+                .map(|b| {
+                    let mut buf = PoolBuf::new();
+                    buf.extend_from_slice(b);
+                    buf
+                })
+                .collect()
+        };
+        let a = filters(&[&[1, 2], &[3]]);
+        // same bytes, split differently across chunks
+        let b = filters(&[&[1], &[2, 3]]);
+        assert_ne!(bloom_hash(&a), bloom_hash(&b));
+
+        let mut sent = HashMap::new();
+        assert_eq!(unchanged_bloom_hash(&mut sent, &cert(1), &a), None);
+        assert_eq!(
+            unchanged_bloom_hash(&mut sent, &cert(1), &a),
+            Some(bloom_hash(&a))
+        );
+        // each peer is tracked separately
+        assert_eq!(unchanged_bloom_hash(&mut sent, &cert(2), &a), None);
+        // changed filters are sent in full, then become the ones to match
+        assert_eq!(unchanged_bloom_hash(&mut sent, &cert(1), &b), None);
+        assert_eq!(
+            unchanged_bloom_hash(&mut sent, &cert(1), &b),
+            Some(bloom_hash(&b))
+        );
+    }
+
+    #[test]
+    fn single_chunk_filters_keep_the_unchunked_wire_form() {
+        use kitsune_p2p_types::codec::*;
+
+        let filter = |b: u8| {
+            let mut buf = PoolBuf::new();
+            buf.extend_from_slice(&[b; 4]);
+            buf
+        };
+        let roundtrip = |gossip: GossipWire| {
+            let data = gossip.encode_vec().unwrap();
+            GossipWire::decode_ref(&data).unwrap().1
+        };
+
+        // what peers from before chunking send and expect
+        assert!(matches!(
+            roundtrip(initiate_with_filters(vec![filter(1)])),
+            GossipWire::Initiate(Initiate { filter: f }) if f[..] == filter(1)[..]
+        ));
+        assert!(matches!(
+            roundtrip(accept_with_filters(vec![filter(1)])),
+            GossipWire::Accept(Accept { filter: f }) if f[..] == filter(1)[..]
+        ));
+        assert!(matches!(
+            roundtrip(initiate_with_filters(vec![filter(1), filter(2)])),
+            GossipWire::InitiateChunked(InitiateChunked { filters }) if filters.len() == 2
+        ));
+        assert!(matches!(
+            roundtrip(accept_with_filters(vec![filter(1), filter(2)])),
+            GossipWire::AcceptChunked(AcceptChunked { filters }) if filters.len() == 2
+        ));
+    }
+
+    #[test]
+    fn remote_blooms_expire() {
+        let cert = |n: u8| Tx2Cert::from(vec![n; 32]);
+        let ttl = std::time::Duration::from_secs(60);
+        let now = std::time::Instant::now();
+        let mut remote_blooms: HashMap<Tx2Cert, RemoteBlooms> = HashMap::new();
+        remote_blooms.insert(cert(1), (now, Some(1), None));
+        if let Some(old) = now.checked_sub(ttl * 2) {
+            remote_blooms.insert(cert(2), (old, Some(2), None));
+        }
+
+        expire_remote_blooms(&mut remote_blooms, ttl);
+        assert_eq!(
+            remote_blooms.keys().cloned().collect::<Vec<_>>(),
+            vec![cert(1)]
+        );
+        expire_remote_blooms(&mut remote_blooms, std::time::Duration::default());
+        assert!(remote_blooms.is_empty());
+    }
+
+    #[test]
+    fn blooms_never_exceed_the_max_chunk_size() {
+        const KEY_COUNT: u32 = 1000;
+        const MAX_CHUNK_BYTES: usize = 300;
+
+        let key_set: KeySet = (0..KEY_COUNT)
+            .map(|i| {
+                // spread the keys over the dht locations
+                let mut bytes = vec![0; 32];
+                bytes.extend_from_slice(&i.wrapping_mul(2654435761).to_le_bytes());
+                Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(bytes))))
+            })
+            .collect();
+        let total_bytes = BloomFilter::compute_bitmap_size(KEY_COUNT as usize, TGT_FP);
+        assert!(total_bytes > MAX_CHUNK_BYTES);
+
+        // no limit, the configured chunk count is used
+        assert_eq!(BloomChunks::build(&key_set, 1, 0).0.len(), 1);
+
+        let bloom = BloomChunks::build(&key_set, 1, MAX_CHUNK_BYTES);
+        assert!(bloom.0.len() >= (total_bytes + MAX_CHUNK_BYTES - 1) / MAX_CHUNK_BYTES);
+        assert!(bloom.byte_count() <= (bloom.0.len() * MAX_CHUNK_BYTES) as u64);
+        assert!(key_set.iter().all(|key| bloom.check(key)));
+
+        // a chunk that is still too large is capped
+        let capped = BloomChunks::build(&key_set, 1, 1);
+        for chunk in capped.0.iter() {
+            assert!(chunk.number_of_bits() <= 8);
+        }
+        assert!(key_set.iter().all(|key| capped.check(key)));
+    }
+
+    #[test]
+    fn blooms_meet_the_target_false_positive_rate() {
+        const KEY_COUNT: usize = 10_000;
+        const QUERY_COUNT: usize = 100_000;
+        // the observed rate is itself random, leave room for the noise
+        // (~3% of the target at this query count) and a little more
+        const TOLERANCE: f64 = 1.5;
+
+        use rand::Rng;
+        let random_key = || {
+            let mut bytes = vec![0; 36];
+            rand::thread_rng().fill(&mut bytes[..]);
+            Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(bytes))))
+        };
+        let random_keys = |count: usize| -> KeySet { (0..count).map(|_| random_key()).collect() };
+        let fp_rate = |bloom: &BloomChunks, key_set: &KeySet| {
+            let false_positives = (0..QUERY_COUNT)
+                .map(|_| random_key())
+                .filter(|key| !key_set.contains(key) && bloom.check(key))
+                .count();
+            false_positives as f64 / QUERY_COUNT as f64
+        };
+
+        for chunk_count in &[1, 4, 16] {
+            let key_set = random_keys(KEY_COUNT);
+            let mut bloom = BloomChunks::build(&key_set, *chunk_count, 0);
+            assert!(key_set.iter().all(|key| bloom.check(key)));
+            let observed = fp_rate(&bloom, &key_set);
+            assert!(
+                observed <= TGT_FP * TOLERANCE,
+                "{} chunks: observed false positive rate {} over target {}",
+                chunk_count,
+                observed,
+                TGT_FP,
+            );
+
+            // a rebuild reusing the filters for slightly fewer keys
+            // keeps the rate
+            let key_set = random_keys(KEY_COUNT * 9 / 10);
+            bloom.rebuild(&key_set, *chunk_count, 0);
+            assert!(key_set.iter().all(|key| bloom.check(key)));
+            let observed = fp_rate(&bloom, &key_set);
+            assert!(
+                observed <= TGT_FP * TOLERANCE,
+                "{} chunks, rebuilt: observed false positive rate {} over target {}",
+                chunk_count,
+                observed,
+                TGT_FP,
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_stalled_space_doesnt_hold_up_gossip_in_another() {
+        use crate::event::KitsuneP2pEvent;
+        use futures::FutureExt;
+        use futures::StreamExt;
+        use kitsune_p2p_types::tx2::tx2_pool_promote::*;
+        use kitsune_p2p_types::tx2::*;
+
+        let t = KitsuneTimeout::from_millis(5000);
+        let f = tx2_mem_adapter(MemConfig::default()).await.unwrap();
+        let f = tx2_pool_promote(f, Default::default());
+        let f = tx2_api::<wire::Wire>(f, Default::default());
+        let ep = f.bind("none:", t).await.unwrap();
+
+        // as in spawn_space, each space's gossip gets its own event channel,
+        // and the stalled space's events aren't answered until released
+        let (release, released) = tokio::sync::watch::channel(false);
+        let spawn_space = |space: u8, stalled: bool| {
+            let (evt_sender, mut evt_receiver) = futures::channel::mpsc::channel(10);
+            let mut released = released.clone();
+            tokio::task::spawn(async move {
+                while let Some(evt) = evt_receiver.next().await {
+                    while stalled && !*released.borrow() {
+                        released.changed().await.unwrap();
+                    }
+                    match evt {
+                        KitsuneP2pEvent::QueryAgentInfoSigned { respond, .. } => {
+                            respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                        }
+                        KitsuneP2pEvent::FetchOpHashesForConstraints { respond, .. } => {
+                            respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                        }
+                        KitsuneP2pEvent::FetchOpHashData { respond, .. } => {
+                            respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                        }
+                        _ => (),
+                    }
+                }
+            });
+            let gossip = SimpleBloomMod::new(
+                Default::default(),
+                Arc::new(KitsuneSpace::new(vec![space; 36])),
+                ep.handle().clone(),
+                evt_sender,
+            );
+            let events = gossip.subscribe_events();
+            gossip.local_agent_join(Arc::new(KitsuneAgent::new(vec![0; 36])));
+            (gossip, events)
+        };
+        let (_stalled, mut stalled_events) = spawn_space(1, true);
+        let (_running, mut running_events) = spawn_space(2, false);
+
+        let timeout = std::time::Duration::from_secs(5);
+        tokio::time::timeout(timeout, running_events.recv())
+            .await
+            .expect("the running space was held up by the stalled one")
+            .unwrap();
+        assert!(stalled_events.try_recv().is_err());
+
+        release.send(true).unwrap();
+        tokio::time::timeout(timeout, stalled_events.recv())
+            .await
+            .expect("the stalled space didn't carry on once released")
+            .unwrap();
+
+        ep.handle().close(0, "").await;
+    }
+}
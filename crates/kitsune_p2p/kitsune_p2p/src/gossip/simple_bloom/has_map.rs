@@ -0,0 +1,233 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// Which local agents hold which keys.
+/// Every key is stored once, however many local agents hold it, and each
+/// agent only keeps a bit per key. On a conductor running many agents
+/// over the same dna this is far smaller than a key set per agent.
+/// Agents are always iterated in order of their key bytes, so a local
+/// sync over the same map sends its gossip in the same order every time.
+#[derive(Clone, Default)]
+pub(crate) struct HasMap {
+    /// every key held by any of the agents, by index
+    keys: Vec<Arc<MetaOpKey>>,
+    /// the index of each key in `keys`
+    index: HashMap<Arc<MetaOpKey>, usize>,
+    /// which keys each agent holds
+    has: BTreeMap<Arc<KitsuneAgent>, KeyBits>,
+}
+
+/// One bit per key of a [HasMap], set if the agent holds that key
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct KeyBits(Vec<u64>);
+
+impl KeyBits {
+    /// is the key at `index` held
+    pub fn contains(&self, index: usize) -> bool {
+        self.0
+            .get(index / 64)
+            .map(|word| word & (1 << (index % 64)) != 0)
+            .unwrap_or(false)
+    }
+
+    /// mark the key at `index` as held
+    pub fn insert(&mut self, index: usize) {
+        let word = index / 64;
+        if self.0.len() <= word {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << (index % 64);
+    }
+
+    /// how many keys are held
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// the indexes of the held keys, in order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(word_index, word)| {
+            let word = *word;
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| word_index * 64 + bit)
+        })
+    }
+}
+
+impl HasMap {
+    /// make `agent` part of the map, holding nothing if it isn't yet
+    pub fn add_agent(&mut self, agent: Arc<KitsuneAgent>) {
+        self.has.entry(agent).or_default();
+    }
+
+    /// take `agent` out of the map, keys only it held stay in the map
+    pub fn remove_agent(&mut self, agent: &Arc<KitsuneAgent>) {
+        self.has.remove(agent);
+    }
+
+    /// record that `agent` holds `key`
+    pub fn insert(&mut self, agent: &Arc<KitsuneAgent>, key: Arc<MetaOpKey>) {
+        let index = self.intern(key);
+        match self.has.get_mut(agent) {
+            Some(bits) => bits.insert(index),
+            None => {
+                let mut bits = KeyBits::default();
+                bits.insert(index);
+                self.has.insert(agent.clone(), bits);
+            }
+        }
+    }
+
+    /// record that every agent in the map holds `key`
+    pub fn insert_for_all(&mut self, key: Arc<MetaOpKey>) {
+        if self.has.is_empty() {
+            return;
+        }
+        let index = self.intern(key);
+        for bits in self.has.values_mut() {
+            bits.insert(index);
+        }
+    }
+
+    /// the key at `index`
+    pub fn key(&self, index: usize) -> &Arc<MetaOpKey> {
+        &self.keys[index]
+    }
+
+    /// every key held by any of the agents
+    pub fn keys(&self) -> impl Iterator<Item = &Arc<MetaOpKey>> {
+        self.keys.iter()
+    }
+
+    /// how many distinct keys the agents hold between them
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// how many agents are in the map
+    pub fn agent_count(&self) -> usize {
+        self.has.len()
+    }
+
+    /// each agent with the keys it holds, ordered by agent
+    pub fn agents(&self) -> impl Iterator<Item = (&Arc<KitsuneAgent>, &KeyBits)> {
+        self.has.iter()
+    }
+
+    /// the keys `agent` holds
+    pub fn keys_of<'a>(
+        &'a self,
+        agent: &Arc<KitsuneAgent>,
+    ) -> impl Iterator<Item = &'a Arc<MetaOpKey>> + 'a {
+        self.has
+            .get(agent)
+            .into_iter()
+            .flat_map(move |bits| bits.iter().map(move |index| &self.keys[index]))
+    }
+
+    /// the agents holding `key`
+    pub fn holders<'a>(
+        &'a self,
+        key: &Arc<MetaOpKey>,
+    ) -> impl Iterator<Item = &'a Arc<KitsuneAgent>> + 'a {
+        let index = self.index.get(key).copied();
+        self.has
+            .iter()
+            .filter(move |(_, bits)| index.map(|index| bits.contains(index)).unwrap_or(false))
+            .map(|(agent, _)| agent)
+    }
+
+    /// which keys each agent holds, see [HasMap::set_agent_bits]
+    pub fn agent_bits(&self) -> &BTreeMap<Arc<KitsuneAgent>, KeyBits> {
+        &self.has
+    }
+
+    /// replace which keys each agent holds.
+    /// `has` must be indexed by this map's keys, i.e. come from
+    /// [HasMap::agent_bits] and only have had bits of existing keys set since
+    pub fn set_agent_bits(&mut self, has: BTreeMap<Arc<KitsuneAgent>, KeyBits>) {
+        self.has = has;
+    }
+
+    fn intern(&mut self, key: Arc<MetaOpKey>) -> usize {
+        if let Some(index) = self.index.get(&key) {
+            return *index;
+        }
+        let index = self.keys.len();
+        self.keys.push(key.clone());
+        self.index.insert(key, index);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(i: u8) -> Arc<KitsuneAgent> {
+        Arc::new(KitsuneAgent::new(vec![i; 36]))
+    }
+
+    fn key(i: u8) -> Arc<MetaOpKey> {
+        Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))))
+    }
+
+    #[test]
+    fn key_bits_track_held_indexes() {
+        let mut bits = KeyBits::default();
+        assert!(!bits.contains(0));
+        for index in &[3, 64, 200, 3] {
+            bits.insert(*index);
+        }
+        assert!(bits.contains(3) && bits.contains(64) && bits.contains(200));
+        assert!(!bits.contains(4) && !bits.contains(1000));
+        assert_eq!(bits.count(), 3);
+        assert_eq!(bits.iter().collect::<Vec<_>>(), vec![3, 64, 200]);
+    }
+
+    #[test]
+    fn keys_held_by_many_agents_are_stored_once() {
+        let mut has_hash = HasMap::default();
+        // each agent's fetch hands back its own copy of the same key
+        for i in 0..3 {
+            has_hash.insert(&agent(i), key(1));
+        }
+        has_hash.insert(&agent(0), key(2));
+        has_hash.add_agent(agent(3));
+        has_hash.insert_for_all(key(3));
+
+        assert_eq!(has_hash.agent_count(), 4);
+        assert_eq!(has_hash.key_count(), 3);
+        let shared: Vec<_> = (0..3)
+            .map(|i| has_hash.keys_of(&agent(i)).next().unwrap().clone())
+            .collect();
+        assert!(shared.iter().all(|k| Arc::ptr_eq(k, &shared[0])));
+
+        let of_0: HashSet<_> = has_hash.keys_of(&agent(0)).cloned().collect();
+        assert_eq!(of_0, vec![key(1), key(2), key(3)].into_iter().collect());
+        let of_3: Vec<_> = has_hash.keys_of(&agent(3)).cloned().collect();
+        assert_eq!(of_3, vec![key(3)]);
+        assert_eq!(has_hash.keys_of(&agent(9)).count(), 0);
+
+        let holders: HashSet<_> = has_hash.holders(&key(1)).cloned().collect();
+        assert_eq!(holders, (0..3).map(agent).collect());
+        assert_eq!(has_hash.holders(&key(9)).count(), 0);
+    }
+
+    #[test]
+    fn agents_are_ordered_by_key() {
+        let mut has_hash = HasMap::default();
+        for i in &[7, 2, 9, 0, 4] {
+            has_hash.insert(&agent(*i), key(*i));
+        }
+        has_hash.insert_for_all(key(1));
+        let agents: Vec<_> = has_hash.agents().map(|(a, _)| a.clone()).collect();
+        assert_eq!(
+            agents,
+            vec![agent(0), agent(2), agent(4), agent(7), agent(9)]
+        );
+        let holders: Vec<_> = has_hash.holders(&key(1)).cloned().collect();
+        assert_eq!(holders, agents);
+    }
+}
@@ -0,0 +1,210 @@
+use super::*;
+
+/// The order a gossip round hands out the ops another agent is missing,
+/// so that if the round is cut short the most useful ones went first.
+/// Lower priorities go first, ops of equal priority keep the order
+/// they were found in.
+/// `received_at` is when the op was received in utc epoch milliseconds,
+/// if our implementor told us.
+pub(crate) trait OpOrder: 'static + Send + Sync {
+    /// priority of an op known only by its key, before its data is fetched
+    fn key_priority(&self, key: &MetaOpKey, received_at: Option<i64>) -> u64;
+
+    /// priority of an op whose data is at hand
+    fn data_priority(&self, data: &MetaOpData, received_at: Option<i64>) -> u64;
+
+    /// does this order look at when ops were received,
+    /// if not there is no need to keep track
+    fn uses_received_at(&self) -> bool {
+        false
+    }
+}
+
+/// Agent infos go before everything else, so a newly joined agent can
+/// discover its peers as early as possible. Ops whose size is known go
+/// smallest first.
+pub(crate) struct AgentInfoFirst;
+
+impl OpOrder for AgentInfoFirst {
+    fn key_priority(&self, key: &MetaOpKey, _received_at: Option<i64>) -> u64 {
+        match key {
+            MetaOpKey::Agent(_, _) => 0,
+            MetaOpKey::Op(_) => 1,
+        }
+    }
+
+    fn data_priority(&self, data: &MetaOpData, _received_at: Option<i64>) -> u64 {
+        match data {
+            MetaOpData::Agent(_) => 0,
+            MetaOpData::Op(_, _) => 1 + data.byte_count() as u64,
+        }
+    }
+}
+
+/// Ops go in whatever order they were found in.
+pub(crate) struct Unordered;
+
+impl OpOrder for Unordered {
+    fn key_priority(&self, _key: &MetaOpKey, _received_at: Option<i64>) -> u64 {
+        0
+    }
+
+    fn data_priority(&self, _data: &MetaOpData, _received_at: Option<i64>) -> u64 {
+        0
+    }
+}
+
+/// Ops go most recently received first, so a peer catching up gets
+/// recent activity before old history. Agent infos go by when they
+/// were signed. Ops we don't know the time of go last.
+pub(crate) struct NewestFirst;
+
+impl OpOrder for NewestFirst {
+    fn key_priority(&self, key: &MetaOpKey, received_at: Option<i64>) -> u64 {
+        newest_first(key_time_ms(key, received_at))
+    }
+
+    fn data_priority(&self, data: &MetaOpData, received_at: Option<i64>) -> u64 {
+        newest_first(data_time_ms(data, received_at))
+    }
+
+    fn uses_received_at(&self) -> bool {
+        true
+    }
+}
+
+/// Ops go least recently received first, as for [`NewestFirst`]
+/// but the other way round. Ops we don't know the time of still go last.
+pub(crate) struct OldestFirst;
+
+impl OpOrder for OldestFirst {
+    fn key_priority(&self, key: &MetaOpKey, received_at: Option<i64>) -> u64 {
+        oldest_first(key_time_ms(key, received_at))
+    }
+
+    fn data_priority(&self, data: &MetaOpData, received_at: Option<i64>) -> u64 {
+        oldest_first(data_time_ms(data, received_at))
+    }
+
+    fn uses_received_at(&self) -> bool {
+        true
+    }
+}
+
+/// when an op was received, or an agent info signed, if known
+fn key_time_ms(key: &MetaOpKey, received_at: Option<i64>) -> Option<u64> {
+    match key {
+        MetaOpKey::Agent(_, signed_at_ms) => Some(*signed_at_ms),
+        MetaOpKey::Op(_) => received_at.map(|t| t.max(0) as u64),
+    }
+}
+
+/// when an op was received, or an agent info signed, if known
+fn data_time_ms(data: &MetaOpData, received_at: Option<i64>) -> Option<u64> {
+    match data {
+        MetaOpData::Agent(agent_info) => Some(agent_info.signed_at_ms()),
+        MetaOpData::Op(_, _) => received_at.map(|t| t.max(0) as u64),
+    }
+}
+
+fn newest_first(time_ms: Option<u64>) -> u64 {
+    match time_ms {
+        Some(t) => (u64::MAX - 1).saturating_sub(t),
+        None => u64::MAX,
+    }
+}
+
+fn oldest_first(time_ms: Option<u64>) -> u64 {
+    match time_ms {
+        Some(t) => std::cmp::min(t, u64::MAX - 1),
+        None => u64::MAX,
+    }
+}
+
+/// is `name` a `gossip_op_order` we know
+pub(crate) fn is_known_op_order(name: &str) -> bool {
+    matches!(
+        name,
+        "agent-info-first" | "unordered" | "newest-first" | "oldest-first"
+    )
+}
+
+/// the [`OpOrder`] named by the `gossip_op_order` tuning param,
+/// falling back to [`AgentInfoFirst`] for names we don't know
+pub(crate) fn op_order(tuning_params: &KitsuneP2pTuningParams) -> &'static dyn OpOrder {
+    match tuning_params.gossip_op_order.as_str() {
+        "unordered" => &Unordered,
+        "newest-first" => &NewestFirst,
+        "oldest-first" => &OldestFirst,
+        _ => &AgentInfoFirst,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
+
+    #[test]
+    fn agent_info_first_orders_agents_then_small_ops() {
+        let op = |n: u8, len: usize| {
+            Arc::new(MetaOpData::Op(
+                Arc::new(KitsuneOpHash::new(vec![n; 36])),
+                vec![0; len],
+            ))
+        };
+        let agent = Arc::new(MetaOpData::Agent(fixt!(AgentInfoSigned)));
+
+        let mut data = vec![op(1, 100), op(2, 10), agent.clone(), op(3, 10)];
+        data.sort_by_key(|d| AgentInfoFirst.data_priority(d, None));
+        assert_eq!(data, vec![agent.clone(), op(2, 10), op(3, 10), op(1, 100)]);
+
+        let mut data = vec![op(1, 100), op(2, 10), agent.clone()];
+        data.sort_by_key(|d| Unordered.data_priority(d, None));
+        assert_eq!(data, vec![op(1, 100), op(2, 10), agent]);
+
+        let mut keys = vec![
+            Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![1; 36])))),
+            Arc::new(MetaOpKey::Agent(
+                Arc::new(KitsuneAgent::new(vec![2; 36])),
+                0,
+            )),
+        ];
+        keys.sort_by_key(|k| AgentInfoFirst.key_priority(k, None));
+        assert!(matches!(&*keys[0], MetaOpKey::Agent(_, _)));
+    }
+
+    #[test]
+    fn time_orders_go_by_when_ops_were_received() {
+        let op = |n: u8| MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![n; 36])));
+        let received_at =
+            |keys: &[(MetaOpKey, Option<i64>)]| keys.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+
+        let mut keys = vec![
+            (op(1), Some(2000)),
+            (op(2), None),
+            (op(3), Some(3000)),
+            (op(4), Some(1000)),
+        ];
+        keys.sort_by_key(|(k, t)| NewestFirst.key_priority(k, *t));
+        assert_eq!(
+            received_at(&keys),
+            vec![Some(3000), Some(2000), Some(1000), None]
+        );
+        keys.sort_by_key(|(k, t)| OldestFirst.key_priority(k, *t));
+        assert_eq!(
+            received_at(&keys),
+            vec![Some(1000), Some(2000), Some(3000), None]
+        );
+
+        // agent infos go by when they were signed
+        let agent = MetaOpKey::Agent(Arc::new(KitsuneAgent::new(vec![5; 36])), 2500);
+        assert!(
+            NewestFirst.key_priority(&agent, None) < NewestFirst.key_priority(&op(1), Some(2000))
+        );
+        assert!(
+            OldestFirst.key_priority(&agent, None) > OldestFirst.key_priority(&op(1), Some(2000))
+        );
+    }
+}
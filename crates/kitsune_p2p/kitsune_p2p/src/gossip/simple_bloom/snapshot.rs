@@ -0,0 +1,194 @@
+use super::*;
+use kitsune_p2p_types::dependencies::serde_json;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The op keys each local agent held as of a complete local sync,
+/// persisted so that after a restart the first local sync only has to
+/// fetch the op hashes each of them integrated since, instead of every
+/// op hash they hold. An agent is only ever resumed from its own keys,
+/// never from what the others held.
+/// As in a [HasMap], a key held by many agents is stored once.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct BloomSnapshot {
+    /// the false positive rate the bloom was built for
+    pub fp_rate: f64,
+    /// the `gossip_bloom_chunk_count` the bloom was built with
+    pub chunk_count: u32,
+    /// ops each agent integrated before this time are all in its op keys
+    pub covered_until_utc_epoch_s: i64,
+    /// the op keys held by any of the agents, agent info keys
+    /// are left out as they are always collected fresh
+    pub op_keys: Vec<Arc<MetaOpKey>>,
+    /// each local agent, with which of `op_keys` it held
+    pub agents: Vec<(Arc<KitsuneAgent>, KeyBits)>,
+}
+
+impl BloomSnapshot {
+    /// what each agent with a watermark held as of it,
+    /// covering up to the earliest of the watermarks
+    pub fn new(tuning_params: &KitsuneP2pTuningParams, op_watermarks: &OpWatermarks) -> Self {
+        let mut has_map = HasMap::default();
+        for agent in op_watermarks.agents() {
+            has_map.add_agent(agent.clone());
+            for key in op_watermarks
+                .op_keys(agent)
+                .filter(|key| matches!(&***key, MetaOpKey::Op(_)))
+            {
+                has_map.insert(agent, key.clone());
+            }
+        }
+        let covered_until_utc_epoch_s = op_watermarks
+            .agents()
+            .filter_map(|agent| op_watermarks.covered_until_utc_epoch_s(agent))
+            .min()
+            .unwrap_or(0);
+        Self {
+            fp_rate: TGT_FP,
+            chunk_count: tuning_params.gossip_bloom_chunk_count,
+            covered_until_utc_epoch_s,
+            op_keys: has_map.keys().cloned().collect(),
+            agents: has_map
+                .agent_bits()
+                .iter()
+                .map(|(agent, bits)| (agent.clone(), bits.clone()))
+                .collect(),
+        }
+    }
+
+    /// the op keys `agent` held, `None` if it isn't in the snapshot
+    pub fn op_keys_of<'a>(
+        &'a self,
+        agent: &Arc<KitsuneAgent>,
+    ) -> Option<impl Iterator<Item = &'a Arc<MetaOpKey>> + 'a> {
+        let (_, bits) = self.agents.iter().find(|(a, _)| a == agent)?;
+        // a damaged snapshot may point past the keys, those are skipped
+        Some(bits.iter().filter_map(move |index| self.op_keys.get(index)))
+    }
+
+    /// load the snapshot at `path`, `None` if there is none or if it
+    /// was built for a different bloom configuration than the current one
+    pub fn load(path: &Path, tuning_params: &KitsuneP2pTuningParams) -> Option<Self> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                tracing::warn!(?path, ?e, "failed to read gossip bloom snapshot");
+                return None;
+            }
+        };
+        let snapshot: Self = match serde_json::from_slice(&bytes) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                tracing::warn!(?path, ?e, "failed to decode gossip bloom snapshot");
+                return None;
+            }
+        };
+        if (snapshot.fp_rate - TGT_FP).abs() > f64::EPSILON
+            || snapshot.chunk_count != tuning_params.gossip_bloom_chunk_count
+        {
+            tracing::warn!(
+                ?path,
+                snapshot_fp_rate = %snapshot.fp_rate,
+                snapshot_chunk_count = %snapshot.chunk_count,
+                fp_rate = %TGT_FP,
+                chunk_count = %tuning_params.gossip_bloom_chunk_count,
+                "gossip bloom snapshot does not match the bloom config, ignoring it",
+            );
+            return None;
+        }
+        Some(snapshot)
+    }
+
+    /// write the snapshot to `path`, replacing any previous one
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        // write then rename, so a crash never leaves a partial snapshot
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+/// where the bloom snapshot for `space` lives,
+/// `None` if `gossip_bloom_snapshot_dir` is not set
+pub(crate) fn bloom_snapshot_path(
+    tuning_params: &KitsuneP2pTuningParams,
+    space: &KitsuneSpace,
+) -> Option<PathBuf> {
+    if tuning_params.gossip_bloom_snapshot_dir.is_empty() {
+        return None;
+    }
+    let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
+    Some(
+        PathBuf::from(&tuning_params.gossip_bloom_snapshot_dir)
+            .join(format!("{}.bloom.json", space_b64)),
+    )
+}
+
+/// the current time in seconds since the unix epoch
+pub(crate) fn now_utc_epoch_s() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_roundtrip_and_config_check() {
+        let dir =
+            std::env::temp_dir().join(format!("kitsune-bloom-snapshot-{}", now_utc_epoch_s()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_bloom_snapshot_dir = dir.to_string_lossy().to_string();
+        let mut tuning_params = Arc::new(tuning_params);
+        let space = KitsuneSpace::new(vec![0; 36]);
+        let path = bloom_snapshot_path(&tuning_params, &space).unwrap();
+
+        assert_eq!(BloomSnapshot::load(&path, &tuning_params), None);
+
+        let agent = |i: u8| Arc::new(KitsuneAgent::new(vec![i; 36]));
+        let op_key = |i: u8| Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+        let mut op_watermarks = OpWatermarks::default();
+        op_watermarks.insert(
+            agent(1),
+            100,
+            vec![op_key(2), Arc::new(MetaOpKey::Agent(agent(1), 42))],
+        );
+        op_watermarks.insert(agent(3), 90, vec![op_key(2), op_key(4)]);
+        let snapshot = BloomSnapshot::new(&tuning_params, &op_watermarks);
+        let op_keys_of = |i: u8| {
+            snapshot
+                .op_keys_of(&agent(i))
+                .map(|keys| keys.cloned().collect::<Vec<_>>())
+        };
+        // each agent keeps its own keys, and agent info keys are not persisted
+        assert_eq!(op_keys_of(1), Some(vec![op_key(2)]));
+        assert_eq!(op_keys_of(3), Some(vec![op_key(2), op_key(4)]));
+        assert_eq!(op_keys_of(5), None);
+        assert_eq!(snapshot.op_keys.len(), 2);
+        assert_eq!(snapshot.covered_until_utc_epoch_s, 90);
+
+        snapshot.save(&path).unwrap();
+        assert_eq!(
+            BloomSnapshot::load(&path, &tuning_params),
+            Some(snapshot.clone())
+        );
+
+        // a snapshot built for another bloom config is ignored
+        Arc::make_mut(&mut tuning_params).gossip_bloom_chunk_count += 1;
+        assert_eq!(BloomSnapshot::load(&path, &tuning_params), None);
+        Arc::make_mut(&mut tuning_params).gossip_bloom_chunk_count -= 1;
+        let mut other_fp = snapshot;
+        other_fp.fp_rate = TGT_FP * 2.0;
+        other_fp.save(&path).unwrap();
+        assert_eq!(BloomSnapshot::load(&path, &tuning_params), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,64 +1,435 @@
 use super::*;
+use crate::actor::AgentArcStats;
+use crate::actor::LocalSyncStats;
 use crate::event::*;
 use kitsune_p2p_types::dht_arc::*;
+use std::ops::Bound;
 
+impl AgentArcStats {
+    fn new(agent: Arc<KitsuneAgent>, arc: &DhtArc) -> Self {
+        let start_loc = match arc.range().start {
+            Bound::Included(loc) | Bound::Excluded(loc) => loc,
+            Bound::Unbounded => 0,
+        };
+        Self {
+            agent,
+            start_loc,
+            length: arc.absolute_length(),
+        }
+    }
+}
+
+pub(crate) struct LocalSyncResult {
+    pub data_map: DataMap,
+    pub key_set: KeySet,
+    pub bloom: KeyFilter,
+    /// the per-round budget ran out before every local agent
+    /// had every op, another round is needed to finish
+    pub has_remaining_work: bool,
+    /// the number of ops one local agent held and another didn't,
+    /// whether or not handing them over succeeded.
+    /// 0 means the local agents were already in sync
+    pub diverged_op_count: usize,
+    pub stats: LocalSyncStats,
+    /// the agent infos fetched from the agent store this round,
+    /// `None` if the cached agent infos were used
+    pub queried_agent_infos: Option<Vec<AgentInfoSigned>>,
+    /// every op integrated before this time is in `key_set`
+    pub covered_until_utc_epoch_s: i64,
+    /// what each local agent was found to hold, for the next local sync
+    pub op_watermarks: OpWatermarks,
+    /// when the ops the local agents hold were received, where known.
+    /// only kept if the `gossip_op_order` goes by it
+    pub op_received_at: OpReceivedAt,
+    /// the error that cut the sync short, if any.
+    /// everything else in the result is still what was built before it,
+    /// and `has_remaining_work` is set so the rest is retried next round
+    pub error: Option<KitsuneError>,
+}
+
+/// What a local sync round starts from, mostly carried over from earlier rounds
+#[derive(Default)]
+pub(crate) struct LocalSyncInput {
+    /// if set, only those of the local agents that are also in it
+    /// take part in the sync, the rest are left untouched
+    pub only_agents: Option<HashSet<Arc<KitsuneAgent>>>,
+    /// if set, used instead of querying the agent store
+    pub cached_agent_infos: Option<Vec<AgentInfoSigned>>,
+    /// if set, cleared and repopulated as the resulting bloom
+    /// instead of allocating a new one
+    pub reuse_bloom: Option<BloomChunks>,
+    /// if set, each agent in it starts from the op keys it held,
+    /// instead of fetching the op hashes it integrated before it
+    pub snapshot: Option<BloomSnapshot>,
+    /// likewise for each agent in it, whose op keys take
+    /// precedence over the snapshot's
+    pub op_watermarks: OpWatermarks,
+    /// when those op keys were received, as far as known
+    pub op_received_at: OpReceivedAt,
+    /// if set, only the ops and agent infos located in it are collected,
+    /// so the bloom only covers that part of the dht. Such a sync leaves
+    /// `op_watermarks` as they were, since they must cover the full arc,
+    /// and only drops the receive times of ops in the arc which are gone
+    pub arc: Option<DhtArc>,
+    /// each agent in it only has the ops in its arc collected,
+    /// and its watermark only covers that arc
+    pub agent_arcs: AgentArcs,
+}
+
+/// Sync `local_agents` with each other, starting from `input`.
+/// Every space runs this on its own gossip task with its own `evt_sender`,
+/// so a slow sync in one space never holds up the sync of another.
 pub(crate) async fn step_2_local_sync_inner(
     space: Arc<KitsuneSpace>,
     evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    tuning_params: KitsuneP2pTuningParams,
     local_agents: HashSet<Arc<KitsuneAgent>>,
-) -> KitsuneResult<(DataMap, KeySet, BloomFilter)> {
+    input: LocalSyncInput,
+) -> LocalSyncResult {
+    let LocalSyncInput {
+        only_agents,
+        cached_agent_infos,
+        reuse_bloom,
+        snapshot,
+        op_watermarks,
+        op_received_at,
+        arc,
+        agent_arcs,
+    } = input;
     let mut inner = Inner {
         space,
         evt_sender,
+        tuning_params,
         local_agents,
+        only_agents,
+        cached_agent_infos,
+        queried_agent_infos: None,
         data_map: HashMap::new(),
-        has_hash: HashMap::new(),
+        has_hash: HasMap::default(),
+        has_remaining_work: false,
+        diverged_op_count: 0,
+        stats: LocalSyncStats::default(),
+        snapshot,
+        op_watermarks,
+        op_received_at,
+        arc,
+        agent_arcs,
+        covered_until_utc_epoch_s: 0,
     };
 
+    let started = std::time::Instant::now();
     inner.collect_local_ops().await;
+    inner.stats.collect_local_ops_duration = started.elapsed();
+
+    let started = std::time::Instant::now();
     inner.collect_local_agents().await;
-    inner.local_sync().await?;
-    Ok(inner.finish())
+    inner.stats.collect_local_agents_duration = started.elapsed();
+
+    let started = std::time::Instant::now();
+    let error = inner.local_sync().await.err();
+    inner.stats.local_sync_duration = started.elapsed();
+
+    LocalSyncResult {
+        error,
+        ..inner.finish(reuse_bloom)
+    }
 }
 
 struct Inner {
     space: Arc<KitsuneSpace>,
     evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    tuning_params: KitsuneP2pTuningParams,
     local_agents: HashSet<Arc<KitsuneAgent>>,
+    only_agents: Option<HashSet<Arc<KitsuneAgent>>>,
+    cached_agent_infos: Option<Vec<AgentInfoSigned>>,
+    queried_agent_infos: Option<Vec<AgentInfoSigned>>,
     data_map: DataMap,
     has_hash: HasMap,
+    has_remaining_work: bool,
+    diverged_op_count: usize,
+    stats: LocalSyncStats,
+    snapshot: Option<BloomSnapshot>,
+    op_watermarks: OpWatermarks,
+    op_received_at: OpReceivedAt,
+    arc: Option<DhtArc>,
+    agent_arcs: AgentArcs,
+    covered_until_utc_epoch_s: i64,
+}
+
+/// The op keys each local agent held as of its watermark,
+/// so the next local sync only has to fetch what it integrated since.
+/// As in a [HasMap], a key held by many agents is stored once.
+#[derive(Default)]
+pub(crate) struct OpWatermarks {
+    op_keys: HasMap,
+    covered_until_utc_epoch_s: HashMap<Arc<KitsuneAgent>, i64>,
+}
+
+impl OpWatermarks {
+    /// every op `agent` integrated before this time is in its op keys,
+    /// `None` if it has no watermark
+    pub fn covered_until_utc_epoch_s(&self, agent: &Arc<KitsuneAgent>) -> Option<i64> {
+        self.covered_until_utc_epoch_s.get(agent).copied()
+    }
+
+    /// the op keys `agent` held as of its watermark
+    pub fn op_keys<'a>(
+        &'a self,
+        agent: &Arc<KitsuneAgent>,
+    ) -> impl Iterator<Item = &'a Arc<MetaOpKey>> + 'a {
+        self.op_keys.keys_of(agent)
+    }
+
+    /// the agents with a watermark
+    pub fn agents(&self) -> impl Iterator<Item = &Arc<KitsuneAgent>> {
+        self.covered_until_utc_epoch_s.keys()
+    }
+
+    /// set the watermark of `agent`
+    pub fn insert(
+        &mut self,
+        agent: Arc<KitsuneAgent>,
+        covered_until_utc_epoch_s: i64,
+        op_keys: impl IntoIterator<Item = Arc<MetaOpKey>>,
+    ) {
+        self.op_keys.remove_agent(&agent);
+        self.op_keys.add_agent(agent.clone());
+        for key in op_keys {
+            self.op_keys.insert(&agent, key);
+        }
+        self.covered_until_utc_epoch_s
+            .insert(agent, covered_until_utc_epoch_s);
+    }
+
+    /// carry the watermark of `agent` in `previous` over, if it has one
+    fn keep(&mut self, previous: &OpWatermarks, agent: &Arc<KitsuneAgent>) {
+        if let Some(covered_until_utc_epoch_s) = previous.covered_until_utc_epoch_s(agent) {
+            self.insert(
+                agent.clone(),
+                covered_until_utc_epoch_s,
+                previous.op_keys(agent).cloned(),
+            );
+        }
+    }
+
+    /// forget the watermark of `agent`
+    pub fn remove(&mut self, agent: &Arc<KitsuneAgent>) {
+        self.op_keys.remove_agent(agent);
+        self.covered_until_utc_epoch_s.remove(agent);
+    }
+
+    /// forget every watermark
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The storage arcs local agents have been set to,
+/// agents without one have their ops collected over the full arc.
+pub(crate) type AgentArcs = HashMap<Arc<KitsuneAgent>, DhtArc>;
+
+/// is this agent part of the sync
+fn is_sync_target(
+    only_agents: &Option<HashSet<Arc<KitsuneAgent>>>,
+    agent: &Arc<KitsuneAgent>,
+) -> bool {
+    only_agents
+        .as_ref()
+        .map(|only| only.contains(agent))
+        .unwrap_or(true)
+}
+
+/// is this key part of the sync, which covers the full arc if `arc` isn't set
+fn is_in_arc(arc: &Option<DhtArc>, key: &MetaOpKey) -> bool {
+    arc.as_ref()
+        .map(|arc| arc.contains(key.loc()))
+        .unwrap_or(true)
 }
 
 impl Inner {
+    /// record which ops each targeted local agent holds in `has_hash`.
+    /// `fetch_op_hashes_for_constraints` answers with all of an agent's
+    /// op hashes at once rather than a stream, so each answer is folded
+    /// into `has_hash` as soon as it arrives and then dropped: at most
+    /// `gossip_local_sync_fetch_concurrency` answers are held alongside it.
+    /// The bloom can't be built from the answers as they arrive instead,
+    /// since `local_sync` needs to know which agent holds which op, and
+    /// `has_hash` already keeps a single copy of an op held by many agents.
+    /// An agent with a watermark only has the op hashes integrated since
+    /// it fetched, and gets a new watermark once they are added to it.
+    /// When the ops were received is recorded if the op order needs it.
+    /// A sync over part of the arc keeps the watermarks as they were.
     pub async fn collect_local_ops(&mut self) {
+        use futures::stream::StreamExt;
+
         let Inner {
             space,
             evt_sender,
+            tuning_params,
             local_agents,
+            only_agents,
             has_hash,
+            stats,
+            snapshot,
+            op_watermarks,
+            op_received_at,
+            arc,
+            agent_arcs,
+            covered_until_utc_epoch_s,
             ..
         } = self;
 
-        // collect all local agents' ops
-        for agent in local_agents.iter() {
-            if let Ok(ops) = evt_sender
-                .fetch_op_hashes_for_constraints(FetchOpHashesForConstraintsEvt {
+        let restricted = arc.is_some();
+        let dht_arc = arc.unwrap_or_else(|| DhtArc::new(0, u32::MAX));
+        let record_received_at = op_order(tuning_params).uses_received_at();
+        *covered_until_utc_epoch_s = now_utc_epoch_s();
+        let fetched_at_utc_epoch_s = *covered_until_utc_epoch_s;
+
+        let targets: HashSet<Arc<KitsuneAgent>> = local_agents
+            .iter()
+            .filter(|agent| is_sync_target(only_agents, agent))
+            .cloned()
+            .collect();
+        // a snapshot stands in for the watermark of each agent in it
+        // which has none, an agent not in it fetches all its op hashes
+        let snapshot = snapshot.take();
+        if let Some(snapshot) = &snapshot {
+            tracing::debug!(
+                snapshot_agent_count = %snapshot.agents.len(),
+                snapshot_op_count = %snapshot.op_keys.len(),
+                covered_until_utc_epoch_s = %snapshot.covered_until_utc_epoch_s,
+                "resuming local sync from bloom snapshot",
+            );
+        }
+
+        // an agent whose fetch fails loses its watermark, and agents which
+        // are no longer local lose theirs, the rest keep them for next time.
+        // the watermarks are built anew so keys nobody holds are dropped
+        let previous = std::mem::take(op_watermarks);
+        for agent in previous.agents() {
+            if local_agents.contains(agent) && !targets.contains(agent) {
+                op_watermarks.keep(&previous, agent);
+            }
+        }
+        let targets: Vec<_> = targets
+            .into_iter()
+            .map(|agent| {
+                // integration times are whole seconds, so refetch the last
+                // one of the watermark, or else of the snapshot,
+                // rather than risk missing ops from it
+                let since = previous
+                    .covered_until_utc_epoch_s(&agent)
+                    .or_else(|| {
+                        snapshot
+                            .as_ref()
+                            .filter(|s| s.op_keys_of(&agent).is_some())
+                            .map(|s| s.covered_until_utc_epoch_s)
+                    })
+                    .map(|covered_until| covered_until - 1)
+                    .unwrap_or(i64::MIN);
+                // a sync over part of the dht goes by that part instead
+                let dht_arc = match agent_arcs.get(&agent) {
+                    Some(agent_arc) if !restricted => *agent_arc,
+                    _ => dht_arc,
+                };
+                stats
+                    .agent_arcs
+                    .push(AgentArcStats::new(agent.clone(), &dht_arc));
+                let input = FetchOpHashesForConstraintsEvt {
                     space: space.clone(),
                     agent: agent.clone(),
-                    dht_arc: DhtArc::new(0, u32::MAX),
-                    since_utc_epoch_s: i64::MIN,
+                    dht_arc,
+                    since_utc_epoch_s: since,
                     until_utc_epoch_s: i64::MAX,
-                })
-                .await
-            {
-                for op in ops {
-                    let key = Arc::new(MetaOpKey::Op(op));
-                    has_hash
-                        .entry(agent.clone())
-                        .or_insert_with(HashSet::new)
-                        .insert(key);
+                };
+                (agent, input)
+            })
+            .collect();
+
+        let concurrency = std::cmp::max(
+            tuning_params.gossip_local_sync_fetch_concurrency as usize,
+            1,
+        );
+        let timeout = event_timeout(tuning_params);
+
+        // collect all targeted local agents' ops,
+        // with at most `concurrency` fetches in flight at once
+        let mut fetches = futures::stream::iter(targets.into_iter().map(|(agent, input)| {
+            let mut evt_sender = evt_sender.clone();
+            async move {
+                let ops = within_event_timeout(
+                    timeout,
+                    "fetch_op_hashes_for_constraints",
+                    evt_sender.fetch_op_hashes_for_constraints(input),
+                )
+                .await;
+                (agent, ops)
+            }
+        }))
+        .buffer_unordered(concurrency);
+
+        let mut failed_agent_fetch_count = 0;
+        while let Some((agent, ops)) = fetches.next().await {
+            let ops = match ops {
+                Ok(ops) => ops,
+                // we don't know what this agent holds, leave it out
+                // of this round entirely rather than claiming it has nothing
+                Err(e) => {
+                    tracing::warn!(?agent, ?e, "failed to fetch local op hashes");
+                    failed_agent_fetch_count += 1;
+                    continue;
+                }
+            };
+            // an agent with no ops still takes part in the sync
+            has_hash.add_agent(agent.clone());
+            let agent_arc = agent_arcs.get(&agent).copied();
+            let in_arcs = |key: &MetaOpKey| is_in_arc(arc, key) && is_in_arc(&agent_arc, key);
+            // start from what the watermark, or else the snapshot, says it held
+            if previous.covered_until_utc_epoch_s(&agent).is_some() {
+                for key in previous.op_keys(&agent).filter(|key| in_arcs(key)) {
+                    has_hash.insert(&agent, key.clone());
+                }
+            } else if let Some(keys) = snapshot.as_ref().and_then(|s| s.op_keys_of(&agent)) {
+                for key in keys.filter(|key| in_arcs(key)) {
+                    has_hash.insert(&agent, key.clone());
+                }
+            }
+            for (op, received_at) in ops {
+                let key = Arc::new(MetaOpKey::Op(op));
+                if !in_arcs(&key) {
+                    continue;
+                }
+                if let Some(received_at) = received_at.filter(|_| record_received_at) {
+                    op_received_at.insert(key.clone(), received_at);
                 }
+                has_hash.insert(&agent, key);
+            }
+            // a sync over part of the arc hands the watermark back untouched
+            if restricted {
+                op_watermarks.keep(&previous, &agent);
+                continue;
+            }
+            op_watermarks.insert(
+                agent.clone(),
+                fetched_at_utc_epoch_s,
+                has_hash.keys_of(&agent).cloned(),
+            );
+        }
+        stats.failed_agent_fetch_count = failed_agent_fetch_count;
+
+        // only keep the times of ops still held, or outside the arc synced
+        if record_received_at {
+            let previous = std::mem::take(op_received_at);
+            op_received_at.extend(
+                has_hash
+                    .keys()
+                    .filter_map(|key| Some((key.clone(), *previous.get(key)?))),
+            );
+            if restricted {
+                op_received_at.extend(previous.into_iter().filter(|(key, _)| !is_in_arc(arc, key)));
             }
+        } else {
+            op_received_at.clear();
         }
     }
 
@@ -66,159 +437,1451 @@ impl Inner {
         let Inner {
             space,
             evt_sender,
+            tuning_params,
             local_agents,
+            cached_agent_infos,
+            queried_agent_infos,
             data_map,
             has_hash,
+            arc,
             ..
         } = self;
 
-        // agent store is shared between agents in one space
-        // we only have to query it once for all local_agents
-        if let Some(agent) = local_agents.iter().next() {
-            if let Ok(agent_infos) = evt_sender
-                .query_agent_info_signed(QueryAgentInfoSignedEvt {
-                    space: space.clone(),
-                    agent: agent.clone(),
-                })
+        let agent_infos = match cached_agent_infos.take() {
+            Some(agent_infos) => agent_infos,
+            None => {
+                // agent store is shared between agents in one space
+                // we only have to query it once for all local_agents
+                let agent = match local_agents.iter().next() {
+                    Some(agent) => agent,
+                    None => return,
+                };
+                match within_event_timeout(
+                    event_timeout(tuning_params),
+                    "query_agent_info_signed",
+                    evt_sender.query_agent_info_signed(QueryAgentInfoSignedEvt {
+                        space: space.clone(),
+                        agent: agent.clone(),
+                    }),
+                )
                 .await
-            {
-                for agent_info in agent_infos {
-                    let data = Arc::new(MetaOpData::Agent(agent_info));
-                    let key = data.key();
-                    data_map.insert(key.clone(), data);
-                    for (_agent, has) in has_hash.iter_mut() {
-                        has.insert(key.clone());
+                {
+                    Ok(agent_infos) => {
+                        *queried_agent_infos = Some(agent_infos.clone());
+                        agent_infos
+                    }
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to query local agent infos");
+                        return;
                     }
                 }
             }
+        };
+
+        for agent_info in agent_infos {
+            let data = Arc::new(MetaOpData::Agent(agent_info));
+            let key = data.key();
+            if !is_in_arc(arc, &key) {
+                continue;
+            }
+            data_map.insert(key.clone(), data);
+            has_hash.insert_for_all(key);
         }
     }
 
+    /// hand ops between local agents, if this fails part way
+    /// the ops handed over so far are still recorded.
+    /// agents are gone through in the order of the [HasMap], so the
+    /// same map always sends the same gossip in the same order.
+    /// does nothing if `gossip_skip_local_sync` is set
     pub async fn local_sync(&mut self) -> KitsuneResult<()> {
-        let mut new_has_map = self.has_hash.clone();
+        use futures::stream::StreamExt;
+
+        if self.tuning_params.gossip_skip_local_sync {
+            tracing::debug!("skipping local sync, gossip_skip_local_sync is set");
+            return Ok(());
+        }
+
+        let mut new_has_map = self.has_hash.agent_bits().clone();
 
         let Self {
             space,
             evt_sender,
+            tuning_params,
+            only_agents,
             data_map,
             has_hash,
+            has_remaining_work,
+            diverged_op_count,
+            stats,
+            op_received_at,
             ..
         } = self;
 
+        let op_order = op_order(tuning_params);
+        let max_ops = tuning_params.gossip_local_sync_max_ops_per_round as usize;
+        let max_bytes = tuning_params.gossip_local_sync_max_bytes_per_round as usize;
+        let concurrency = std::cmp::max(
+            tuning_params.gossip_local_sync_fetch_concurrency as usize,
+            1,
+        );
+        let fetch_sender = evt_sender.clone();
+        let timeout = event_timeout(tuning_params);
+
         let mut local_synced_ops = 0;
-        for (old_agent, old_set) in has_hash.iter() {
+        let mut local_synced_bytes = 0;
+        let mut local_failed_ops = 0;
+        let mut local_unacknowledged_ops = 0;
+        let mut error = None;
+        let old_has_map: &HasMap = has_hash;
+        'sync: for (old_agent, old_set) in old_has_map.agents() {
+            if !is_sync_target(only_agents, old_agent) {
+                continue;
+            }
             for (new_agent, new_set) in new_has_map.iter_mut() {
-                if old_agent == new_agent {
+                if old_agent == new_agent || !is_sync_target(only_agents, new_agent) {
                     continue;
                 }
-                for old_key in old_set.iter() {
-                    if !new_set.contains(old_key) {
-                        local_synced_ops += 1;
-                        let op_data =
-                            data_map_get(evt_sender, space, old_agent, data_map, &old_key).await?;
-
-                        match &*op_data {
-                            MetaOpData::Op(key, data) => {
-                                evt_sender
-                                    .gossip(
-                                        space.clone(),
-                                        new_agent.clone(),
-                                        old_agent.clone(),
-                                        key.clone(),
-                                        data.clone(),
+                let mut missing_keys: Vec<_> = old_set
+                    .iter()
+                    .filter(|index| !new_set.contains(*index))
+                    .collect();
+                missing_keys.sort_by_key(|index| {
+                    let key = old_has_map.key(*index);
+                    op_order.key_priority(key, op_received_at.get(key).copied())
+                });
+                let missing_keys: Vec<_> = missing_keys
+                    .into_iter()
+                    .map(|index| {
+                        let old_key = old_has_map.key(index);
+                        (index, old_key.clone(), data_map.get(old_key).cloned())
+                    })
+                    .collect();
+
+                // fetch ops ahead of sending them, keeping their order,
+                // so fetching the next ops overlaps sending the current one
+                let space: &Arc<KitsuneSpace> = space;
+                let mut fetches = futures::stream::iter(missing_keys.into_iter().map(
+                    |(index, old_key, op_data)| {
+                        let mut evt_sender = fetch_sender.clone();
+                        async move {
+                            let mut stall = std::time::Duration::default();
+                            let op_data = match op_data {
+                                Some(op_data) => Ok(op_data),
+                                None => {
+                                    match evt_channel_ready(
+                                        &mut evt_sender,
+                                        &mut stall,
+                                        timeout,
+                                        "fetch_op_hash_data",
                                     )
                                     .await
-                                    .map_err(KitsuneError::other)?;
-                            }
-                            // this should be impossible right now
-                            // due to the shared agent store
-                            MetaOpData::Agent(_) => unreachable!(),
+                                    {
+                                        Ok(()) => {
+                                            fetch_from_holders(
+                                                &mut evt_sender,
+                                                space,
+                                                old_agent,
+                                                old_has_map,
+                                                &old_key,
+                                                timeout,
+                                            )
+                                            .await
+                                        }
+                                        Err(e) => Err(e),
+                                    }
+                                }
+                            };
+                            (index, old_key, stall, op_data)
+                        }
+                    },
+                ))
+                .buffered(concurrency);
+
+                let mut next = fetches.next().await;
+                while let Some((index, old_key, stall, op_data)) = next.take() {
+                    stats.evt_channel_stall += stall;
+                    let op_data = match op_data {
+                        Ok(op_data) => op_data,
+                        // a stalled event handler fails every fetch
+                        // alike, so stop rather than wait out the
+                        // timeout for each of them
+                        Err(e) if is_event_timeout(&e) => {
+                            *has_remaining_work = true;
+                            error = Some(e);
+                            break 'sync;
                         }
+                        // a single failed fetch shouldn't stop the sync,
+                        // leave the op out of the recipient's set so it
+                        // is retried next round
+                        Err(e) => {
+                            tracing::warn!(
+                                ?new_agent,
+                                key = ?old_key,
+                                ?e,
+                                "local sync op fetch failed"
+                            );
+                            local_failed_ops += 1;
+                            next = fetches.next().await;
+                            continue;
+                        }
+                    };
+                    data_map.insert(old_key, op_data.clone());
+
+                    match &*op_data {
+                        MetaOpData::Op(key, data) => {
+                            // stop once this round's budget is spent,
+                            // whatever is left is picked up next round.
+                            // always allow one op so a single large op
+                            // can't stall the sync forever.
+                            let over_ops = max_ops > 0 && local_synced_ops >= max_ops;
+                            let over_bytes = max_bytes > 0
+                                && local_synced_bytes > 0
+                                && local_synced_bytes + data.len() > max_bytes;
+                            if over_ops || over_bytes {
+                                *has_remaining_work = true;
+                                break 'sync;
+                            }
 
-                        new_set.insert(old_key.clone());
+                            // a full channel or a timed out send means the
+                            // event handler is stalled, stop as for a fetch
+                            if let Err(e) = evt_channel_ready(
+                                evt_sender,
+                                &mut stats.evt_channel_stall,
+                                timeout,
+                                "gossip",
+                            )
+                            .await
+                            {
+                                *has_remaining_work = true;
+                                error = Some(e);
+                                break 'sync;
+                            }
+                            let send = within_event_timeout(
+                                timeout,
+                                "gossip",
+                                evt_sender.gossip(
+                                    space.clone(),
+                                    new_agent.clone(),
+                                    old_agent.clone(),
+                                    key.clone(),
+                                    data.clone(),
+                                ),
+                            );
+                            let (sent, fetched) = futures::future::join(send, fetches.next()).await;
+                            next = fetched;
+                            match sent {
+                                Err(e) if is_event_timeout(&e) => {
+                                    *has_remaining_work = true;
+                                    error = Some(e);
+                                    break 'sync;
+                                }
+                                // a single failed recipient shouldn't stop
+                                // replication to everyone else, leave the op
+                                // out of their set so it is retried next round
+                                Err(e) => {
+                                    tracing::warn!(
+                                        ?new_agent,
+                                        ?key,
+                                        ?e,
+                                        "local sync gossip failed"
+                                    );
+                                    local_failed_ops += 1;
+                                    continue;
+                                }
+                                // only count the op as synced once the
+                                // recipient says it holds it
+                                Ok(false) => {
+                                    tracing::debug!(
+                                        ?new_agent,
+                                        ?key,
+                                        "local sync gossip not acknowledged"
+                                    );
+                                    local_unacknowledged_ops += 1;
+                                    continue;
+                                }
+                                Ok(true) => (),
+                            }
+                            local_synced_bytes += data.len();
+                        }
+                        // this should be impossible right now
+                        // due to the shared agent store
+                        MetaOpData::Agent(_) => unreachable!(),
                     }
+
+                    local_synced_ops += 1;
+                    new_set.insert(index);
                 }
             }
         }
 
-        if local_synced_ops > 0 {
+        if local_synced_ops > 0 || local_failed_ops > 0 || local_unacknowledged_ops > 0 {
             tracing::debug!(
                 %local_synced_ops,
+                %local_synced_bytes,
+                %local_failed_ops,
+                %local_unacknowledged_ops,
+                %has_remaining_work,
+                evt_channel_stall_ms = %stats.evt_channel_stall.as_millis(),
                 "local sync",
             );
         }
 
-        *has_hash = new_has_map;
+        has_hash.set_agent_bits(new_has_map);
+        *diverged_op_count = local_synced_ops + local_failed_ops + local_unacknowledged_ops;
+        stats.unacknowledged_op_count = local_unacknowledged_ops;
 
-        Ok(())
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
     }
 
-    pub fn finish(self) -> (DataMap, KeySet, BloomFilter) {
+    /// build the result, rebuilding `reuse_bloom` in place if set
+    pub fn finish(self, reuse_bloom: Option<BloomChunks>) -> LocalSyncResult {
+        let started = std::time::Instant::now();
         let Self {
-            data_map, has_hash, ..
+            tuning_params,
+            queried_agent_infos,
+            data_map,
+            has_hash,
+            has_remaining_work,
+            diverged_op_count,
+            mut stats,
+            covered_until_utc_epoch_s,
+            op_watermarks,
+            op_received_at,
+            ..
         } = self;
+        let bloom_chunk_count = tuning_params.gossip_bloom_chunk_count as usize;
+        let bloom_max_chunk_bytes = tuning_params.gossip_bloom_max_chunk_bytes as usize;
+        let bloom_min_key_count = tuning_params.gossip_bloom_min_key_count as usize;
+
+        stats.data_map_len = data_map.len();
+        stats.data_map_bytes = data_map.values().map(|data| data.byte_count()).sum();
+        tracing::debug!(
+            data_map_len = %stats.data_map_len,
+            data_map_bytes = %stats.data_map_bytes,
+            "local sync data map",
+        );
 
-        // 1 in 100 false positives...
-        // we can get 1 in 1000 for ~2x the filter size, but may not be worth it
-        // 1 in 100 pretty much guarantees full sync after two communications.
-        const TGT_FP: f64 = 0.01;
+        let all_key_count = has_hash.key_count();
+        stats.outstanding_op_count = has_hash
+            .agents()
+            .map(|(_, set)| all_key_count - set.count())
+            .sum();
 
         // at this point, all the local has_hash maps should be identical,
-        // so we can just take the first one
-        let (key_set, bloom) = if let Some((_, map)) = has_hash.into_iter().next() {
-            let len = map.len();
+        // so we can just take the first one.
+        // unless they weren't synced, then take what any of them holds.
+        // with no local agents there is nothing to hold, but the filter
+        // is still built from the (empty) key set like any other
+        let first_agent = has_hash.agents().next().map(|(agent, _)| agent.clone());
+        let key_set: KeySet = match first_agent {
+            Some(_) if tuning_params.gossip_skip_local_sync => has_hash.keys().cloned().collect(),
+            Some(agent) => has_hash.keys_of(&agent).cloned().collect(),
+            None => KeySet::new(),
+        };
+        let len = key_set.len();
+        let bloom = if len < bloom_min_key_count {
+            // too few keys for a bloom to be worth building,
+            // peers are sent the keys themselves
             tracing::trace!(
                 local_op_count=%len,
-                "generating local bloom",
+                %bloom_min_key_count,
+                "using exact local keys",
             );
-            let mut bloom = bloomfilter::Bloom::new_for_fp_rate(len, TGT_FP);
-            for h in map.iter() {
-                bloom.set(h);
-            }
-            (map, bloom)
+            KeyFilter::Exact(key_set.clone())
         } else {
-            (HashSet::new(), bloomfilter::Bloom::new(1, 1))
+            tracing::trace!(
+                local_op_count=%len,
+                %bloom_chunk_count,
+                "generating local bloom",
+            );
+            let bloom = match reuse_bloom {
+                Some(mut bloom) => {
+                    bloom.rebuild(&key_set, bloom_chunk_count, bloom_max_chunk_bytes);
+                    bloom
+                }
+                None => BloomChunks::build(&key_set, bloom_chunk_count, bloom_max_chunk_bytes),
+            };
+            tracing::trace!(
+                local_op_count=%len,
+                bloom_bit_count=%bloom.bit_count(),
+                bloom_byte_count=%bloom.byte_count(),
+                bloom_hash_fn_count=%bloom.hash_fn_count(),
+                "generated local bloom",
+            );
+            check_bloom_capacity(&bloom, len);
+            KeyFilter::Bloom(bloom)
         };
+        stats.finish_duration = started.elapsed();
+
+        LocalSyncResult {
+            data_map,
+            key_set,
+            bloom,
+            has_remaining_work,
+            diverged_op_count,
+            stats,
+            queried_agent_infos,
+            covered_until_utc_epoch_s,
+            op_watermarks,
+            op_received_at,
+            error: None,
+        }
+    }
+}
+
+/// warn if the bloom was sized for a very different number of keys
+/// than it holds, the false positive rate is then far off target
+/// (or we are sending far more bits than needed)
+fn check_bloom_capacity(bloom: &BloomChunks, key_count: usize) {
+    // how far apart key count and capacity can be before we warn
+    const MISMATCH_FACTOR: u64 = 2;
+
+    let key_count = key_count as u64;
+    let capacity = bloom.capacity();
+    if key_count > capacity * MISMATCH_FACTOR || capacity > key_count * MISMATCH_FACTOR {
+        tracing::warn!(
+            %key_count,
+            bloom_capacity=%capacity,
+            bloom_bit_count=%bloom.bit_count(),
+            "local bloom capacity is mismatched to its key set",
+        );
+    }
+}
+
+/// wait for room in the event channel, adding the time spent waiting
+/// to `stall`, so an event channel too small for the sync shows up
+/// in the stats rather than as a sync that is slow for no clear reason.
+/// waiting longer than `timeout` fails with an error naming `event`,
+/// the event waiting to be sent.
+/// a closed channel is left for the following send to report
+async fn evt_channel_ready<T>(
+    evt_sender: &mut futures::channel::mpsc::Sender<T>,
+    stall: &mut std::time::Duration,
+    timeout: Option<std::time::Duration>,
+    event: &'static str,
+) -> KitsuneResult<()> {
+    let start = std::time::Instant::now();
+    let mut waited = false;
+    let ready = futures::future::poll_fn(|cx| {
+        let ready = evt_sender.poll_ready(cx);
+        waited |= ready.is_pending();
+        ready.map(|_| Ok::<_, std::convert::Infallible>(()))
+    });
+    let res = within_event_timeout(timeout, event, ready).await;
+    if waited {
+        *stall += start.elapsed();
+    }
+    res
+}
 
-        (data_map, key_set, bloom)
+/// how long to wait on the event handler for a single event,
+/// `None` to wait forever
+fn event_timeout(tuning_params: &KitsuneP2pTuningParams) -> Option<std::time::Duration> {
+    match tuning_params.gossip_event_timeout_ms {
+        0 => None,
+        ms => Some(std::time::Duration::from_millis(ms as u64)),
     }
 }
 
-async fn data_map_get(
+/// await the answer to a single `event`, failing with
+/// [KitsuneErrorKind::EventTimedOut] if it takes longer than `timeout`
+async fn within_event_timeout<T, E, F>(
+    timeout: Option<std::time::Duration>,
+    event: &'static str,
+    f: F,
+) -> KitsuneResult<T>
+where
+    F: std::future::Future<Output = Result<T, E>>,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let res = match timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, f).await {
+            Ok(res) => res,
+            Err(_) => {
+                tracing::warn!(%event, ?timeout, "event handler timed out");
+                return Err(KitsuneErrorKind::EventTimedOut { event }.into());
+            }
+        },
+        None => f.await,
+    };
+    res.map_err(KitsuneError::other)
+}
+
+fn is_event_timeout(e: &KitsuneError) -> bool {
+    matches!(e.kind(), KitsuneErrorKind::EventTimedOut { .. })
+}
+
+/// fetch the data for `key` from `agent`. if `agent` no longer has the op,
+/// e.g. because its store was pruned since we collected our keys,
+/// the other local agents holding it according to `has_hash` are asked in turn.
+async fn fetch_from_holders(
     evt_sender: &mut futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
     space: &Arc<KitsuneSpace>,
     agent: &Arc<KitsuneAgent>,
-    map: &mut DataMap,
+    has_hash: &HasMap,
     key: &Arc<MetaOpKey>,
+    timeout: Option<std::time::Duration>,
 ) -> KitsuneResult<Arc<MetaOpData>> {
+    let op_hash = match &**key {
+        MetaOpKey::Op(op_hash) => op_hash,
+        // the query agents api returns all the data,
+        // so we should already be fully pre-populated.
+        MetaOpKey::Agent(_, _) => unreachable!(),
+    };
+
+    let holders =
+        std::iter::once(agent).chain(has_hash.holders(key).filter(|holder| *holder != agent));
+    let mut error = None;
+    for holder in holders {
+        match fetch_op(evt_sender, space, holder, op_hash, timeout).await {
+            Ok((op_hash, data)) => return Ok(Arc::new(MetaOpData::Op(op_hash, data))),
+            // the other holders are behind the same stalled handler
+            Err(e) if is_event_timeout(&e) => return Err(e),
+            Err(e) => {
+                tracing::debug!(?holder, ?op_hash, ?e, "op fetch missed");
+                error = Some(e);
+            }
+        }
+    }
+    // the first holder is always asked, so there is an error
+    Err(error.unwrap())
+}
+
+async fn fetch_op(
+    evt_sender: &mut futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
+    space: &Arc<KitsuneSpace>,
+    agent: &Arc<KitsuneAgent>,
+    op_hash: &Arc<KitsuneOpHash>,
+    timeout: Option<std::time::Duration>,
+) -> KitsuneResult<(Arc<KitsuneOpHash>, Vec<u8>)> {
     use crate::event::*;
-    if let Some(data) = map.get(key) {
-        return Ok(data.clone());
+    let mut op = within_event_timeout(
+        timeout,
+        "fetch_op_hash_data",
+        evt_sender.fetch_op_hash_data(FetchOpHashDataEvt {
+            space: space.clone(),
+            agent: agent.clone(),
+            op_hashes: vec![op_hash.clone()],
+        }),
+    )
+    .await?;
+
+    if op.len() != 1 {
+        return Err(KitsuneErrorKind::OpFetchCountMismatch {
+            requested: op_hash.clone(),
+            returned: op.len(),
+        }
+        .into());
     }
-    match &**key {
-        MetaOpKey::Op(key) => {
-            let mut op = evt_sender
-                .fetch_op_hash_data(FetchOpHashDataEvt {
-                    space: space.clone(),
-                    agent: agent.clone(),
-                    op_hashes: vec![key.clone()],
-                })
+
+    Ok(op.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use futures::StreamExt;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn collect_local_ops_respects_fetch_concurrency() {
+        const CONCURRENCY: usize = 3;
+
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, .. } => {
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    respond.r(Ok(async move {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        Ok(vec![])
+                    }
+                    .boxed()
+                    .into()));
+                    None
+                }
+                evt => Some(evt),
+            });
+        }
+
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_local_sync_fetch_concurrency = CONCURRENCY as u32;
+        let local_agents: HashSet<Arc<KitsuneAgent>> = (0..10u8)
+            .map(|i| Arc::new(KitsuneAgent::new(vec![i; 36])))
+            .collect();
+        let mut inner = Inner {
+            tuning_params: Arc::new(tuning_params),
+            local_agents,
+            ..syncing_inner(evt_sender, HasMap::default())
+        };
+
+        inner.collect_local_ops().await;
+
+        assert_eq!(inner.has_hash.agent_count(), 10);
+        // every agent currently gossips over the full arc
+        assert_eq!(inner.stats.agent_arcs.len(), 10);
+        for arc in &inner.stats.agent_arcs {
+            assert_eq!(arc.length, u32::MAX as u64 + 1);
+        }
+        let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight <= CONCURRENCY, "{} in flight", max_in_flight);
+        assert!(max_in_flight > 1, "fetches were not run concurrently");
+
+        let op_hash = Arc::new(KitsuneOpHash::new(vec![9; 36]));
+        inner.data_map.insert(
+            Arc::new(MetaOpKey::Op(op_hash.clone())),
+            Arc::new(MetaOpData::Op(op_hash, vec![0; 10])),
+        );
+        let result = inner.finish(None);
+        assert_eq!(result.stats.data_map_len, 1);
+        assert_eq!(result.stats.data_map_bytes, 36 + 10);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn watermarks_dont_miss_ops_at_the_boundary() {
+        let space = Arc::new(KitsuneSpace::new(vec![0; 36]));
+        let agent = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let op = |i: u8| Arc::new(KitsuneOpHash::new(vec![i; 36]));
+        let keys = |ops: &[u8]| -> KeySet {
+            ops.iter()
+                .map(|i| Arc::new(MetaOpKey::Op(op(*i))))
+                .collect()
+        };
+
+        // the ops the agent holds, by integration time
+        let held = Arc::new(std::sync::Mutex::new(vec![(0, op(1))]));
+        let fetched_since = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        {
+            let held = held.clone();
+            let fetched_since = fetched_since.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, input, .. } => {
+                    fetched_since.lock().unwrap().push(input.since_utc_epoch_s);
+                    let ops: Vec<_> = held
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .filter(|(integrated_at, _)| {
+                            *integrated_at >= input.since_utc_epoch_s
+                                && *integrated_at < input.until_utc_epoch_s
+                        })
+                        .map(|(integrated_at, op)| (op.clone(), Some(integrated_at * 1000)))
+                        .collect();
+                    respond.r(Ok(async move { Ok(ops) }.boxed().into()));
+                    None
+                }
+                evt => Some(evt),
+            });
+        }
+        let sync = |op_watermarks| {
+            step_2_local_sync_inner(
+                space.clone(),
+                evt_sender.clone(),
+                Arc::new(Default::default()),
+                vec![agent.clone()].into_iter().collect(),
+                LocalSyncInput {
+                    cached_agent_infos: Some(vec![]),
+                    op_watermarks,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let first = sync(OpWatermarks::default()).await;
+        let first_covered = first.covered_until_utc_epoch_s;
+        assert_eq!(first.key_set, keys(&[1]));
+        // integrated during the second the first sync fetched in, after it did
+        held.lock().unwrap().push((first_covered, op(2)));
+        let second = sync(first.op_watermarks).await;
+        let second_covered = second.covered_until_utc_epoch_s;
+        assert_eq!(second.key_set, keys(&[1, 2]));
+        held.lock().unwrap().push((second_covered + 1, op(3)));
+        let third = sync(second.op_watermarks).await;
+        assert_eq!(third.key_set, keys(&[1, 2, 3]));
+        // a full fetch finds the same ops
+        let full = sync(OpWatermarks::default()).await;
+        assert_eq!(full.key_set, third.key_set);
+
+        assert_eq!(
+            *fetched_since.lock().unwrap(),
+            vec![i64::MIN, first_covered - 1, second_covered - 1, i64::MIN]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn syncing_part_of_the_arc_only_covers_it() {
+        let space = Arc::new(KitsuneSpace::new(vec![0; 36]));
+        let agent = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let op = |i: u8| Arc::new(KitsuneOpHash::new(vec![i; 36]));
+        let keys = |ops: &[u8]| -> KeySet {
+            ops.iter()
+                .map(|i| Arc::new(MetaOpKey::Op(op(*i))))
+                .collect()
+        };
+        // around the location of op 1, far from ops 0x80 and 0x81
+        let arc = DhtArc::new(0x0101_0101_u32, 0x1000);
+
+        let fetched_arcs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        {
+            let fetched_arcs = fetched_arcs.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, input, .. } => {
+                    fetched_arcs.lock().unwrap().push(input.dht_arc);
+                    // more than was asked for, which is still left out
+                    let ops = vec![(op(1), None), (op(0x80), None)];
+                    respond.r(Ok(async move { Ok(ops) }.boxed().into()));
+                    None
+                }
+                evt => Some(evt),
+            });
+        }
+        let mut op_watermarks = OpWatermarks::default();
+        op_watermarks.insert(agent.clone(), 5, keys(&[0x81]));
+
+        let result = step_2_local_sync_inner(
+            space,
+            evt_sender,
+            Arc::new(Default::default()),
+            vec![agent.clone()].into_iter().collect(),
+            LocalSyncInput {
+                cached_agent_infos: Some(vec![]),
+                op_watermarks,
+                arc: Some(arc),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(result.error.is_none());
+        assert_eq!(*fetched_arcs.lock().unwrap(), vec![arc]);
+        assert_eq!(result.key_set, keys(&[1]));
+        // the watermark still covers the full arc for the next full sync
+        let watermarks = &result.op_watermarks;
+        assert_eq!(watermarks.covered_until_utc_epoch_s(&agent), Some(5));
+        assert_eq!(
+            watermarks.op_keys(&agent).cloned().collect::<KeySet>(),
+            keys(&[0x81])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn agents_only_have_ops_in_their_arc_collected() {
+        let space = Arc::new(KitsuneSpace::new(vec![0; 36]));
+        let agent = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let op = |i: u8| Arc::new(KitsuneOpHash::new(vec![i; 36]));
+        let keys = |ops: &[u8]| -> KeySet {
+            ops.iter()
+                .map(|i| Arc::new(MetaOpKey::Op(op(*i))))
+                .collect()
+        };
+        // around the location of op 1, far from op 0x80
+        let agent_arc = DhtArc::new(0x0101_0101_u32, 0x1000);
+
+        let fetched_arcs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        {
+            let fetched_arcs = fetched_arcs.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, input, .. } => {
+                    fetched_arcs.lock().unwrap().push(input.dht_arc);
+                    // more than was asked for, which is still left out
+                    let ops = vec![(op(1), None), (op(0x80), None)];
+                    respond.r(Ok(async move { Ok(ops) }.boxed().into()));
+                    None
+                }
+                evt => Some(evt),
+            });
+        }
+        let mut agent_arcs = HashMap::new();
+        agent_arcs.insert(agent.clone(), agent_arc);
+
+        let result = step_2_local_sync_inner(
+            space,
+            evt_sender,
+            Arc::new(Default::default()),
+            vec![agent.clone()].into_iter().collect(),
+            LocalSyncInput {
+                cached_agent_infos: Some(vec![]),
+                agent_arcs,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(result.error.is_none());
+        assert_eq!(*fetched_arcs.lock().unwrap(), vec![agent_arc]);
+        assert_eq!(result.key_set, keys(&[1]));
+        assert_eq!(
+            result
+                .op_watermarks
+                .op_keys(&agent)
+                .cloned()
+                .collect::<KeySet>(),
+            keys(&[1])
+        );
+        assert_eq!(
+            result.stats.agent_arcs[0].length,
+            agent_arc.absolute_length()
+        );
+    }
+
+    #[test]
+    fn watermarks_store_keys_held_by_many_agents_once() {
+        let agent = |i: u8| Arc::new(KitsuneAgent::new(vec![i; 36]));
+        // each agent's fetch hands back its own copy of the same key
+        let key = || Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![1; 36]))));
+        let mut op_watermarks = OpWatermarks::default();
+        for i in 0..3 {
+            op_watermarks.insert(agent(i), 5, vec![key()]);
+        }
+        op_watermarks.remove(&agent(2));
+
+        let held: Vec<_> = op_watermarks
+            .agents()
+            .flat_map(|agent| op_watermarks.op_keys(agent).cloned())
+            .collect();
+        assert_eq!(held.len(), 2);
+        assert!(Arc::ptr_eq(&held[0], &held[1]));
+        assert_eq!(op_watermarks.covered_until_utc_epoch_s(&agent(0)), Some(5));
+        assert_eq!(op_watermarks.covered_until_utc_epoch_s(&agent(2)), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snapshot_leaves_out_agents_whose_fetch_failed() {
+        let space = Arc::new(KitsuneSpace::new(vec![0; 36]));
+        let holder = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let failing = Arc::new(KitsuneAgent::new(vec![2; 36]));
+        let op = Arc::new(KitsuneOpHash::new(vec![9; 36]));
+        // the failing agent's fetch only fails before the restart
+        let fail = Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let fetched_since = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sends = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        {
+            let (holder, failing, op) = (holder.clone(), failing.clone(), op.clone());
+            let (fail, fetched_since, sends) = (fail.clone(), fetched_since.clone(), sends.clone());
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, input, .. } => {
+                    let since = input.since_utc_epoch_s;
+                    fetched_since.lock().unwrap().push((input.agent[0], since));
+                    let ops = if input.agent == failing && fail.load(Ordering::SeqCst) {
+                        Err(KitsuneP2pError::from("fetch failed"))
+                    } else if input.agent == holder && since == i64::MIN {
+                        Ok(vec![(op.clone(), None)])
+                    } else {
+                        Ok(vec![])
+                    };
+                    respond.r(Ok(async move { ops }.boxed().into()));
+                    None
+                }
+                KitsuneP2pEvent::Gossip { ref to_agent, .. } => {
+                    sends.lock().unwrap().push(to_agent[0]);
+                    Some(evt)
+                }
+                evt => Some(evt),
+            });
+        }
+        let sync = |snapshot| {
+            step_2_local_sync_inner(
+                space.clone(),
+                evt_sender.clone(),
+                Arc::new(Default::default()),
+                vec![holder.clone(), failing.clone()].into_iter().collect(),
+                LocalSyncInput {
+                    cached_agent_infos: Some(vec![]),
+                    snapshot,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let first = sync(None).await;
+        assert!(first.error.is_none());
+        // which keeps the round from being saved, but even if it were
+        // the snapshot says nothing about what the failing agent holds
+        assert_eq!(first.stats.failed_agent_fetch_count, 1);
+        let snapshot = BloomSnapshot::new(&Default::default(), &first.op_watermarks);
+        assert!(snapshot.op_keys_of(&failing).is_none());
+
+        // after a restart the failing agent fetches everything,
+        // and is handed the op only the holder's snapshot had
+        fail.store(false, Ordering::SeqCst);
+        fetched_since.lock().unwrap().clear();
+        let second = sync(Some(snapshot)).await;
+        assert!(second.error.is_none());
+        assert_eq!(second.stats.failed_agent_fetch_count, 0);
+        let mut fetched_since = fetched_since.lock().unwrap().clone();
+        fetched_since.sort();
+        assert_eq!(
+            fetched_since,
+            vec![(1, first.covered_until_utc_epoch_s - 1), (2, i64::MIN)]
+        );
+        assert_eq!(*sends.lock().unwrap(), vec![2]);
+        assert!(second.key_set.contains(&MetaOpKey::Op(op)));
+    }
+
+    /// an `Inner` ready to finish with `has_hash`
+    fn finishing_inner(has_hash: HasMap) -> Inner {
+        let (evt_sender, _evt_receiver) = futures::channel::mpsc::channel(1);
+        Inner {
+            space: Arc::new(KitsuneSpace::new(vec![0; 36])),
+            evt_sender,
+            tuning_params: Arc::new(Default::default()),
+            local_agents: HashSet::new(),
+            only_agents: None,
+            cached_agent_infos: None,
+            queried_agent_infos: None,
+            data_map: HashMap::new(),
+            has_hash,
+            has_remaining_work: true,
+            diverged_op_count: 0,
+            stats: LocalSyncStats::default(),
+            snapshot: None,
+            op_watermarks: OpWatermarks::default(),
+            op_received_at: HashMap::new(),
+            arc: None,
+            agent_arcs: HashMap::new(),
+            covered_until_utc_epoch_s: 0,
+        }
+    }
+
+    /// an `Inner` for the agents in `has_hash` to sync with each other
+    /// through `evt_sender`
+    fn syncing_inner(
+        evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+        has_hash: HasMap,
+    ) -> Inner {
+        Inner {
+            evt_sender,
+            local_agents: has_hash.agents().map(|(agent, _)| agent.clone()).collect(),
+            has_remaining_work: false,
+            ..finishing_inner(has_hash)
+        }
+    }
+
+    /// answer the events sent to `evt_receiver` with `answer`, or for any
+    /// event it hands back, as if the agents held no ops but had the data
+    /// of every op asked for and acknowledged every op gossiped to them
+    fn spawn_event_handler(
+        mut evt_receiver: futures::channel::mpsc::Receiver<KitsuneP2pEvent>,
+        mut answer: impl FnMut(KitsuneP2pEvent) -> Option<KitsuneP2pEvent> + Send + 'static,
+    ) {
+        tokio::task::spawn(async move {
+            while let Some(evt) = evt_receiver.next().await {
+                match answer(evt) {
+                    Some(KitsuneP2pEvent::FetchOpHashesForConstraints { respond, .. }) => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    Some(KitsuneP2pEvent::FetchOpHashData { respond, input, .. }) => {
+                        respond.r(Ok(async move {
+                            Ok(input
+                                .op_hashes
+                                .into_iter()
+                                .map(|op_hash| (op_hash, vec![1, 2, 3]))
+                                .collect())
+                        }
+                        .boxed()
+                        .into()));
+                    }
+                    Some(KitsuneP2pEvent::Gossip { respond, .. }) => {
+                        respond.r(Ok(async move { Ok(true) }.boxed().into()));
+                    }
+                    _ => (),
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn finish_counts_ops_local_agents_are_missing() {
+        let key = |i: u8| Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+        let mut has_hash = HasMap::default();
+        for i in 1..=3 {
+            has_hash.insert(&Arc::new(KitsuneAgent::new(vec![0; 36])), key(i));
+        }
+        has_hash.insert(&Arc::new(KitsuneAgent::new(vec![1; 36])), key(1));
+        let inner = finishing_inner(has_hash);
+        assert_eq!(inner.finish(None).stats.outstanding_op_count, 2);
+    }
+
+    #[test]
+    fn finish_builds_the_bloom_from_the_key_set() {
+        let key = |i: u8| Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+        let chunk_count = KitsuneP2pTuningParams::default().gossip_bloom_chunk_count as usize;
+        let mut has_hash = HasMap::default();
+        for i in 1..=3 {
+            has_hash.insert(&Arc::new(KitsuneAgent::new(vec![0; 36])), key(i));
+        }
+        let result = finishing_inner(has_hash).finish(None);
+        assert_eq!(result.key_set.len(), 3);
+        assert!(result.key_set.iter().all(|k| result.bloom.check(k)));
+
+        // without local agents both are empty, whether the bloom
+        // is built fresh or rebuilt from a previous one
+        let result = finishing_inner(HasMap::default()).finish(None);
+        assert!(result.key_set.is_empty());
+        let bloom = result.bloom.into_bloom().expect("built a bloom");
+        assert_eq!(bloom.encode().len(), chunk_count);
+        assert!(!bloom.check(&key(1)));
+        let reuse = BloomChunks::build(&(1..=3).map(key).collect(), chunk_count, 0);
+        let result = finishing_inner(HasMap::default()).finish(Some(reuse));
+        assert!(result.key_set.is_empty());
+        let bloom = result.bloom.into_bloom().expect("built a bloom");
+        assert_eq!(bloom.encode().len(), chunk_count);
+        assert!(!bloom.check(&key(1)));
+    }
+
+    #[test]
+    fn finish_uses_exact_keys_below_the_min_key_count() {
+        let key = |i: u8| Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+        let inner_with_min = |min_key_count: u32| {
+            let mut has_hash = HasMap::default();
+            for i in 1..=3 {
+                has_hash.insert(&Arc::new(KitsuneAgent::new(vec![0; 36])), key(i));
+            }
+            let mut tuning_params =
+                kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+            tuning_params.gossip_bloom_min_key_count = min_key_count;
+            Inner {
+                tuning_params: Arc::new(tuning_params),
+                ..finishing_inner(has_hash)
+            }
+        };
+
+        let result = inner_with_min(4).finish(None);
+        match &result.bloom {
+            KeyFilter::Exact(keys) => assert_eq!(keys, &result.key_set),
+            KeyFilter::Bloom(_) => panic!("expected exact keys below the min key count"),
+        }
+        assert!(!result.bloom.check(&key(4)));
+
+        let result = inner_with_min(3).finish(None);
+        assert!(matches!(result.bloom, KeyFilter::Bloom(_)));
+        assert!(result.key_set.iter().all(|k| result.bloom.check(k)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_from_holders_falls_back_to_other_holders() {
+        let agent = |i: u8| Arc::new(KitsuneAgent::new(vec![i; 36]));
+        let pruned = agent(0);
+        let holder = agent(1);
+        let op_hash = Arc::new(KitsuneOpHash::new(vec![9; 36]));
+        let key = Arc::new(MetaOpKey::Op(op_hash.clone()));
+
+        let (mut evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        {
+            let holder = holder.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                // only `holder` still has the op
+                KitsuneP2pEvent::FetchOpHashData { respond, input, .. }
+                    if input.agent != holder =>
+                {
+                    respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    None
+                }
+                evt => Some(evt),
+            });
+        }
+
+        let has_map = |holders: &[&Arc<KitsuneAgent>]| {
+            let mut has_hash = HasMap::default();
+            for holder in holders {
+                has_hash.insert(holder, key.clone());
+            }
+            // an agent that doesn't hold the op is never asked
+            has_hash.add_agent(agent(2));
+            has_hash
+        };
+        let has_hash = has_map(&[&pruned, &holder]);
+        let space = Arc::new(KitsuneSpace::new(vec![0; 36]));
+
+        let data = fetch_from_holders(&mut evt_sender, &space, &pruned, &has_hash, &key, None)
+            .await
+            .unwrap();
+        assert!(matches!(&*data, MetaOpData::Op(_, d) if d == &vec![1, 2, 3]));
+
+        // with no other holder the miss is an error
+        let has_hash = has_map(&[&pruned]);
+        assert!(
+            fetch_from_holders(&mut evt_sender, &space, &pruned, &has_hash, &key, None)
                 .await
-                .map_err(KitsuneError::other)?;
+                .is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn evt_channel_stalls_are_measured() {
+        let (mut evt_sender, mut evt_receiver) = futures::channel::mpsc::channel::<()>(0);
+        let mut stall = std::time::Duration::default();
+
+        // room in the channel, no waiting
+        evt_channel_ready(&mut evt_sender, &mut stall, None, "test")
+            .await
+            .unwrap();
+        assert_eq!(stall, std::time::Duration::default());
+
+        // a full channel waits until the receiver catches up
+        evt_sender.try_send(()).unwrap();
+        let receiver = tokio::task::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            evt_receiver.next().await
+        });
+        evt_channel_ready(&mut evt_sender, &mut stall, None, "test")
+            .await
+            .unwrap();
+        assert!(stall >= std::time::Duration::from_millis(50));
+        assert_eq!(receiver.await.unwrap(), Some(()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn phase_durations_are_measured() {
+        const FETCH_TIME: std::time::Duration = std::time::Duration::from_millis(200);
+
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        spawn_event_handler(evt_receiver, |evt| match evt {
+            KitsuneP2pEvent::FetchOpHashesForConstraints { respond, .. } => {
+                respond.r(Ok(async move {
+                    tokio::time::sleep(FETCH_TIME).await;
+                    Ok(vec![])
+                }
+                .boxed()
+                .into()));
+                None
+            }
+            evt => Some(evt),
+        });
+
+        let result = step_2_local_sync_inner(
+            Arc::new(KitsuneSpace::new(vec![0; 36])),
+            evt_sender,
+            Arc::new(Default::default()),
+            vec![Arc::new(KitsuneAgent::new(vec![1; 36]))]
+                .into_iter()
+                .collect(),
+            LocalSyncInput {
+                cached_agent_infos: Some(vec![]),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // the slow fetch shows up in the op collection phase alone
+        assert!(result.stats.collect_local_ops_duration >= FETCH_TIME);
+        assert!(result.stats.collect_local_agents_duration < FETCH_TIME);
+        assert!(result.stats.local_sync_duration < FETCH_TIME);
+        assert!(result.stats.finish_duration < FETCH_TIME);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_events_time_out() {
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        spawn_event_handler(evt_receiver, |evt| match evt {
+            // op data is never handed back
+            KitsuneP2pEvent::FetchOpHashData { respond, .. } => {
+                respond.r(Ok(futures::future::pending().boxed().into()));
+                None
+            }
+            evt => Some(evt),
+        });
 
-            if op.len() != 1 {
-                return Err(format!("Error fetching op {:?}", &key).into());
+        let holder = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let mut has_hash = HasMap::default();
+        let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![9; 36]))));
+        // a second holder isn't asked once the first has stalled
+        for agent in vec![holder.clone(), Arc::new(KitsuneAgent::new(vec![2; 36]))] {
+            has_hash.insert(&agent, key.clone());
+        }
+        has_hash.add_agent(missing.clone());
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_event_timeout_ms = 50;
+        let mut inner = Inner {
+            tuning_params: Arc::new(tuning_params),
+            local_agents: vec![holder, missing.clone()].into_iter().collect(),
+            ..syncing_inner(evt_sender, has_hash)
+        };
+
+        let start = std::time::Instant::now();
+        let error = inner.local_sync().await.unwrap_err();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        assert!(matches!(
+            error.kind(),
+            KitsuneErrorKind::EventTimedOut {
+                event: "fetch_op_hash_data"
             }
+        ));
+        assert!(inner.has_remaining_work);
+        assert_eq!(inner.has_hash.keys_of(&missing).count(), 0);
+    }
 
-            let (key, data) = op.remove(0);
-            let data = Arc::new(MetaOpData::Op(key.clone(), data));
-            let key = Arc::new(MetaOpKey::Op(key));
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_overlaps_fetches_and_sends() {
+        const OP_COUNT: u8 = 10;
+        const DELAY: std::time::Duration = std::time::Duration::from_millis(20);
 
-            map.insert(key, data.clone());
-            Ok(data)
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        spawn_event_handler(evt_receiver, |evt| match evt {
+            KitsuneP2pEvent::FetchOpHashData { respond, input, .. } => {
+                respond.r(Ok(async move {
+                    tokio::time::sleep(DELAY).await;
+                    Ok(input
+                        .op_hashes
+                        .into_iter()
+                        .map(|op_hash| (op_hash, vec![1, 2, 3]))
+                        .collect())
+                }
+                .boxed()
+                .into()));
+                None
+            }
+            KitsuneP2pEvent::Gossip { respond, .. } => {
+                respond.r(Ok(async move {
+                    tokio::time::sleep(DELAY).await;
+                    Ok(true)
+                }
+                .boxed()
+                .into()));
+                None
+            }
+            evt => Some(evt),
+        });
+
+        let holder = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let mut has_hash = HasMap::default();
+        for i in 0..OP_COUNT {
+            let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+            has_hash.insert(&holder, key);
         }
-        // the query agents api returns all the data,
-        // so we should already be fully pre-populated.
-        MetaOpKey::Agent(_, _) => unreachable!(),
+        has_hash.add_agent(missing.clone());
+        let mut inner = syncing_inner(evt_sender, has_hash);
+
+        let start = std::time::Instant::now();
+        inner.local_sync().await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(inner.has_hash.keys_of(&missing).count(), OP_COUNT as usize);
+        assert_eq!(inner.data_map.len(), OP_COUNT as usize);
+        // fetching then sending each op in turn takes at least this long
+        let serial = DELAY * 2 * OP_COUNT as u32;
+        assert!(elapsed < serial, "{:?} is no faster than serial", elapsed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_only_counts_acknowledged_ops() {
+        const OP_COUNT: u8 = 3;
+
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        spawn_event_handler(evt_receiver, |evt| match evt {
+            // op 0 never lands
+            KitsuneP2pEvent::Gossip {
+                respond, op_hash, ..
+            } if op_hash[0] == 0 => {
+                respond.r(Ok(async move { Ok(false) }.boxed().into()));
+                None
+            }
+            evt => Some(evt),
+        });
+
+        let holder = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let mut has_hash = HasMap::default();
+        for i in 0..OP_COUNT {
+            let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+            has_hash.insert(&holder, key);
+        }
+        has_hash.add_agent(missing.clone());
+        let mut inner = syncing_inner(evt_sender, has_hash);
+
+        inner.local_sync().await.unwrap();
+
+        let unacknowledged = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![0; 36]))));
+        let synced: Vec<_> = inner.has_hash.keys_of(&missing).collect();
+        assert_eq!(synced.len(), OP_COUNT as usize - 1);
+        assert!(!synced.contains(&&unacknowledged));
+        assert_eq!(inner.stats.unacknowledged_op_count, 1);
+        assert_eq!(inner.diverged_op_count, OP_COUNT as usize);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_continues_past_failed_op_fetches() {
+        const OP_COUNT: u8 = 3;
+
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        spawn_event_handler(evt_receiver, |evt| match evt {
+            KitsuneP2pEvent::FetchOpHashData { respond, input, .. } => {
+                // op 0 is never found
+                let ops: Vec<_> = input
+                    .op_hashes
+                    .into_iter()
+                    .filter(|op_hash| op_hash[0] != 0)
+                    .map(|op_hash| (op_hash, vec![1, 2, 3]))
+                    .collect();
+                respond.r(Ok(async move { Ok(ops) }.boxed().into()));
+                None
+            }
+            evt => Some(evt),
+        });
+
+        let holder = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let mut has_hash = HasMap::default();
+        for i in 0..OP_COUNT {
+            let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+            has_hash.insert(&holder, key);
+        }
+        has_hash.add_agent(missing.clone());
+        let mut inner = syncing_inner(evt_sender, has_hash);
+
+        inner.local_sync().await.unwrap();
+
+        let unfetched = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![0; 36]))));
+        let synced: Vec<_> = inner.has_hash.keys_of(&missing).collect();
+        assert_eq!(synced.len(), OP_COUNT as usize - 1);
+        assert!(!synced.contains(&&unfetched));
+        assert_eq!(inner.diverged_op_count, OP_COUNT as usize);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_can_be_skipped() {
+        const OP_COUNT: u8 = 5;
+
+        let (evt_sender, mut evt_receiver) = futures::channel::mpsc::channel(10);
+        let holder = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let mut has_hash = HasMap::default();
+        for i in 0..OP_COUNT {
+            let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![i; 36]))));
+            has_hash.insert(&holder, key);
+        }
+        has_hash.add_agent(missing.clone());
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_skip_local_sync = true;
+        let mut inner = Inner {
+            tuning_params: Arc::new(tuning_params),
+            ..syncing_inner(evt_sender, has_hash)
+        };
+
+        inner.local_sync().await.unwrap();
+        // nothing was fetched or handed over
+        assert!(evt_receiver.next().now_or_never().is_none());
+        assert_eq!(inner.has_hash.keys_of(&missing).count(), 0);
+
+        // the bloom still covers every local op
+        let result = inner.finish(None);
+        assert_eq!(result.key_set.len(), OP_COUNT as usize);
+        assert!(result.key_set.iter().all(|key| result.bloom.check(key)));
+        assert_eq!(result.diverged_op_count, 0);
+        assert_eq!(result.stats.outstanding_op_count, OP_COUNT as usize);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_sends_in_agent_order() {
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        let sends = Arc::new(std::sync::Mutex::new(Vec::new()));
+        {
+            let sends = sends.clone();
+            spawn_event_handler(evt_receiver, move |evt| {
+                if let KitsuneP2pEvent::Gossip {
+                    to_agent,
+                    from_agent,
+                    ..
+                } = &evt
+                {
+                    sends.lock().unwrap().push((from_agent[0], to_agent[0]));
+                }
+                Some(evt)
+            });
+        }
+
+        // each agent holds an op the others are missing
+        let mut has_hash = HasMap::default();
+        for i in &[5u8, 1, 3] {
+            let agent = Arc::new(KitsuneAgent::new(vec![*i; 36]));
+            let key = Arc::new(MetaOpKey::Op(Arc::new(KitsuneOpHash::new(vec![*i; 36]))));
+            has_hash.insert(&agent, key);
+        }
+        let mut inner = syncing_inner(evt_sender, has_hash);
+
+        inner.local_sync().await.unwrap();
+        assert_eq!(
+            *sends.lock().unwrap(),
+            vec![(1, 3), (1, 5), (3, 1), (3, 5), (5, 1), (5, 3)]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_sync_sends_newest_ops_first() {
+        let holder = Arc::new(KitsuneAgent::new(vec![1; 36]));
+        let missing = Arc::new(KitsuneAgent::new(vec![2; 36]));
+        let (evt_sender, evt_receiver) = futures::channel::mpsc::channel(10);
+        let sends = Arc::new(std::sync::Mutex::new(Vec::new()));
+        {
+            let holder = holder.clone();
+            let sends = sends.clone();
+            spawn_event_handler(evt_receiver, move |evt| match evt {
+                KitsuneP2pEvent::FetchOpHashesForConstraints { respond, input, .. }
+                    if input.agent == holder =>
+                {
+                    // (op, received at)
+                    let held = [
+                        (1u8, Some(2000)),
+                        (2, Some(3000)),
+                        (3, Some(1000)),
+                        (4, None),
+                    ];
+                    let ops: Vec<_> = held
+                        .iter()
+                        .map(|(n, t)| (Arc::new(KitsuneOpHash::new(vec![*n; 36])), *t))
+                        .collect();
+                    respond.r(Ok(async move { Ok(ops) }.boxed().into()));
+                    None
+                }
+                KitsuneP2pEvent::Gossip { ref op_hash, .. } => {
+                    sends.lock().unwrap().push(op_hash[0]);
+                    Some(evt)
+                }
+                evt => Some(evt),
+            });
+        }
+
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_op_order = "newest-first".to_string();
+        let result = step_2_local_sync_inner(
+            Arc::new(KitsuneSpace::new(vec![0; 36])),
+            evt_sender,
+            Arc::new(tuning_params),
+            vec![holder, missing].into_iter().collect(),
+            LocalSyncInput {
+                cached_agent_infos: Some(vec![]),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(result.error.is_none());
+        // ops we don't know the time of go last
+        assert_eq!(*sends.lock().unwrap(), vec![2, 1, 3, 4]);
+        assert_eq!(result.op_received_at.len(), 3);
     }
 }
@@ -95,10 +95,31 @@ pub(crate) fn danger_mutex_locked_sync_step_3_initiate_inner(
     }
 
     if let Some((cert, url)) = initiate {
-        let gossip = encode_bloom_filter(&inner.local_bloom);
-        let bloom_byte_count = gossip.len();
-        tracing::info!(%url, ?cert, %bloom_byte_count, "initiating gossip");
-        let gossip = GossipWire::initiate(gossip);
+        let gossip = match &inner.local_bloom {
+            KeyFilter::Exact(keys) => {
+                // the remote drops our last filters for these keys,
+                // so the next filters must be sent in full
+                inner.sent_bloom_hashes.remove(&cert);
+                let key_count = keys.len();
+                tracing::info!(%url, ?cert, %key_count, "initiating gossip, exact keys");
+                GossipWire::initiate_exact(keys.iter().cloned().collect())
+            }
+            KeyFilter::Bloom(bloom) => {
+                let filters = bloom.encode();
+                match unchanged_bloom_hash(&mut inner.sent_bloom_hashes, &cert, &filters) {
+                    Some(bloom_hash) => {
+                        tracing::info!(%url, ?cert, %bloom_hash, "initiating gossip, bloom unchanged");
+                        GossipWire::initiate_unchanged(bloom_hash)
+                    }
+                    None => {
+                        let bloom_chunk_count = filters.len();
+                        let bloom_byte_count: usize = filters.iter().map(|f| f.len()).sum();
+                        tracing::info!(%url, ?cert, %bloom_chunk_count, %bloom_byte_count, "initiating gossip");
+                        initiate_with_filters(filters)
+                    }
+                }
+            }
+        };
         inner.outgoing.push((cert, HowToConnect::Url(url), gossip));
     }
 
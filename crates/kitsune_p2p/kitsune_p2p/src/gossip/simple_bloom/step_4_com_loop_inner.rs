@@ -1,5 +1,7 @@
 use super::*;
+use futures::future::FutureExt;
 use kitsune_p2p_types::codec::*;
+use std::sync::atomic::Ordering;
 
 pub(crate) async fn step_4_com_loop_inner_outgoing(
     inner: &Share<SimpleBloomModInner>,
@@ -31,6 +33,15 @@ pub(crate) async fn step_4_com_loop_inner_outgoing(
     Ok(())
 }
 
+/// The filters a remote sent with its initiate / accept
+enum RemoteBloom {
+    Full(Vec<PoolBuf>),
+    /// the keys of all the remote's ops, in place of filters
+    Exact(Vec<Arc<MetaOpKey>>),
+    /// unchanged since the ones with this hash
+    Unchanged(u64),
+}
+
 pub(crate) async fn step_4_com_loop_inner_incoming(
     inner: &Share<SimpleBloomModInner>,
     con: Tx2ConHnd<wire::Wire>,
@@ -39,7 +50,7 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
     use crate::event::*;
 
     // parse the message
-    let (send_accept, remote_filter) = match gossip {
+    let (send_accept, remote_filters): (bool, RemoteBloom) = match gossip {
         GossipWire::Initiate(Initiate { filter }) => {
             let bloom_byte_count = filter.len();
             tracing::debug!(
@@ -47,7 +58,29 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
                 "incoming 'Initiate'",
             );
 
-            (true, filter)
+            (true, RemoteBloom::Full(vec![filter]))
+        }
+        GossipWire::InitiateChunked(InitiateChunked { filters }) => {
+            let bloom_chunk_count = filters.len();
+            let bloom_byte_count: usize = filters.iter().map(|f| f.len()).sum();
+            tracing::debug!(
+                %bloom_chunk_count,
+                %bloom_byte_count,
+                "incoming 'InitiateChunked'",
+            );
+
+            (true, RemoteBloom::Full(filters))
+        }
+        GossipWire::InitiateUnchanged(InitiateUnchanged { bloom_hash }) => {
+            tracing::debug!(%bloom_hash, "incoming 'InitiateUnchanged'");
+
+            (true, RemoteBloom::Unchanged(bloom_hash))
+        }
+        GossipWire::InitiateExact(InitiateExact { keys }) => {
+            let key_count = keys.len();
+            tracing::debug!(%key_count, "incoming 'InitiateExact'");
+
+            (true, RemoteBloom::Exact(keys))
         }
         GossipWire::Accept(Accept { filter }) => {
             let bloom_byte_count = filter.len();
@@ -56,7 +89,43 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
                 "incoming 'Accept'",
             );
 
-            (false, filter)
+            (false, RemoteBloom::Full(vec![filter]))
+        }
+        GossipWire::AcceptChunked(AcceptChunked { filters }) => {
+            let bloom_chunk_count = filters.len();
+            let bloom_byte_count: usize = filters.iter().map(|f| f.len()).sum();
+            tracing::debug!(
+                %bloom_chunk_count,
+                %bloom_byte_count,
+                "incoming 'AcceptChunked'",
+            );
+
+            (false, RemoteBloom::Full(filters))
+        }
+        GossipWire::AcceptUnchanged(AcceptUnchanged { bloom_hash }) => {
+            tracing::debug!(%bloom_hash, "incoming 'AcceptUnchanged'");
+
+            (false, RemoteBloom::Unchanged(bloom_hash))
+        }
+        GossipWire::AcceptExact(AcceptExact { keys }) => {
+            let key_count = keys.len();
+            tracing::debug!(%key_count, "incoming 'AcceptExact'");
+
+            (false, RemoteBloom::Exact(keys))
+        }
+        GossipWire::BloomUnknown(BloomUnknown { bloom_hash }) => {
+            tracing::debug!(%bloom_hash, "incoming 'BloomUnknown'");
+
+            // send our filters in full next time
+            let peer_cert = con.peer_cert();
+            inner.share_mut(move |i, _| {
+                if i.sent_bloom_hashes.get(&peer_cert) == Some(&bloom_hash) {
+                    i.sent_bloom_hashes.remove(&peer_cert);
+                }
+                Ok(())
+            })?;
+
+            return Ok(());
         }
         GossipWire::Chunk(Chunk { finished, chunks }) => {
             let chunk_count = chunks.len();
@@ -67,6 +136,8 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
             );
 
             // parse/integrate the chunks
+            let unacknowledged = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let unacknowledged_clone = unacknowledged.clone();
             let futs = inner.share_mut(move |i, _| {
                 if let Some(tgt_cert) = i.initiate_tgt.clone() {
                     if finished && con.peer_cert() == tgt_cert {
@@ -80,15 +151,31 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
                     for agent in i.local_agents.iter() {
                         match &*chunk {
                             MetaOpData::Op(key, data) => {
-                                futs.push(i.evt_sender.gossip(
+                                let gossip = i.evt_sender.gossip(
                                     i.space.clone(),
                                     agent.clone(),
                                     agent.clone(), // TODO - from??
                                     key.clone(),
                                     data.clone(),
-                                ));
+                                );
+                                // ops a local agent doesn't acknowledge
+                                // are handed to it again by the local sync
+                                let unacknowledged = unacknowledged_clone.clone();
+                                futs.push(
+                                    async move {
+                                        if !gossip.await? {
+                                            unacknowledged.fetch_add(1, Ordering::Relaxed);
+                                        }
+                                        Ok(())
+                                    }
+                                    .boxed()
+                                    .into(),
+                                );
                             }
                             MetaOpData::Agent(agent_info_signed) => {
+                                // the agent store is about to change,
+                                // don't keep using the old agent infos
+                                i.agent_info_cache = None;
                                 // TODO - we actually only need to do this
                                 // once, since the agent store is shared...
                                 futs.push(i.evt_sender.put_agent_info_signed(
@@ -114,30 +201,104 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
                     .await
                     .map_err(KitsuneError::other)?;
             }
+            let unacknowledged_op_count = unacknowledged.load(Ordering::Relaxed);
+            if unacknowledged_op_count > 0 {
+                tracing::info!(
+                    %unacknowledged_op_count,
+                    "local agents didn't acknowledge gossiped ops, local sync will hand them over",
+                );
+            }
 
             return Ok(());
         }
     };
 
-    let remote_filter = decode_bloom_filter(&remote_filter);
-
     // send accept if applicable / gather the keys the remote needs
     let con_clone = con.clone();
     let out_keys = inner.share_mut(move |i, _| {
+        let peer_cert = con_clone.peer_cert();
+
         // for now, just always accept gossip initiates
         if send_accept {
-            let local_filter = encode_bloom_filter(&i.local_bloom);
-            let gossip = GossipWire::accept(local_filter);
-            let peer_cert = con_clone.peer_cert();
-            i.outgoing
-                .push((peer_cert, HowToConnect::Con(con_clone), gossip));
+            let gossip = match &i.local_bloom {
+                KeyFilter::Exact(keys) => {
+                    // the remote drops our last filters for these keys,
+                    // so the next filters must be sent in full
+                    i.sent_bloom_hashes.remove(&peer_cert);
+                    GossipWire::accept_exact(keys.iter().cloned().collect())
+                }
+                KeyFilter::Bloom(bloom) => {
+                    let local_filters = bloom.encode();
+                    match unchanged_bloom_hash(&mut i.sent_bloom_hashes, &peer_cert, &local_filters)
+                    {
+                        Some(bloom_hash) => GossipWire::accept_unchanged(bloom_hash),
+                        None => accept_with_filters(local_filters),
+                    }
+                }
+            };
+            i.outgoing.push((
+                peer_cert.clone(),
+                HowToConnect::Con(con_clone.clone()),
+                gossip,
+            ));
         }
 
+        let ttl =
+            std::time::Duration::from_millis(i.tuning_params.gossip_remote_bloom_ttl_ms as u64);
+        expire_remote_blooms(&mut i.remote_blooms, ttl);
+        let received_at = std::time::Instant::now();
+
+        // the remote's chunk count may differ from ours, each key is
+        // checked against whichever remote chunk covers its location
+        let remote_known = match remote_filters {
+            RemoteBloom::Full(filters) => {
+                let bloom_hash = bloom_hash(&filters);
+                let remote_filter = BloomChunks::decode(&filters).map(KeyFilter::Bloom);
+                i.remote_blooms.insert(
+                    peer_cert.clone(),
+                    (received_at, Some(bloom_hash), remote_filter),
+                );
+                true
+            }
+            RemoteBloom::Exact(keys) => {
+                let remote_filter = KeyFilter::Exact(keys.into_iter().collect());
+                i.remote_blooms
+                    .insert(peer_cert.clone(), (received_at, None, Some(remote_filter)));
+                true
+            }
+            RemoteBloom::Unchanged(bloom_hash) => {
+                let known = matches!(
+                    i.remote_blooms.get(&peer_cert),
+                    Some((_, Some(known_hash), _)) if *known_hash == bloom_hash
+                );
+                if !known {
+                    // we can't tell what the remote has this round,
+                    // have it send its filters in full next time
+                    tracing::debug!(?peer_cert, %bloom_hash, "unknown unchanged remote bloom");
+                    let gossip = GossipWire::bloom_unknown(bloom_hash);
+                    i.outgoing
+                        .push((peer_cert.clone(), HowToConnect::Con(con_clone), gossip));
+                }
+                known
+            }
+        };
+
         let mut out_keys = Vec::new();
+        if !remote_known {
+            return Ok(out_keys);
+        }
+        let remote_filter = i
+            .remote_blooms
+            .get(&peer_cert)
+            .and_then(|(_, _, remote_filter)| remote_filter.as_ref());
 
         // find the keys for data the remote doesn't have
         for key in i.local_key_set.iter() {
-            if !remote_filter.check(key) {
+            let remote_has = match &remote_filter {
+                Some(remote_filter) => remote_filter.check(key),
+                None => false,
+            };
+            if !remote_has {
                 out_keys.push(key.clone());
             }
         }
@@ -182,6 +343,15 @@ pub(crate) async fn step_4_com_loop_inner_incoming(
         return Ok(());
     }
 
+    inner.share_mut(|i, _| {
+        let op_order = op_order(&i.tuning_params);
+        let received_at = &i.local_op_received_at;
+        out_data.sort_by_cached_key(|data| {
+            op_order.data_priority(data, received_at.get(&data.key()).copied())
+        });
+        Ok(())
+    })?;
+
     // build up / queue up outgoing messages with the data
     let mut gossip = Vec::new();
     let mut chunks = Vec::new();
@@ -284,13 +454,14 @@ async fn data_map_get(
     use crate::event::*;
 
     // first, see if we already have the data
-    let (space, agent, evt_sender, maybe_data) = inner.share_mut(|i, _| {
+    let (space, agent, evt_sender, maybe_data, known_missing) = inner.share_mut(|i, _| {
         // erm, just using a random agent??
         Ok((
             i.space.clone(),
             i.local_agents.iter().next().unwrap().clone(),
             i.evt_sender.clone(),
             i.local_data_map.get(key).cloned(),
+            i.known_missing_ops.contains(key),
         ))
     })?;
 
@@ -299,6 +470,12 @@ async fn data_map_get(
         return Ok(maybe_data);
     }
 
+    // or if we already looked and it wasn't there, don't look again
+    // until the next local sync
+    if known_missing {
+        return Ok(None);
+    }
+
     let op_key = match &**key {
         MetaOpKey::Op(key) => key.clone(),
         // we should already have all this data...
@@ -319,6 +496,12 @@ async fn data_map_get(
     };
 
     if op.is_empty() {
+        // remember it's missing, but not for a failed fetch,
+        // which might succeed if tried again
+        inner.share_mut(|i, _| {
+            i.known_missing_ops.insert(key.clone());
+            Ok(())
+        })?;
         return Ok(None);
     }
 
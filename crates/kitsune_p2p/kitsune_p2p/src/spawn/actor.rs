@@ -351,7 +351,7 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         from_agent: Arc<KitsuneAgent>,
         op_hash: Arc<KitsuneOpHash>,
         op_data: Vec<u8>,
-    ) -> KitsuneP2pEventHandlerResult<()> {
+    ) -> KitsuneP2pEventHandlerResult<bool> {
         Ok(self
             .evt_sender
             .gossip(space, to_agent, from_agent, op_hash, op_data))
@@ -360,7 +360,7 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
     fn handle_fetch_op_hashes_for_constraints(
         &mut self,
         input: FetchOpHashesForConstraintsEvt,
-    ) -> KitsuneP2pEventHandlerResult<Vec<Arc<KitsuneOpHash>>> {
+    ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<KitsuneOpHash>, Option<i64>)>> {
         Ok(self.evt_sender.fetch_op_hashes_for_constraints(input))
     }
 
@@ -488,4 +488,71 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_status(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<actor::GossipStatus> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.gossip_status(space).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_set_gossip_paused(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        paused: bool,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.set_gossip_paused(space, paused).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_subscribe_gossip_events(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<tokio::sync::broadcast::Receiver<actor::GossipEvent>> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.subscribe_gossip_events(space).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_set_agent_arc(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        arc: dht_arc::DhtArc,
+    ) -> KitsuneP2pHandlerResult<dht_arc::DhtArc> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.set_agent_arc(space, agent, arc).await
+        }
+        .boxed()
+        .into())
+    }
 }
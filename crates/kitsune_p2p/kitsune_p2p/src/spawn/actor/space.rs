@@ -4,6 +4,7 @@ use ghost_actor::dependencies::tracing;
 use ghost_actor::dependencies::tracing_futures::Instrument;
 use kitsune_p2p_mdns::*;
 use kitsune_p2p_types::codec::{rmp_decode, rmp_encode};
+use kitsune_p2p_types::dht_arc::{DhtArc, DhtLocation, MAX_HALF_LENGTH};
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
 
@@ -37,7 +38,9 @@ pub(crate) async fn spawn_space(
     ghost_actor::GhostSender<SpaceInternal>,
     KitsuneP2pEventReceiver,
 )> {
-    let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
+    let (evt_send, evt_recv) = futures::channel::mpsc::channel(
+        config.tuning_params.gossip_event_channel_capacity as usize,
+    );
 
     let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
 
@@ -97,6 +100,7 @@ impl SpaceInternalHandler for Space {
         let mut mdns_handles = self.mdns_handles.clone();
         let network_type = self.config.network_type.clone();
         let agent_list: Vec<Arc<KitsuneAgent>> = self.local_joined_agents.iter().cloned().collect();
+        let agent_arcs = self.agent_arcs.clone();
         let bound_url = self.this_addr.clone();
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
@@ -104,6 +108,11 @@ impl SpaceInternalHandler for Space {
         Ok(async move {
             let urls = vec![bound_url];
             for agent in agent_list {
+                // agents whose arc was never set don't declare one
+                let dht_storage_arc_half_length = agent_arcs
+                    .get(&agent)
+                    .map(|arc| arc.half_length)
+                    .unwrap_or(0);
                 let agent_info = crate::types::agent_store::AgentInfo::new(
                     (*space).clone(),
                     (*agent).clone(),
@@ -112,7 +121,7 @@ impl SpaceInternalHandler for Space {
                     expires_after,
                 )
                 .with_meta_info(crate::types::agent_store::AgentMetaInfo {
-                    dht_storage_arc_half_length: 0,
+                    dht_storage_arc_half_length,
                 })?;
                 let mut data = Vec::new();
                 rmp_encode(&mut data, &agent_info)?;
@@ -306,6 +315,7 @@ impl KitsuneP2pHandler for Space {
         agent: Arc<KitsuneAgent>,
     ) -> KitsuneP2pHandlerResult<()> {
         self.local_joined_agents.remove(&agent);
+        self.agent_arcs.remove(&agent);
         self.gossip_mod.local_agent_leave(agent);
         Ok(async move { Ok(()) }.boxed().into())
     }
@@ -435,6 +445,66 @@ impl KitsuneP2pHandler for Space {
             Ok(inner_fut)
         }
     }
+
+    fn handle_gossip_status(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<actor::GossipStatus> {
+        let status = self.gossip_mod.status();
+        Ok(async move { Ok(status) }.boxed().into())
+    }
+
+    fn handle_set_gossip_paused(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        paused: bool,
+    ) -> KitsuneP2pHandlerResult<()> {
+        self.gossip_mod.set_paused(paused);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_subscribe_gossip_events(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<tokio::sync::broadcast::Receiver<actor::GossipEvent>> {
+        let events = self.gossip_mod.subscribe_events();
+        Ok(async move { Ok(events) }.boxed().into())
+    }
+
+    fn handle_set_agent_arc(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        arc: DhtArc,
+    ) -> KitsuneP2pHandlerResult<DhtArc> {
+        if !self.local_joined_agents.contains(&agent) {
+            return Err(KitsuneP2pError::RoutingAgentError(agent));
+        }
+        if arc.center_loc != DhtLocation::from(agent.get_loc()) {
+            return Err(KitsuneP2pError::other(format!(
+                "arc {:?} is not centered on the location of agent {:?}",
+                arc, agent
+            )));
+        }
+        if arc.half_length > MAX_HALF_LENGTH {
+            return Err(KitsuneP2pError::other(format!(
+                "arc {:?} is longer than the whole dht",
+                arc
+            )));
+        }
+        let previous = self
+            .agent_arcs
+            .insert(agent.clone(), arc)
+            .unwrap_or_else(|| DhtArc::new(agent.get_loc(), MAX_HALF_LENGTH));
+        self.gossip_mod.set_agent_arc(agent, arc);
+        let fut = self.i_s.update_agent_info();
+        Ok(async move {
+            fut.await?;
+            Ok(previous)
+        }
+        .boxed()
+        .into())
+    }
 }
 
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
@@ -446,6 +516,8 @@ pub(crate) struct Space {
     pub(crate) evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     pub(crate) ep_hnd: Tx2EpHnd<wire::Wire>,
     pub(crate) local_joined_agents: HashSet<Arc<KitsuneAgent>>,
+    /// the storage arcs local agents have been set to
+    agent_arcs: HashMap<Arc<KitsuneAgent>, DhtArc>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
     mdns_handles: HashMap<Vec<u8>, Arc<AtomicBool>>,
     mdns_listened_spaces: HashSet<String>,
@@ -494,6 +566,7 @@ impl Space {
             evt_sender,
             ep_hnd,
             local_joined_agents: HashSet::new(),
+            agent_arcs: HashMap::new(),
             config,
             mdns_handles: HashMap::new(),
             mdns_listened_spaces: HashSet::new(),
@@ -201,23 +201,25 @@ impl KitsuneP2pEventHandler for AgentHarness {
         _from_agent: Arc<super::KitsuneAgent>,
         op_hash: Arc<super::KitsuneOpHash>,
         op_data: Vec<u8>,
-    ) -> KitsuneP2pEventHandlerResult<()> {
+    ) -> KitsuneP2pEventHandlerResult<bool> {
         let op_data = String::from_utf8_lossy(&op_data).to_string();
         self.harness_chan.publish(HarnessEventType::Gossip {
             op_hash: (&op_hash).into(),
             op_data: op_data.clone(),
         });
         self.gossip_store.insert(op_hash, op_data);
-        Ok(async move { Ok(()) }.boxed().into())
+        Ok(async move { Ok(true) }.boxed().into())
     }
 
     fn handle_fetch_op_hashes_for_constraints(
         &mut self,
         _input: FetchOpHashesForConstraintsEvt,
-    ) -> KitsuneP2pEventHandlerResult<Vec<Arc<super::KitsuneOpHash>>> {
+    ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<super::KitsuneOpHash>, Option<i64>)>> {
         let hashes: Vec<Arc<super::KitsuneOpHash>> = self.gossip_store.keys().cloned().collect();
         let slug_hashes: Vec<Slug> = hashes.iter().map(|h| h.into()).collect();
         tracing::trace!(?slug_hashes, "FETCH_OP_HASHES");
+        // the store doesn't record when ops were received
+        let hashes: Vec<_> = hashes.into_iter().map(|h| (h, None)).collect();
         Ok(async move { Ok(hashes) }.boxed().into())
     }
 
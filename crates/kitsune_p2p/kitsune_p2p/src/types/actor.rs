@@ -63,6 +63,107 @@ pub struct NotifyMulti {
     pub payload: Vec<u8>,
 }
 
+/// How a space's gossip looked as of its last local sync,
+/// for telling whether the local agents in the space have converged.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GossipStatus {
+    /// When the last local sync finished, in milliseconds since the
+    /// unix epoch. `None` if no local sync has finished yet.
+    pub last_local_sync_ms: Option<u64>,
+    /// How many local agents took part in the last local sync.
+    pub local_agent_count: usize,
+    /// How many ops the last local sync found one local agent
+    /// holding and another not.
+    pub synced_ops: usize,
+    /// Whether every local agent held the same ops once the last
+    /// local sync finished, i.e. their blooms are identical.
+    pub blooms_match: bool,
+    /// Roughly how many ops local agents are still missing from each
+    /// other after the last local sync, summed over the agents.
+    pub outstanding_ops: usize,
+    /// How long the last local sync spent waiting for room in the
+    /// space's event channel. If this is a large part of each sync,
+    /// `gossip_event_channel_capacity` is too small for the sync's op budget.
+    pub evt_channel_stall_ms: u64,
+    /// Whether gossip in the space is paused, see
+    /// [`KitsuneP2pSender::set_gossip_paused`]. The rest of the status
+    /// is as of the last local sync before the pause.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl GossipStatus {
+    /// The space is converged if the last local sync had nothing to sync
+    /// and left every local agent holding the same ops.
+    pub fn is_converged(&self) -> bool {
+        self.last_local_sync_ms.is_some()
+            && self.synced_ops == 0
+            && self.blooms_match
+            && self.outstanding_ops == 0
+    }
+}
+
+/// What a local sync covered, for diagnosing gossip.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct LocalSyncStats {
+    /// The dht arc each local agent taking part had its ops collected over.
+    pub agent_arcs: Vec<AgentArcStats>,
+    /// How many ops and agent infos the resulting data map holds.
+    pub data_map_len: usize,
+    /// Roughly how many bytes the resulting data map holds, counting
+    /// the op hashes, op data and agent infos but not the map's own overhead.
+    pub data_map_bytes: usize,
+    /// How many ops the local agents were still missing from each other
+    /// once the sync finished, summed over the agents.
+    /// 0 means every local agent holds the same ops.
+    pub outstanding_op_count: usize,
+    /// How many ops were handed to a local agent which didn't acknowledge
+    /// them, so they are still counted as missing and handed over again
+    /// next round.
+    pub unacknowledged_op_count: usize,
+    /// How many local agents' op hashes couldn't be fetched,
+    /// they were left out of the sync.
+    pub failed_agent_fetch_count: usize,
+    /// How long the sync spent waiting for room in the event channel.
+    pub evt_channel_stall: std::time::Duration,
+    /// How long fetching the local agents' op hashes took, mostly
+    /// waiting on our implementor.
+    pub collect_local_ops_duration: std::time::Duration,
+    /// How long getting the agent infos took, no time at all
+    /// when the cached ones were used.
+    pub collect_local_agents_duration: std::time::Duration,
+    /// How long handing ops between the local agents took.
+    pub local_sync_duration: std::time::Duration,
+    /// How long building the bloom and the rest of the result took,
+    /// which is all cpu.
+    pub finish_duration: std::time::Duration,
+}
+
+/// The dht arc an agent had its ops collected over in a local sync.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct AgentArcStats {
+    /// The local agent.
+    pub agent: Arc<super::KitsuneAgent>,
+    /// The first dht location in the arc.
+    pub start_loc: u32,
+    /// How many dht locations the arc covers, 0 for an empty arc.
+    pub length: u64,
+}
+
+/// Something that happened in a space's gossip,
+/// see [`KitsuneP2pSender::subscribe_gossip_events`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub enum GossipEvent {
+    /// A local sync finished.
+    LocalSyncFinished {
+        /// How the space's gossip looks now, as
+        /// [`KitsuneP2pSender::gossip_status`] would report it.
+        status: GossipStatus,
+        /// What the local sync covered.
+        stats: LocalSyncStats,
+    },
+}
+
 ghost_actor::ghost_chan! {
     /// The KitsuneP2pSender allows async remote-control of the KitsuneP2p actor.
     pub chan KitsuneP2p<super::KitsuneP2pError> {
@@ -87,5 +188,26 @@ ghost_actor::ghost_chan! {
         /// Returns an approximate number of nodes reached.
         /// The remote sides will see these messages as "Notify" events.
         fn notify_multi(input: NotifyMulti) -> u8;
+
+        /// Report how a space's gossip looked as of its last local sync.
+        fn gossip_status(space: Arc<super::KitsuneSpace>) -> GossipStatus;
+
+        /// Pause or resume gossip in a space. While paused, the space
+        /// neither syncs between its local agents nor gossips with peers,
+        /// and ignores gossip sent to it. Resuming carries on from where
+        /// the last local sync before the pause left off.
+        fn set_gossip_paused(space: Arc<super::KitsuneSpace>, paused: bool) -> ();
+
+        /// Subscribe to a space's gossip events, as they happen.
+        /// A subscriber which falls behind misses the oldest events,
+        /// see [`tokio::sync::broadcast`].
+        fn subscribe_gossip_events(space: Arc<super::KitsuneSpace>) -> tokio::sync::broadcast::Receiver<GossipEvent>;
+
+        /// Set the storage arc of an agent joined to a space, returning the
+        /// arc it had before. The arc must be centered on the agent's location.
+        /// Gossip collects the agent's ops over its arc from the next local
+        /// sync on, and the agent's info is published again with it.
+        /// Agents whose arc was never set hold the full arc.
+        fn set_agent_arc(space: Arc<super::KitsuneSpace>, agent: Arc<super::KitsuneAgent>, arc: kitsune_p2p_types::dht_arc::DhtArc) -> kitsune_p2p_types::dht_arc::DhtArc;
     }
 }
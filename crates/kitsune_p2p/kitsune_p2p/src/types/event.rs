@@ -90,16 +90,21 @@ ghost_actor::ghost_chan! {
         fn notify(space: Arc<super::KitsuneSpace>, to_agent: Arc<super::KitsuneAgent>, from_agent: Arc<super::KitsuneAgent>, payload: Vec<u8>) -> ();
 
         /// We are receiving a dht op we may need to hold distributed via gossip.
+        /// Acknowledge with `true` once `to_agent` holds the op, or has it
+        /// queued to be held. A local sync only counts the op as synced to
+        /// `to_agent` when acknowledged, and sends it again next round if not.
         fn gossip(
             space: Arc<super::KitsuneSpace>,
             to_agent: Arc<super::KitsuneAgent>,
             from_agent: Arc<super::KitsuneAgent>,
             op_hash: Arc<super::KitsuneOpHash>,
             op_data: Vec<u8>,
-        ) -> ();
+        ) -> bool;
 
         /// Gather a list of op-hashes from our implementor that meet criteria.
-        fn fetch_op_hashes_for_constraints(input: FetchOpHashesForConstraintsEvt) -> Vec<Arc<super::KitsuneOpHash>>;
+        /// Each comes with when it was received in utc epoch milliseconds,
+        /// if the implementor knows, so gossip can order ops by it.
+        fn fetch_op_hashes_for_constraints(input: FetchOpHashesForConstraintsEvt) -> Vec<(Arc<super::KitsuneOpHash>, Option<i64>)>;
 
         /// Gather all op-hash data for a list of op-hashes from our implementor.
         fn fetch_op_hash_data(input: FetchOpHashDataEvt) -> Vec<(Arc<super::KitsuneOpHash>, Vec<u8>)>;
@@ -13,6 +13,10 @@ pub trait AsGossipModule: 'static + Send + Sync {
     ) -> KitsuneResult<()>;
     fn local_agent_join(&self, a: Arc<KitsuneAgent>);
     fn local_agent_leave(&self, a: Arc<KitsuneAgent>);
+    fn status(&self) -> actor::GossipStatus;
+    fn set_paused(&self, paused: bool);
+    fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<actor::GossipEvent>;
+    fn set_agent_arc(&self, a: Arc<KitsuneAgent>, arc: dht_arc::DhtArc);
 }
 
 pub struct GossipModule(pub Arc<dyn AsGossipModule>);
@@ -33,6 +37,22 @@ impl GossipModule {
     pub fn local_agent_leave(&self, a: Arc<KitsuneAgent>) {
         self.0.local_agent_leave(a);
     }
+
+    pub fn status(&self) -> actor::GossipStatus {
+        self.0.status()
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.0.set_paused(paused);
+    }
+
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<actor::GossipEvent> {
+        self.0.subscribe_events()
+    }
+
+    pub fn set_agent_arc(&self, a: Arc<KitsuneAgent>, arc: dht_arc::DhtArc) {
+        self.0.set_agent_arc(a, arc);
+    }
 }
 
 /// Represents an interchangeable gossip strategy module factory
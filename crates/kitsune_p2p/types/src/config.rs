@@ -97,6 +97,123 @@ pub mod tuning_params_struct {
         /// [Default: 5 minute]
         gossip_peer_on_error_next_gossip_delay_ms: u32 = 1000 * 60 * 5,
 
+        /// The local op-hash key space is partitioned by dht location
+        /// into this many equal subranges, with one bloom filter
+        /// built per subrange, so that large op sets don't require
+        /// a single multi-megabyte filter. All the chunks are always
+        /// sent and compared. Peers from before chunking can only
+        /// gossip with a node sending a single chunk. [Default: 1]
+        gossip_bloom_chunk_count: u32 = 1,
+
+        /// The largest a single bloom filter bitmap may be.
+        /// If a local bloom would need larger filters, it is split into
+        /// more chunks than `gossip_bloom_chunk_count`, and any chunk still
+        /// too large (because its subrange holds far more keys than the
+        /// others) is capped at this size at a higher false positive rate.
+        /// 0 means no limit. [Default: 16 MiB]
+        gossip_bloom_max_chunk_bytes: u32 = 1024 * 1024 * 16,
+
+        /// Local op sets with fewer keys than this are sent to peers
+        /// as the keys themselves instead of as bloom filters.
+        /// Exact keys have no false positives, but each costs tens of
+        /// bytes to send against about a byte in a bloom, so this only
+        /// pays off for very small op sets. Peers must be new enough to
+        /// decode exact keys. 0 always sends blooms. [Default: 0]
+        gossip_bloom_min_key_count: u32 = 0,
+
+        /// How long the filters a peer last sent are kept, so the peer
+        /// can say they are unchanged instead of sending them again.
+        /// A peer saying filters older than this are unchanged is asked
+        /// to send them in full. Each peer's filters can be as large as
+        /// `gossip_bloom_max_chunk_bytes` per chunk, this keeps them from
+        /// piling up for every peer ever gossiped with.
+        /// [Default: 5 minutes]
+        gossip_remote_bloom_ttl_ms: u32 = 1000 * 60 * 5,
+
+        /// The most ops a single local sync round will hand
+        /// between local agents, anything left over is synced
+        /// in following rounds. 0 means no limit. [Default: 0]
+        gossip_local_sync_max_ops_per_round: u32 = 0,
+
+        /// The most op bytes a single local sync round will hand
+        /// between local agents, anything left over is synced
+        /// in following rounds. 0 means no limit. [Default: 0]
+        gossip_local_sync_max_bytes_per_round: u32 = 0,
+
+        /// How many events a space's event channel buffers before
+        /// senders have to wait on the receiver. The gossip module sends
+        /// its `gossip` / `fetch` events through it, one `gossip` event per
+        /// op a local sync hands between agents, so a buffer that is small
+        /// next to `gossip_local_sync_max_ops_per_round` makes local syncs
+        /// wait on the receiver. The time a local sync spends waiting is
+        /// reported as `evt_channel_stall_ms` in the gossip status.
+        /// [Default: 10]
+        gossip_event_channel_capacity: u32 = 10,
+
+        /// How long the gossip module waits on the event handler to
+        /// answer a single event, including waiting for room in the event
+        /// channel to send it, before giving up on it with an
+        /// `EventTimedOut` error naming the event, so a stalled handler
+        /// shows up as a failed sync rather than gossip that silently
+        /// stops. 0 waits forever. [Default: 30 seconds]
+        gossip_event_timeout_ms: u32 = 1000 * 30,
+
+        /// How long the gossip module reuses the signed agent infos
+        /// it queried for a space before querying them again.
+        /// Gossiped agent infos or local agents joining / leaving
+        /// clear the cache early. 0 disables the cache.
+        /// [Default: 5 seconds]
+        gossip_agent_info_cache_ttl_ms: u32 = 1000 * 5,
+
+        /// The most local agents whose op hashes a local sync
+        /// fetches at the same time, and the most ops it fetches
+        /// ahead of the one it is sending. 0 is treated as 1.
+        /// [Default: 16]
+        gossip_local_sync_fetch_concurrency: u32 = 16,
+
+        /// For debugging network gossip in isolation: if set, local syncs
+        /// still collect the local agents' ops and build the bloom, but
+        /// don't hand ops between local agents. The bloom then covers every
+        /// op any local agent holds. Never set this outside of debugging,
+        /// local agents will only get ops from the network.
+        /// [Default: false]
+        gossip_skip_local_sync: bool = false,
+
+        /// How often, at most, the gossip module logs that a local sync
+        /// found nothing to hand between local agents, so monitoring can
+        /// tell converged gossip from gossip that isn't running.
+        /// 0 disables the heartbeat. [Default: 1 minute]
+        gossip_converged_heartbeat_interval_ms: u32 = 1000 * 60,
+
+        /// The order a gossip round hands out the ops a peer or
+        /// local agent is missing, so that agent infos aren't held up
+        /// behind bulk ops when a round is cut short.
+        /// "agent-info-first" sends agent infos first, then the
+        /// smallest ops. "newest-first" sends the most recently
+        /// received ops first, so a peer catching up gets recent
+        /// activity before old history, "oldest-first" the least
+        /// recently received. Both send ops whose receive time the
+        /// implementor didn't give last. "unordered" sends ops in
+        /// whatever order they are found in. [Default: agent-info-first]
+        gossip_op_order: String = "agent-info-first".to_string(),
+
+        /// If set, after each converged local sync in which every local
+        /// agent's op hashes were fetched, the op keys each agent held
+        /// are saved to a file per space in this directory. The first
+        /// local sync after a restart starts each agent in it from its
+        /// own keys, only fetching the op hashes integrated since.
+        /// A snapshot taken with a different bloom configuration is
+        /// ignored. Empty disables snapshots. [Default: ""]
+        gossip_bloom_snapshot_dir: String = "".to_string(),
+
+        /// Between full fetches, a local sync only fetches the op hashes
+        /// each local agent integrated since the previous local sync, and
+        /// keeps the ones fetched before. Every this many milliseconds it
+        /// fetches all of them again, which also drops ops an agent no
+        /// longer holds. 0 fetches all of them every time.
+        /// [Default: 10 minutes]
+        gossip_local_sync_full_fetch_interval_ms: u32 = 1000 * 60 * 10,
+
         /// Default agent count for remote notify. [Default: 5]
         default_notify_remote_agent_count: u32 = 5,
 
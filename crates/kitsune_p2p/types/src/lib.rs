@@ -170,6 +170,26 @@ pub enum KitsuneErrorKind {
     #[error("Closed")]
     Closed,
 
+    /// Fetching a single op returned the wrong number of results.
+    /// A `returned` count of 0 means the op is missing,
+    /// more than 1 means the store returned duplicates.
+    #[error("Fetching op {requested:?} returned {returned} results, expected 1")]
+    OpFetchCountMismatch {
+        /// The op hash that was requested.
+        requested: Arc<bin_types::KitsuneOpHash>,
+        /// How many ops were actually returned.
+        returned: usize,
+    },
+
+    /// The event handler took longer than `gossip_event_timeout_ms`
+    /// to answer a gossip event, or to make room in the event channel
+    /// for one.
+    #[error("Event handler timed out on a {event} event")]
+    EventTimedOut {
+        /// The event that stalled, named as its event sender method.
+        event: &'static str,
+    },
+
     /// Unspecified error.
     #[error(transparent)]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -263,6 +283,7 @@ pub use timeout::*;
 pub mod async_lazy;
 mod auto_stream_select;
 pub use auto_stream_select::*;
+pub mod bin_types;
 pub mod codec;
 pub mod config;
 pub mod dht_arc;